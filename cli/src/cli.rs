@@ -1,7 +1,11 @@
-use clap::{Parser, Subcommand};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::{Parser, Subcommand, ValueEnum};
 
 use console::style;
 use ghastoolkit::{CodeQLDatabases, GHASError, GitHub, Repository};
+use serde::Serialize;
 
 pub const VERSION_NUMBER: &str = env!("CARGO_PKG_VERSION");
 pub const AUTHOR: &str = env!("CARGO_PKG_AUTHORS");
@@ -46,10 +50,28 @@ pub struct Arguments {
     #[clap(long, env = "GITHUB_REF", help = "GitHub Reference")]
     pub github_reference: Option<String>,
 
+    #[clap(long, value_enum, help = "Output Format", default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    #[clap(short, long, help = "Output File Path (default: stdout)")]
+    pub output: Option<PathBuf>,
+
     #[clap(subcommand)]
     pub commands: Option<ArgumentCommands>,
 }
 
+/// Format of the results
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable output
+    #[default]
+    Text,
+    /// JSON output
+    Json,
+    /// SARIF output (code scanning)
+    Sarif,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum ArgumentCommands {
     Secretscanning {
@@ -70,6 +92,40 @@ pub enum ArgumentCommands {
     Codescanning {
         #[clap(short, long, help = "Audit Mode", default_value_t = false)]
         audit: bool,
+        /// Code Scanning Alert State (open, closed, dismissed, fixed)
+        #[clap(short, long, default_value_t = String::from("open"))]
+        state: String,
+        /// Code Scanning Alert Severity (critical, high, medium, low, error, warning, note)
+        #[clap(long)]
+        severity: Option<String>,
+        /// Code Scanning Tool Name
+        #[clap(short, long)]
+        tool_name: Option<String>,
+        /// Fail if there are any alerts at or above the security severity (critical, high,
+        /// medium, low) or SARIF level (error, warning, note)
+        #[clap(long)]
+        fail_on: Option<String>,
+    },
+
+    /// Upload a SARIF file to GitHub Code Scanning
+    Upload {
+        #[clap(long, help = "Path to the SARIF file")]
+        sarif: PathBuf,
+
+        #[clap(
+            long = "ref",
+            help = "Git Reference (default: GitHub Reference / Branch)"
+        )]
+        reference: Option<String>,
+
+        #[clap(long, env = "GITHUB_SHA", help = "Commit SHA")]
+        sha: Option<String>,
+    },
+
+    /// Supply Chain (Dependency Graph SBOM and licenses)
+    Supplychain {
+        #[clap(subcommand)]
+        command: SupplychainCommands,
     },
 
     Codeql {
@@ -99,6 +155,31 @@ pub enum ArgumentCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum SupplychainCommands {
+    /// Export the repository SBOM from the Dependency Graph
+    Export {
+        #[clap(long, value_enum, help = "SBOM Format", default_value_t = SbomFormat::Spdx)]
+        sbom_format: SbomFormat,
+    },
+    /// List dependency licenses and check them against a license policy
+    Licenses {
+        /// Denied licenses (SPDX identifiers, e.g. `--deny GPL-3.0`)
+        #[clap(long)]
+        deny: Vec<String>,
+    },
+}
+
+/// Format of the exported SBOM
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SbomFormat {
+    /// SPDX (JSON)
+    #[default]
+    Spdx,
+    /// CycloneDX (JSON)
+    Cyclonedx,
+}
+
 fn default_codeql_path() -> String {
     CodeQLDatabases::default_path()
         .to_str()
@@ -116,14 +197,53 @@ impl Arguments {
             .expect("Failed to build GitHub client")
     }
 
+    /// Get the Git reference from the arguments (reference, repository reference, or branch)
+    pub fn reference(&self, repository: &Repository) -> Option<String> {
+        self.github_reference
+            .clone()
+            .filter(|r| !r.is_empty())
+            .or_else(|| repository.reference().map(|r| r.to_string()))
+            .or_else(|| {
+                self.github_branch
+                    .as_ref()
+                    .map(|b| format!("refs/heads/{}", b))
+            })
+    }
+
     pub fn repository(&self) -> Result<Repository, GHASError> {
-        Repository::init()
-            .repo(self.github_repository.clone().unwrap_or_default().as_str())
-            .reference(self.github_reference.clone().unwrap_or_default().as_str())
-            .build()
+        let mut repository =
+            Repository::parse(self.github_repository.as_deref().unwrap_or_default())?;
+        // An unset `GITHUB_REF` can be empty, only use a real reference
+        if let Some(reference) = self.github_reference.as_deref().filter(|r| !r.is_empty()) {
+            repository.set_reference(reference);
+        }
+        Ok(repository)
     }
 }
 
+/// Write the results to the `--output` file (or stdout) using the selected format.
+///
+/// Returns `false` for the text format, the caller then prints the results itself.
+pub fn write_output<T: Serialize>(
+    format: OutputFormat,
+    output: Option<&Path>,
+    value: &T,
+) -> Result<bool> {
+    if format == OutputFormat::Text {
+        return Ok(false);
+    }
+
+    let content = serde_json::to_string_pretty(value)?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, content)?;
+            println!("Results written to :: {}", path.display());
+        }
+        None => println!("{}", content),
+    }
+    Ok(true)
+}
+
 pub fn init() -> Arguments {
     // Load .env file if it exists
     dotenvy::dotenv().ok();
@@ -151,3 +271,76 @@ pub fn init() -> Arguments {
 
     arguments
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::Arguments;
+
+    #[test]
+    fn test_reference_from_branch() {
+        let mut arguments = Arguments::try_parse_from([
+            "ghastoolkit",
+            "--github-repository",
+            "geekmasher/ghastoolkit-rs",
+            "--github-branch",
+            "main",
+        ])
+        .unwrap();
+        // `GITHUB_REF` might be set in the environment running the tests
+        arguments.github_reference = None;
+
+        let repository = arguments.repository().unwrap();
+        assert_eq!(repository.reference(), None);
+        assert_eq!(
+            arguments.reference(&repository),
+            Some(String::from("refs/heads/main"))
+        );
+
+        // An empty reference is ignored
+        arguments.github_reference = Some(String::new());
+        let repository = arguments.repository().unwrap();
+        assert_eq!(repository.reference(), None);
+        assert_eq!(
+            arguments.reference(&repository),
+            Some(String::from("refs/heads/main"))
+        );
+
+        arguments.github_branch = None;
+        assert_eq!(arguments.reference(&repository), None);
+
+        arguments.github_reference = Some(String::from("refs/pull/1/merge"));
+        let repository = arguments.repository().unwrap();
+        assert_eq!(repository.reference(), Some("refs/pull/1/merge"));
+        assert_eq!(
+            arguments.reference(&repository),
+            Some(String::from("refs/pull/1/merge"))
+        );
+    }
+
+    #[test]
+    fn test_repository_reference() {
+        let mut arguments = Arguments::try_parse_from([
+            "ghastoolkit",
+            "--github-repository",
+            "geekmasher/ghastoolkit-rs/src@develop",
+        ])
+        .unwrap();
+        arguments.github_reference = None;
+
+        // The reference and path of the repository are kept
+        let repository = arguments.repository().unwrap();
+        assert_eq!(repository.name(), "ghastoolkit-rs");
+        assert_eq!(repository.branch(), Some("develop"));
+        assert_eq!(repository.reference(), Some("refs/heads/develop"));
+        assert_eq!(repository.path(), &std::path::PathBuf::from("src"));
+
+        // `--ref` overrides the repository reference
+        arguments.github_reference = Some(String::from("refs/heads/main"));
+        let repository = arguments.repository().unwrap();
+        assert_eq!(repository.branch(), Some("main"));
+        assert_eq!(repository.reference(), Some("refs/heads/main"));
+        assert_eq!(repository.path(), &std::path::PathBuf::from("src"));
+    }
+}
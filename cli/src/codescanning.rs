@@ -1,10 +1,70 @@
+use std::path::Path;
+
 use anyhow::Result;
-use ghastoolkit::{GitHub, Repository};
+use ghastoolkit::{
+    codescanning::models::CodeScanningAlert, utils::sarif::Sarif, GitHub, Repository,
+};
+use log::info;
+
+use crate::cli::{write_output, ArgumentCommands, OutputFormat};
+
+pub async fn code_scanning(
+    github: &GitHub,
+    repository: &Repository,
+    args: &ArgumentCommands,
+    format: OutputFormat,
+    output: Option<&Path>,
+) -> Result<()> {
+    let ArgumentCommands::Codescanning {
+        audit,
+        state,
+        severity,
+        tool_name,
+        fail_on,
+    } = args
+    else {
+        return Ok(());
+    };
+
+    let fail_on = match fail_on {
+        Some(level) => Some(
+            FailOn::parse(level)
+                .ok_or_else(|| anyhow::anyhow!("Unknown severity for `--fail-on`: {}", level))?,
+        ),
+        None => None,
+    };
+
+    let alerts = if *audit || format != OutputFormat::Text || fail_on.is_some() {
+        let handler = github.code_scanning(repository);
+        let mut list = handler.list().state(state.as_str());
+        if let Some(severity) = severity {
+            list = list.severity(severity);
+        }
+        if let Some(tool_name) = tool_name {
+            list = list.tool_name(tool_name);
+        }
+        list.all().await?
+    } else {
+        Vec::new()
+    };
+
+    let written = match format {
+        OutputFormat::Sarif => write_output(format, output, &alerts_to_sarif(&alerts))?,
+        _ => write_output(format, output, &alerts)?,
+    };
+    if written {
+        if output.is_some() {
+            println!("Code Scanning Alerts :: {}", alerts.len());
+        }
+    } else {
+        println!("\n ----- Code Scanning -----");
 
-pub async fn code_scanning(github: &GitHub, repository: &Repository, audit: bool) -> Result<()> {
-    println!("\n ----- Code Scanning -----");
+        if !github.code_scanning(repository).is_enabled().await {
+            return Err(anyhow::anyhow!(
+                "Code Scanning is not enabled for this repository"
+            ));
+        }
 
-    if github.code_scanning(repository).is_enabled().await {
         let analyses = github
             .code_scanning(repository)
             .analyses()
@@ -16,30 +76,147 @@ pub async fn code_scanning(github: &GitHub, repository: &Repository, audit: bool
         for analysis in analyses {
             println!(
                 "Code Scanning Analysis :: {} ({})",
-                analysis.tool.name, analysis.tool.version
+                analysis.tool.name,
+                analysis.tool.version.as_deref().unwrap_or("unknown")
             );
         }
 
-        if audit {
-            let alerts = github
-                .code_scanning(repository)
-                .list()
-                .state("open")
-                .send()
-                .await?;
-
-            for alert in alerts {
+        if *audit {
+            for alert in &alerts {
                 println!(
                     "Code Scanning Alert :: {} - {} - {}",
-                    alert.tool.name, alert.rule.name, alert.rule.severity
+                    alert.tool.name,
+                    alert.rule.name,
+                    alert_severity(alert)
                 );
             }
         }
-    } else {
-        return Err(anyhow::anyhow!(
-            "Code Scanning is not enabled for this repository"
-        ));
+    }
+
+    if let Some(threshold) = fail_on {
+        let failing = alerts
+            .iter()
+            .filter(|a| {
+                threshold.exceeded_by(a.rule.security_severity_level.as_deref(), &a.rule.severity)
+            })
+            .count();
+        if failing > 0 {
+            return Err(anyhow::anyhow!(
+                "Found {} code scanning alert(s) at or above the `--fail-on` severity",
+                failing
+            ));
+        }
     }
 
     Ok(())
 }
+
+pub async fn upload_sarif(
+    github: &GitHub,
+    repository: &Repository,
+    sarif: &Path,
+    reference: &str,
+    sha: &str,
+) -> Result<()> {
+    info!("Uploading SARIF :: {}", sarif.display());
+
+    // Upload the file as-is so nothing the SARIF model doesn't know about is lost
+    let upload = github
+        .code_scanning(repository)
+        .upload_sarif_file(sarif, sha, reference)
+        .await?;
+
+    println!("SARIF ID :: {}", upload.id);
+    Ok(())
+}
+
+/// Get the severity of an alert (security severity if present)
+fn alert_severity(alert: &CodeScanningAlert) -> &str {
+    alert
+        .rule
+        .security_severity_level
+        .as_deref()
+        .unwrap_or(alert.rule.severity.as_str())
+}
+
+/// `--fail-on` threshold. Security severities (`critical`, `high`, `medium`, `low`) and
+/// SARIF levels (`error`, `warning`, `note`, `none`) are different scales, so alerts are
+/// only compared on the scale of the threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailOn {
+    SecuritySeverity(u8),
+    Level(u8),
+}
+
+impl FailOn {
+    fn parse(value: &str) -> Option<Self> {
+        security_severity_rank(value)
+            .map(FailOn::SecuritySeverity)
+            .or_else(|| level_rank(value).map(FailOn::Level))
+    }
+
+    /// Check if an alert (security severity and rule SARIF level) is at or above the threshold
+    fn exceeded_by(&self, security_severity: Option<&str>, level: &str) -> bool {
+        match self {
+            FailOn::SecuritySeverity(threshold) => security_severity
+                .and_then(security_severity_rank)
+                .is_some_and(|rank| rank >= *threshold),
+            FailOn::Level(threshold) => level_rank(level).is_some_and(|rank| rank >= *threshold),
+        }
+    }
+}
+
+/// Rank a security severity
+fn security_severity_rank(severity: &str) -> Option<u8> {
+    match severity.to_lowercase().as_str() {
+        "critical" => Some(4),
+        "high" => Some(3),
+        "medium" => Some(2),
+        "low" => Some(1),
+        _ => None,
+    }
+}
+
+/// Rank a SARIF level
+fn level_rank(level: &str) -> Option<u8> {
+    match level.to_lowercase().as_str() {
+        "error" => Some(3),
+        "warning" => Some(2),
+        "note" => Some(1),
+        "none" => Some(0),
+        _ => None,
+    }
+}
+
+/// Create a SARIF file (single run) from code scanning alerts
+fn alerts_to_sarif(alerts: &[CodeScanningAlert]) -> Sarif {
+    let tool = alerts
+        .first()
+        .map(|a| a.tool.name.as_str())
+        .unwrap_or("CodeQL");
+    Sarif::from_alerts(alerts, tool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FailOn;
+
+    #[test]
+    fn test_fail_on() {
+        assert_eq!(FailOn::parse("High"), Some(FailOn::SecuritySeverity(3)));
+        assert_eq!(FailOn::parse("error"), Some(FailOn::Level(3)));
+        assert_eq!(FailOn::parse("severe"), None);
+
+        // Security severities only compare to the alert security severity
+        let high = FailOn::parse("high").unwrap();
+        assert!(high.exceeded_by(Some("critical"), "warning"));
+        assert!(!high.exceeded_by(Some("medium"), "error"));
+        assert!(!high.exceeded_by(None, "error"));
+
+        // SARIF levels only compare to the rule level
+        let error = FailOn::parse("error").unwrap();
+        assert!(error.exceeded_by(None, "error"));
+        assert!(!error.exceeded_by(Some("critical"), "warning"));
+        assert!(FailOn::parse("note").unwrap().exceeded_by(None, "warning"));
+    }
+}
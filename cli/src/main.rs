@@ -5,15 +5,19 @@ use ghastoolkit::{
 };
 use log::{debug, info};
 use secretscanning::secret_scanning;
+use serde_json::json;
 use std::env::temp_dir;
 
 mod cli;
 mod codescanning;
 mod prompts;
 mod secretscanning;
+mod supplychain;
 
+use crate::cli::write_output;
 use crate::prompts::{prompt_select, prompt_text};
-use codescanning::code_scanning;
+use codescanning::{code_scanning, upload_sarif};
+use supplychain::supply_chain;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -22,12 +26,7 @@ async fn main() -> Result<()> {
     let github = arguments.github();
     let mut repository: Repository = match arguments.repository() {
         Ok(repo) => repo,
-        Err(_) => Repository::try_from(
-            prompt_text("GitHub Repository:")
-                .expect("Failed to get repository")
-                .as_str(),
-        )
-        .expect("Failed to parse repository"),
+        Err(_) => Repository::try_from(prompt_text("GitHub Repository:")?)?,
     };
 
     debug!("GitHub :: {}", github);
@@ -35,11 +34,51 @@ async fn main() -> Result<()> {
 
     match arguments.commands {
         Some(cli::ArgumentCommands::Secretscanning { .. }) => {
-            let args = arguments.commands.expect("Args issue");
-            secret_scanning(&github, &repository, &args).await
+            let args = arguments.commands.as_ref().expect("Args issue");
+            secret_scanning(
+                &github,
+                &repository,
+                args,
+                arguments.format,
+                arguments.output.as_deref(),
+            )
+            .await
         }
-        Some(cli::ArgumentCommands::Codescanning { audit }) => {
-            code_scanning(&github, &repository, audit).await
+        Some(cli::ArgumentCommands::Codescanning { .. }) => {
+            let args = arguments.commands.as_ref().expect("Args issue");
+            code_scanning(
+                &github,
+                &repository,
+                args,
+                arguments.format,
+                arguments.output.as_deref(),
+            )
+            .await
+        }
+        Some(cli::ArgumentCommands::Upload {
+            ref sarif,
+            ref reference,
+            ref sha,
+        }) => {
+            let reference = reference
+                .clone()
+                .or_else(|| arguments.reference(&repository))
+                .ok_or_else(|| anyhow::anyhow!("No Git reference provided (--ref)"))?;
+            let sha = sha
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("No commit SHA provided (--sha)"))?;
+
+            upload_sarif(&github, &repository, sarif, &reference, &sha).await
+        }
+        Some(cli::ArgumentCommands::Supplychain { ref command }) => {
+            supply_chain(
+                &github,
+                &repository,
+                command,
+                arguments.format,
+                arguments.output.as_deref(),
+            )
+            .await
         }
         Some(cli::ArgumentCommands::Codeql {
             codeql_path,
@@ -63,6 +102,21 @@ async fn main() -> Result<()> {
             if list {
                 let databases = CodeQLDatabases::from(codeql_databases);
                 info!("Databases :: {}", databases.len());
+
+                let results: Vec<_> = databases
+                    .iter()
+                    .map(|database| {
+                        json!({
+                            "name": database.name(),
+                            "language": database.language(),
+                            "path": database.path(),
+                        })
+                    })
+                    .collect();
+                if write_output(arguments.format, arguments.output.as_deref(), &results)? {
+                    return Ok(());
+                }
+
                 for database in databases {
                     info!("{}", database);
                 }
@@ -70,6 +124,10 @@ async fn main() -> Result<()> {
             } else if languages {
                 let languages = codeql.get_languages().await?;
                 info!("CodeQL Languages Loaded :: {}", languages.len());
+                let names: Vec<&str> = languages.iter().map(|l| l.language()).collect();
+                if write_output(arguments.format, arguments.output.as_deref(), &names)? {
+                    return Ok(());
+                }
 
                 for language in languages {
                     info!("> {}", language);
@@ -97,7 +155,7 @@ async fn main() -> Result<()> {
 
                 let mut database = CodeQLDatabase::init()
                     .source(tempdir.display().to_string())
-                    .language(language.to_string())
+                    .language(language.language())
                     .repository(&repository)
                     .build()?;
 
@@ -106,23 +164,27 @@ async fn main() -> Result<()> {
                 }
 
                 info!("Database :: {}", database);
-                info!("Creating database :: {}", database.path().display());
-
-                codeql.database(&database).overwrite().create().await?;
-
-                // Reload the database after creation
-                database.reload()?;
+                info!(
+                    "Creating and analyzing database :: {}",
+                    database.path().display()
+                );
 
                 let queries = CodeQLQueries::language_default(language.language());
 
-                info!("Analyzing database :: {}", database);
                 let results = codeql
-                    .database(&database)
-                    .queries(queries)
-                    .analyze()
+                    .scan(&mut database, |handler| {
+                        handler.overwrite().queries(queries)
+                    })
                     .await?;
 
                 info!("Results :: {:?}", results.get_results().len());
+                if write_output(
+                    arguments.format,
+                    arguments.output.as_deref(),
+                    &results.get_results(),
+                )? {
+                    return Ok(());
+                }
                 for result in results.get_results() {
                     info!("{}", result);
                 }
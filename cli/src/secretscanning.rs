@@ -1,12 +1,19 @@
+use std::path::Path;
+
 use anyhow::Result;
-use ghastoolkit::{secretscanning::secretalerts::SecretScanningSort, GitHub, Repository};
+use ghastoolkit::{
+    secretscanning::secretalerts::{SecretScanningAlertValidity, SecretScanningSort},
+    GitHub, Repository,
+};
 
-use crate::cli::ArgumentCommands;
+use crate::cli::{write_output, ArgumentCommands, OutputFormat};
 
 pub async fn secret_scanning(
     github: &GitHub,
     repository: &Repository,
     args: &ArgumentCommands,
+    format: OutputFormat,
+    output: Option<&Path>,
 ) -> Result<()> {
     if let ArgumentCommands::Secretscanning {
         state,
@@ -15,35 +22,35 @@ pub async fn secret_scanning(
         links,
     } = args
     {
-        let octocrab = github.octocrab();
-        println!("\n ----- Secret Scanning -----\n");
-
-        let mut handle = github
+        let alerts = github
             .secret_scanning(repository)
             .list()
             .sort(SecretScanningSort::Created)
             .state(state.clone().unwrap_or_default())
             .secret_type(r#type.clone().unwrap_or_default())
             .validity(validity.clone().unwrap_or_default())
-            .send()
+            .all()
             .await?;
 
-        let mut alerts = handle.take_items();
-
-        while let Ok(Some(mut page)) = octocrab.get_page(&handle.next).await {
-            alerts.extend(page.take_items());
-            handle = page;
+        if write_output(format, output, &alerts)? {
+            if output.is_some() {
+                println!("Secret Scanning Alerts :: {}", alerts.len());
+            }
+            return Ok(());
         }
 
+        println!("\n ----- Secret Scanning -----\n");
+
         for alert in &alerts {
             println!(
-                "> {} :: {} ({}, {:?})",
+                "> {} :: {} ({}, {})",
                 alert.number,
                 alert.secret_type_display_name,
                 alert.state,
-                alert.validity.as_ref().unwrap_or_else(|| {
-                    &ghastoolkit::secretscanning::secretalerts::SecretScanningAlertValidity::Unknown
-                })
+                alert
+                    .validity
+                    .as_ref()
+                    .unwrap_or(&SecretScanningAlertValidity::Unknown)
             );
             if *links {
                 println!("  > {}", alert.html_url);
@@ -0,0 +1,177 @@
+use std::path::Path;
+
+use anyhow::Result;
+use ghastoolkit::{
+    supplychain::{
+        sbom::{SpdxDocument, SpdxPackage},
+        spdx::Expr,
+        License,
+    },
+    Dependency, GitHub, Repository,
+};
+use serde_json::{json, Value};
+
+use crate::cli::{write_output, OutputFormat, SbomFormat, SupplychainCommands};
+
+pub async fn supply_chain(
+    github: &GitHub,
+    repository: &Repository,
+    command: &SupplychainCommands,
+    format: OutputFormat,
+    output: Option<&Path>,
+) -> Result<()> {
+    let sbom = github.dependency_graph_sbom(repository).await?.sbom;
+
+    match command {
+        SupplychainCommands::Export { sbom_format } => {
+            // The SBOM is always exported as JSON
+            let format = OutputFormat::Json;
+            match sbom_format {
+                SbomFormat::Spdx => write_output(format, output, &sbom)?,
+                SbomFormat::Cyclonedx => write_output(format, output, &to_cyclonedx(&sbom))?,
+            };
+        }
+        SupplychainCommands::Licenses { deny } => {
+            let dependencies = sbom.dependencies();
+            let denied: Vec<License> = deny.iter().map(|l| License::from(l.as_str())).collect();
+            let violations = dependencies.license_violations(&denied);
+
+            let to_json = |d: &Dependency| json!({ "purl": d.purl(), "licenses": d.licenses });
+            let results = json!({
+                "dependencies": dependencies.iter().map(to_json).collect::<Vec<_>>(),
+                "violations": violations.iter().map(|d| to_json(d)).collect::<Vec<_>>(),
+            });
+            if write_output(format, output, &results)? {
+                if output.is_some() {
+                    println!("Dependencies :: {}", dependencies.len());
+                }
+            } else {
+                println!("\n ----- Supply Chain Licenses -----\n");
+                for dependency in &dependencies {
+                    println!("> {} :: {}", dependency.purl(), dependency.licenses);
+                }
+                println!("\n Total Dependencies :: {}", dependencies.len());
+
+                if !violations.is_empty() {
+                    println!("\n ----- License Violations -----\n");
+                    for dependency in &violations {
+                        println!("> {} :: {}", dependency.purl(), dependency.licenses);
+                    }
+                }
+            }
+
+            if !violations.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Found {} dependencies using denied licenses",
+                    violations.len()
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert the SPDX SBOM into a (minimal) CycloneDX document
+fn to_cyclonedx(sbom: &SpdxDocument) -> Value {
+    let components: Vec<Value> = sbom
+        .packages
+        .iter()
+        .filter_map(|package| {
+            let purl = package.purl()?;
+            let licenses = cyclonedx_licenses(package);
+            Some(json!({
+                "type": "library",
+                "bom-ref": purl,
+                "name": package.name,
+                "version": package.version_info.clone().unwrap_or_default(),
+                "purl": purl,
+                "licenses": licenses,
+            }))
+        })
+        .collect();
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "component": { "type": "application", "name": sbom.name },
+        },
+        "components": components,
+    })
+}
+
+/// Get the CycloneDX licenses of a package. Compound SPDX expressions are kept as an
+/// `expression`, licenses without an SPDX identifier use the license `name`.
+fn cyclonedx_licenses(package: &SpdxPackage) -> Vec<Value> {
+    let Some(expression) = package.license_expression() else {
+        return Vec::new();
+    };
+    let licenses = package.licenses();
+
+    match licenses.expression() {
+        Some(Expr::License(_)) => {}
+        Some(_) => return vec![json!({ "expression": expression })],
+        None if licenses.len() == 1 => {}
+        None => {
+            return licenses
+                .iter()
+                .map(|l| cyclonedx_license(l, &l.to_string()))
+                .collect()
+        }
+    }
+    licenses
+        .iter()
+        .map(|l| cyclonedx_license(l, expression))
+        .collect()
+}
+
+/// Get a CycloneDX license, using the `id` for SPDX licenses
+fn cyclonedx_license(license: &License, name: &str) -> Value {
+    if license.is_spdx() {
+        json!({ "license": { "id": license.to_spdx_id() } })
+    } else {
+        json!({ "license": { "name": name } })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ghastoolkit::supplychain::sbom::DependencyGraphSbom;
+
+    use super::to_cyclonedx;
+
+    #[test]
+    fn test_to_cyclonedx() {
+        let sbom: DependencyGraphSbom = serde_json::from_str(
+            r#"{"sbom": {"spdxVersion": "SPDX-2.3", "name": "test", "packages": [
+                {"name": "cargo:serde", "versionInfo": "1.0.0", "licenseConcluded": "MIT",
+                 "externalRefs": [{"referenceCategory": "PACKAGE-MANAGER", "referenceType": "purl", "referenceLocator": "pkg:cargo/serde@1.0.0"}]},
+                {"name": "cargo:either", "versionInfo": "1.0.0", "licenseConcluded": "MIT OR Apache-2.0",
+                 "externalRefs": [{"referenceCategory": "PACKAGE-MANAGER", "referenceType": "purl", "referenceLocator": "pkg:cargo/either@1.0.0"}]},
+                {"name": "npm:internal", "versionInfo": "1.0.0", "licenseDeclared": "Proprietary License",
+                 "externalRefs": [{"referenceCategory": "PACKAGE-MANAGER", "referenceType": "purl", "referenceLocator": "pkg:npm/internal@1.0.0"}]},
+                {"name": "test"}
+            ]}}"#,
+        )
+        .unwrap();
+
+        let bom = to_cyclonedx(&sbom.sbom);
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        let components = bom["components"].as_array().unwrap();
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[0]["purl"], "pkg:cargo/serde@1.0.0");
+        assert_eq!(components[0]["licenses"][0]["license"]["id"], "MIT");
+        // Compound licenses are kept as an SPDX expression
+        assert_eq!(
+            components[1]["licenses"],
+            serde_json::json!([{ "expression": "MIT OR Apache-2.0" }])
+        );
+        // Licenses without an SPDX identifier use the name
+        assert_eq!(
+            components[2]["licenses"],
+            serde_json::json!([{ "license": { "name": "Proprietary License" } }])
+        );
+    }
+}
@@ -1,13 +1,20 @@
 use std::{
+    collections::HashMap,
     fmt::Display,
     path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
 };
 
 use log::debug;
 
 use crate::{
-    codeql::{database::handler::CodeQLDatabaseHandler, CodeQLLanguage},
-    CodeQLDatabase, GHASError,
+    codeql::{
+        database::{handler::CodeQLDatabaseHandler, queries::CodeQLQueries, CodeQLDatabaseBuilder},
+        languages::CodeQLLanguages,
+        CodeQLExtractor, CodeQLLanguage,
+    },
+    utils::{sarif::Sarif, spans::SpanTimer},
+    CodeQLDatabase, CodeQLPacks, GHASError, GitHub, Repository,
 };
 
 mod models;
@@ -29,27 +36,40 @@ pub struct CodeQL {
     search_path: Vec<PathBuf>,
     /// Additional packs to use
     additional_packs: Vec<String>,
+    /// Additional environment variables passed to the CodeQL CLI
+    env: HashMap<String, String>,
+    /// Offline mode (no registry / network access)
+    offline: bool,
+    /// Container registries and their tokens (host, token)
+    registries: Vec<(String, String)>,
+    /// Cached output of `codeql resolve languages` (per instance)
+    languages: OnceLock<ResolvedLanguages>,
 }
 
 impl CodeQL {
-    /// Create a new CodeQL instance
-    #[cfg(not(feature = "async"))]
-    pub fn new() -> Self {
-        CodeQL::default()
-    }
+    /// Default number of repositories scanned at the same time
+    /// (see [`CodeQL::scan_repositories`])
+    pub const DEFAULT_SCAN_CONCURRENCY: usize = 2;
 
-    /// Create a new CodeQL instance
-    #[cfg(feature = "async")]
+    /// Create a new CodeQL instance, finding the CodeQL CLI and its version.
+    ///
+    /// Use [`CodeQL::default()`] to create an instance without running the CLI.
     pub async fn new() -> Self {
         let path = CodeQL::find_codeql().await.unwrap_or_default();
+        let mut search_path = Vec::new();
+        CodeQL::add_bundle_search_path(&path, &mut search_path);
 
         CodeQL {
             version: CodeQL::get_version(&path).await.ok(),
             path,
             threads: 0,
             ram: None,
-            search_path: Vec::new(),
+            search_path,
             additional_packs: Vec::new(),
+            env: HashMap::new(),
+            offline: false,
+            registries: Vec::new(),
+            languages: OnceLock::new(),
         }
     }
 
@@ -58,20 +78,89 @@ impl CodeQL {
         &self.path
     }
 
+    /// Get the effective search paths of the CodeQL CLI.
+    ///
+    /// This is the configured search paths plus the CodeQL bundle (`CODEQL_DIST`)
+    /// when the CLI is from the bundle. These are passed as `--search-path` to the
+    /// commands that resolve extractors or packs.
+    pub fn effective_search_paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.search_path.clone();
+        CodeQL::add_bundle_search_path(&self.path, &mut paths);
+        paths
+    }
+
+    /// Check if CodeQL is running in offline mode
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Get the additional environment variables passed to the CodeQL CLI
+    pub fn env(&self) -> &HashMap<String, String> {
+        &self.env
+    }
+
+    /// Get the value for `CODEQL_REGISTRIES_AUTH` (if any registries are set).
+    ///
+    /// The format is a comma-separated list of `<registry_url>=<token>` pairs.
+    pub(crate) fn registries_auth(&self) -> Option<String> {
+        if self.registries.is_empty() {
+            return None;
+        }
+        Some(
+            self.registries
+                .iter()
+                .map(|(host, token)| format!("{}={}", host, token))
+                .collect::<Vec<String>>()
+                .join(","),
+        )
+    }
+
+    /// Add the `--additional-packs` argument (if any additional packs are set)
+    pub(crate) fn add_additional_packs(&self, args: &mut Vec<String>) {
+        if self.additional_packs.is_empty() {
+            return;
+        }
+        // Paths are separated by `:` (or `;` on Windows)
+        match std::env::join_paths(&self.additional_packs) {
+            Ok(paths) => args.push(format!("--additional-packs={}", paths.to_string_lossy())),
+            Err(e) => debug!("Invalid additional packs path :: {}", e),
+        }
+    }
+
+    /// Add the `--search-path` argument (if any search paths are set)
+    pub(crate) fn add_search_path(&self, args: &mut Vec<String>) {
+        let search_path = self.effective_search_paths();
+        if search_path.is_empty() {
+            return;
+        }
+        match std::env::join_paths(&search_path) {
+            Ok(paths) => args.push(format!("--search-path={}", paths.to_string_lossy())),
+            Err(e) => debug!("Invalid search path :: {}", e),
+        }
+    }
+
     /// Initialize a new CodeQL Builder instance
     pub fn init() -> CodeQLBuilder {
         CodeQLBuilder::default()
     }
 
     /// Find CodeQL CLI on the system (asynchronous)
+    ///
+    /// The search order is:
+    ///
+    /// 1. `PATH`
+    /// 2. `CODEQL_PATH` (directory) or `CODEQL_BINARY` (binary)
+    /// 3. `CODEQL_DIST` (CodeQL bundle, `<dist>/codeql`)
+    /// 4. GitHub Actions tool cache (`toolcache` feature)
+    /// 5. Common install locations: `~/.codeql/codeql`, `~/codeql`, `/opt/codeql`,
+    ///    `/usr/local/bin/codeql`, and the VS Code CodeQL extension's distribution
     pub async fn find_codeql() -> Option<PathBuf> {
         if let Some(p) = CodeQL::find_codeql_path() {
             return Some(p);
         }
         // Root CodeQL Paths
         if let Some(e) = std::env::var_os("CODEQL_PATH") {
-            let p = PathBuf::from(e).join("codeql");
-            if p.exists() && p.is_file() {
+            if let Some(p) = CodeQL::find_codeql_in(&codeql_binaries(Path::new(&e))) {
                 return Some(p);
             }
         } else if let Some(e) = std::env::var_os("CODEQL_BINARY") {
@@ -80,6 +169,12 @@ impl CodeQL {
                 return Some(p);
             }
         }
+        // CodeQL bundle
+        if let Some(e) = std::env::var_os("CODEQL_DIST") {
+            if let Some(p) = CodeQL::find_codeql_in(&codeql_binaries(Path::new(&e))) {
+                return Some(p);
+            }
+        }
 
         #[cfg(feature = "toolcache")]
         {
@@ -88,21 +183,98 @@ impl CodeQL {
             }
         }
 
-        None
+        let home = std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map(PathBuf::from);
+        CodeQL::find_codeql_in(&CodeQL::common_locations(home.as_deref()))
     }
 
-    fn find_codeql_path() -> Option<PathBuf> {
-        debug!("Looking for CodeQL in PATH");
-        // Check if CodeQL is in the PATH
-        if let Ok(paths) = std::env::var("PATH") {
-            for path in paths.split(':') {
-                let p = Path::new(path).join("codeql");
-                if p.exists() && p.is_file() {
-                    return Some(p);
+    /// Add the CodeQL bundle (`CODEQL_DIST`) to the search path if the CLI is from
+    /// the bundle, so the bundled extractors and packs are available
+    fn add_bundle_search_path(path: &Path, search_path: &mut Vec<PathBuf>) {
+        let dist = std::env::var_os("CODEQL_DIST").map(PathBuf::from);
+        if let Some(dist) = CodeQL::bundle_dist(path, dist.as_deref()) {
+            if !search_path.contains(&dist) {
+                debug!(
+                    "Adding CodeQL bundle to the search path :: {}",
+                    dist.display()
+                );
+                search_path.push(dist);
+            }
+        }
+    }
+
+    /// Get the bundle directory if the CLI is in the bundle (`<dist>/codeql`)
+    fn bundle_dist(path: &Path, dist: Option<&Path>) -> Option<PathBuf> {
+        let dist = dist?;
+        if path.parent()? == dist && dist.is_dir() {
+            Some(dist.to_path_buf())
+        } else {
+            None
+        }
+    }
+
+    /// Common CodeQL install locations (in search order)
+    fn common_locations(home: Option<&Path>) -> Vec<PathBuf> {
+        let mut locations = Vec::new();
+        if let Some(home) = home {
+            locations.push(home.join(".codeql").join("codeql").join("codeql"));
+            locations.push(home.join("codeql").join("codeql"));
+        }
+        locations.push(PathBuf::from("/opt/codeql/codeql"));
+        locations.push(PathBuf::from("/usr/local/bin/codeql"));
+
+        // VS Code CodeQL extension managed distributions (newest first)
+        if let Some(home) = home {
+            let mut storages = vec![
+                home.join(".config/Code/User/globalStorage"),
+                home.join("Library/Application Support/Code/User/globalStorage"),
+            ];
+            if let Some(appdata) = std::env::var_os("APPDATA") {
+                storages.push(PathBuf::from(appdata).join("Code/User/globalStorage"));
+            }
+            for storage in storages {
+                let pattern = storage
+                    .join("github.vscode-codeql")
+                    .join("distribution*")
+                    .join("codeql")
+                    .join(if cfg!(windows) {
+                        "codeql.exe"
+                    } else {
+                        "codeql"
+                    });
+                if let Ok(paths) = glob::glob(&pattern.to_string_lossy()) {
+                    let mut paths: Vec<PathBuf> = paths.filter_map(|p| p.ok()).collect();
+                    paths.sort_by_key(|p| std::cmp::Reverse(distribution_number(p)));
+                    locations.extend(paths);
                 }
             }
         }
-        None
+        locations
+    }
+
+    /// Find the first candidate which is an executable file
+    fn find_codeql_in(candidates: &[PathBuf]) -> Option<PathBuf> {
+        candidates
+            .iter()
+            .find(|p| {
+                debug!("Looking for CodeQL in :: {}", p.display());
+                is_executable(p)
+            })
+            .cloned()
+    }
+
+    fn find_codeql_path() -> Option<PathBuf> {
+        debug!("Looking for CodeQL in PATH");
+        CodeQL::find_codeql_in_paths(&std::env::var_os("PATH")?)
+    }
+
+    /// Find CodeQL in a `PATH` style list of directories (using the platform separator)
+    fn find_codeql_in_paths(paths: &std::ffi::OsStr) -> Option<PathBuf> {
+        let candidates: Vec<PathBuf> = std::env::split_paths(paths)
+            .flat_map(|dir| codeql_binaries(&dir))
+            .collect();
+        CodeQL::find_codeql_in(&candidates)
     }
 
     #[cfg(feature = "toolcache")]
@@ -115,11 +287,52 @@ impl CodeQL {
     }
 
     /// Run a CodeQL command asynchronously
+    ///
+    /// The additional environment variables are passed to the command. In offline mode
+    /// registry credentials are removed from the environment and commands which need
+    /// to download packs from a registry are refused.
     pub async fn run(&self, args: Vec<&str>) -> Result<String, GHASError> {
+        self.run_with_env(args, &[]).await
+    }
+
+    /// Run a CodeQL command with extra environment variables for this command only
+    /// (these take precedence over the instance's environment variables)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "codeql",
+            skip_all,
+            fields(args = ?args, duration_ms = tracing::field::Empty)
+        )
+    )]
+    pub(crate) async fn run_with_env(
+        &self,
+        args: Vec<&str>,
+        env: &[(&str, &str)],
+    ) -> Result<String, GHASError> {
+        let _timer = SpanTimer::start();
         debug!("CodeQL.run args :: {:?}", args);
+        debug!(
+            "CodeQL.run search paths :: {:?}",
+            self.effective_search_paths()
+        );
+
+        if self.offline && args.starts_with(&["pack", "download"]) {
+            return Err(GHASError::Unsupported(String::from(
+                "Unable to download packs in offline mode",
+            )));
+        }
 
         let mut cmd = tokio::process::Command::new(&self.path);
         cmd.args(args);
+        if let Some(auth) = self.registries_auth() {
+            cmd.env("CODEQL_REGISTRIES_AUTH", auth);
+        }
+        cmd.envs(&self.env);
+        cmd.envs(env.iter().copied());
+        if self.offline {
+            cmd.env_remove("CODEQL_REGISTRIES_AUTH");
+        }
 
         let output = cmd.output().await?;
 
@@ -138,19 +351,306 @@ impl CodeQL {
 
     /// Pass a CodeQLDatabase to the CodeQL CLI to return a CodeQLDatabaseHandler.
     /// This handler can be used to run queries and other operations on the database.
-    pub fn database<'a>(&'a self, db: &'a CodeQLDatabase) -> CodeQLDatabaseHandler {
+    pub fn database<'a>(&'a self, db: &'a CodeQLDatabase) -> CodeQLDatabaseHandler<'a, 'a> {
         CodeQLDatabaseHandler::new(db, self)
     }
 
+    /// Create and analyze a CodeQL database, returning the SARIF results.
+    ///
+    /// The `configure` closure can be used to set options on the database handler
+    /// (category, threat model, queries, etc.) before the database is created and analyzed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ghastoolkit::{CodeQL, CodeQLDatabase};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let codeql = CodeQL::default();
+    ///
+    /// let mut database = CodeQLDatabase::init()
+    ///     .name("ghastoolkit")
+    ///     .language("python")
+    ///     .source(String::from("/path/to/source"))
+    ///     .build()
+    ///     .expect("Failed to create CodeQL database");
+    ///
+    /// let sarif = codeql
+    ///     .scan(&mut database, |handler| {
+    ///         handler
+    ///             .overwrite()
+    ///             .category("/language:python")
+    ///             .threat_model("local")
+    ///             .download_packs(true)
+    ///     })
+    ///     .await
+    ///     .expect("Failed to scan");
+    /// # }
+    /// ```
+    pub async fn scan<F>(
+        &self,
+        database: &mut CodeQLDatabase,
+        configure: F,
+    ) -> Result<Sarif, GHASError>
+    where
+        F: for<'a> FnOnce(CodeQLDatabaseHandler<'a, 'a>) -> CodeQLDatabaseHandler<'a, 'a>,
+    {
+        let sarif = {
+            let mut handler = configure(self.database(database));
+            handler.create().await?;
+            handler.analyze().await?
+        };
+
+        // Reload the database after creation
+        database.reload()?;
+
+        Ok(sarif)
+    }
+
+    /// Create and analyze a database for each language of a source root and merge
+    /// the results into a single SARIF.
+    ///
+    /// Each language has its own database (in the default databases path) and the
+    /// SARIF category is set to `/language:<language>` so the results of every
+    /// language are kept separate when uploaded.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ghastoolkit::{codeql::CodeQLLanguage, CodeQL};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let codeql = CodeQL::new().await;
+    ///
+    /// let sarif = codeql
+    ///     .scan_all("./", vec![CodeQLLanguage::Python, CodeQLLanguage::JavaScript])
+    ///     .await
+    ///     .expect("Failed to scan");
+    /// println!("Results :: {}", sarif.get_results().len());
+    /// # }
+    /// ```
+    pub async fn scan_all(
+        &self,
+        source: impl Into<PathBuf>,
+        languages: Vec<CodeQLLanguage>,
+    ) -> Result<Sarif, GHASError> {
+        self.scan_languages(&source.into(), languages, None).await
+    }
+
+    /// Create and analyze a database for each language of a source root, the databases
+    /// are associated with the repository (if provided)
+    async fn scan_languages(
+        &self,
+        source: &Path,
+        languages: Vec<CodeQLLanguage>,
+        repository: Option<&Repository>,
+    ) -> Result<Sarif, GHASError> {
+        let mut sarif = Sarif::new();
+        let name = CodeQLDatabaseBuilder::source_name(source).ok_or_else(|| {
+            GHASError::CodeQLDatabaseError(format!(
+                "Could not determine database name for source: {}",
+                source.display()
+            ))
+        })?;
+
+        for language in languages {
+            let mut builder = CodeQLDatabase::init()
+                .name(name.clone())
+                .source(source.display().to_string())
+                .language(language.language());
+            if let Some(repository) = repository {
+                builder = builder.repository(repository);
+            }
+            let mut database = builder.build()?;
+            debug!("Scanning {} :: {}", language.language(), database);
+
+            let category = format!("/language:{}", language.language());
+            let results = self
+                .scan(&mut database, |handler| {
+                    handler.overwrite().category(category)
+                })
+                .await?;
+            sarif.merge(results);
+        }
+
+        Ok(sarif)
+    }
+
+    /// Clone, create and analyze databases for multiple repositories, scanning up to
+    /// `concurrency` repositories at the same time.
+    ///
+    /// Each repository is cloned to its path (unless the path is already a Git
+    /// repository), the languages are detected from the source code and every language
+    /// is scanned (see [`CodeQL::scan_all`]). CodeQL is CPU and memory heavy, a
+    /// `concurrency` of `0` uses [`CodeQL::DEFAULT_SCAN_CONCURRENCY`].
+    ///
+    /// The results are returned in the same order as the repositories.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ghastoolkit::{CodeQL, GitHub, Repository};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let codeql = CodeQL::new().await;
+    /// let github = GitHub::default();
+    ///
+    /// let repositories = vec![
+    ///     (Repository::new("geekmasher", "ghastoolkit-rs"), PathBuf::from("/tmp/ghastoolkit-rs")),
+    ///     (Repository::new("geekmasher", "ghastoolkit"), PathBuf::from("/tmp/ghastoolkit")),
+    /// ];
+    ///
+    /// for (repository, result) in codeql.scan_repositories(&github, repositories, 2).await {
+    ///     match result {
+    ///         Ok(sarif) => println!("{} :: {} results", repository, sarif.count_results()),
+    ///         Err(e) => println!("{} :: failed - {}", repository, e),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn scan_repositories(
+        &self,
+        github: &GitHub,
+        repositories: Vec<(Repository, PathBuf)>,
+        concurrency: usize,
+    ) -> Vec<(Repository, Result<Sarif, GHASError>)> {
+        let concurrency = match concurrency {
+            0 => CodeQL::DEFAULT_SCAN_CONCURRENCY,
+            c => c,
+        };
+
+        let languages = match self.get_extractors().await {
+            Ok(extractors) => Arc::new(CodeQLLanguages::from(extractors)),
+            Err(e) => {
+                return repositories
+                    .into_iter()
+                    .map(|(repository, _)| {
+                        let error = GHASError::CodeQLError(format!(
+                            "Failed to resolve the CodeQL extractors: {}",
+                            e
+                        ));
+                        (repository, Err(error))
+                    })
+                    .collect();
+            }
+        };
+
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency));
+        let tasks: Vec<_> = repositories
+            .into_iter()
+            .map(|(repository, path)| {
+                let codeql = self.clone();
+                let github = github.clone();
+                let languages = Arc::clone(&languages);
+                let semaphore = Arc::clone(&semaphore);
+                let task_repository = repository.clone();
+
+                let task = tokio::spawn(async move {
+                    let _permit = semaphore
+                        .acquire_owned()
+                        .await
+                        .map_err(|e| GHASError::UnknownError(e.to_string()))?;
+                    codeql
+                        .scan_repository(&github, task_repository, path, &languages)
+                        .await
+                });
+                (repository, task)
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        for (repository, task) in tasks {
+            let result = match task.await {
+                Ok(result) => result,
+                Err(e) => Err(GHASError::from(e)),
+            };
+            results.push((repository, result));
+        }
+        results
+    }
+
+    /// Clone (if needed) and scan all the languages detected in a repository
+    async fn scan_repository(
+        &self,
+        github: &GitHub,
+        mut repository: Repository,
+        path: PathBuf,
+        languages: &CodeQLLanguages,
+    ) -> Result<Sarif, GHASError> {
+        if path.join(".git").exists() {
+            repository.set_root(path.clone());
+        } else {
+            debug!("Cloning {} to {}", repository, path.display());
+            let github = github.clone();
+            let clone_path = path.display().to_string();
+            repository = tokio::task::spawn_blocking(move || {
+                github
+                    .clone_repository(&mut repository, &clone_path)
+                    .map(|_| repository)
+            })
+            .await??;
+        }
+
+        let detected: Vec<CodeQLLanguage> = languages
+            .detect(&path)
+            .into_iter()
+            .map(|(language, _)| language)
+            .filter(|language| !language.is_secondary())
+            .collect();
+        if detected.is_empty() {
+            return Err(GHASError::CodeQLError(format!(
+                "No CodeQL languages found in {}",
+                repository
+            )));
+        }
+
+        self.scan_languages(&path, detected, Some(&repository))
+            .await
+    }
+
     /// Get the version of the loaded CodeQL CLI
     pub fn version(&self) -> Option<String> {
         self.version.clone()
     }
 
+    /// Get the parsed version of the loaded CodeQL CLI as `(major, minor, patch)`.
+    /// Build metadata (e.g. `2.16.0+abcdef`) is ignored.
+    pub fn version_tuple(&self) -> Option<(u64, u64, u64)> {
+        let version = parse_version(self.version.as_deref()?)?;
+        Some((version.major, version.minor, version.patch))
+    }
+
+    /// Check if the loaded CodeQL CLI version is at least `version` (e.g. `2.16.0`).
+    /// Returns `false` if either version can't be parsed.
+    ///
+    /// ```rust
+    /// use ghastoolkit::CodeQL;
+    ///
+    /// let codeql = CodeQL::default();
+    /// // No CodeQL CLI version is known
+    /// assert!(!codeql.version_at_least("2.16.0"));
+    /// ```
+    pub fn version_at_least(&self, version: &str) -> bool {
+        match (
+            self.version.as_deref().and_then(parse_version),
+            parse_version(version),
+        ) {
+            (Some(current), Some(required)) => {
+                (current.major, current.minor, current.patch)
+                    >= (required.major, required.minor, required.patch)
+            }
+            _ => false,
+        }
+    }
+
     /// Get the version of the CodeQL CLI
     pub async fn get_version(path: &Path) -> Result<String, GHASError> {
         let output = tokio::process::Command::new(path)
-            .args(&["version", "--format", "terse"])
+            .args(["version", "--format", "terse"])
             .output()
             .await?;
 
@@ -210,27 +710,227 @@ impl CodeQL {
             .collect())
     }
 
-    /// Get all languages supported by the CodeQL CLI
-    pub async fn get_all_languages(&self) -> Result<Vec<CodeQLLanguage>, GHASError> {
-        match self
-            .run(vec!["resolve", "languages", "--format", "json"])
-            .await
-        {
-            Ok(v) => {
-                let languages: ResolvedLanguages = serde_json::from_str(&v)?;
-                let mut result = Vec::new();
-                for (language, _) in languages {
-                    // allow custom languages if they come from CodeQL CLI
-                    result.push(CodeQLLanguage::from((language.as_str(), true)));
+    /// Get the extractors available to the CodeQL CLI (from `codeql resolve languages`)
+    pub async fn get_extractors(&self) -> Result<Vec<CodeQLExtractor>, GHASError> {
+        let languages = self.resolve_languages().await?;
+
+        let mut extractors = Vec::new();
+        for (language, paths) in languages {
+            for path in paths {
+                let config = PathBuf::from(path).join("codeql-extractor.yml");
+                match CodeQLExtractor::load_path(&config) {
+                    Ok(extractor) => extractors.push(extractor),
+                    Err(e) => debug!(
+                        "Failed to load extractor for {} :: {}",
+                        language,
+                        e.display_chain()
+                    ),
                 }
-                result.sort();
-                Ok(result)
             }
-            Err(e) => Err(e),
         }
+        Ok(extractors)
+    }
+
+    /// Resolve the extractor the CodeQL CLI will use for a language
+    /// (`codeql resolve extractor`), including custom extractors on the search path.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ghastoolkit::CodeQL;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let codeql = CodeQL::default();
+    ///
+    /// let extractor = codeql
+    ///     .resolve_extractor("rust")
+    ///     .await
+    ///     .expect("Failed to resolve extractor");
+    /// println!("Extractor :: {} v{}", extractor.name, extractor.version);
+    /// # }
+    /// ```
+    pub async fn resolve_extractor(&self, language: &str) -> Result<CodeQLExtractor, GHASError> {
+        let root = self.resolve_extractor_root(language).await?;
+        CodeQLExtractor::load_path(root.join("codeql-extractor.yml"))
+    }
+
+    /// Resolve the root directory of the extractor for a language
+    /// (`codeql resolve extractor`)
+    pub async fn resolve_extractor_root(&self, language: &str) -> Result<PathBuf, GHASError> {
+        let mut args = vec![
+            String::from("resolve"),
+            String::from("extractor"),
+            format!("--language={}", language),
+            String::from("--format=json"),
+        ];
+        self.add_search_path(&mut args);
+
+        let output = self.run(args.iter().map(|a| a.as_str()).collect()).await?;
+        CodeQL::extractor_root(&output)
+    }
+
+    /// Parse the extractor root directory from the output of `codeql resolve extractor`
+    fn extractor_root(output: &str) -> Result<PathBuf, GHASError> {
+        let value: serde_json::Value = serde_json::from_str(output)?;
+        // Older versions output the path, newer versions an object with the root
+        let root = match &value {
+            serde_json::Value::String(path) => Some(path.as_str()),
+            serde_json::Value::Object(obj) => obj.get("extractor_root").and_then(|v| v.as_str()),
+            _ => None,
+        };
+        match root {
+            Some(root) => Ok(PathBuf::from(root)),
+            None => Err(GHASError::Parse(format!(
+                "Unable to parse extractor location: {}",
+                output.trim()
+            ))),
+        }
+    }
+
+    /// List the packs downloaded to the local CodeQL packages cache
+    /// (`CodeQLPacks::codeql_packages_path`). Every installed version of a pack is included.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ghastoolkit::CodeQL;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let codeql = CodeQL::default();
+    ///
+    /// for pack in codeql.list_packs().expect("Failed to list packs") {
+    ///     println!("Pack :: {}", pack);
+    /// }
+    /// # }
+    /// ```
+    pub fn list_packs(&self) -> Result<CodeQLPacks, GHASError> {
+        let path = CodeQLPacks::codeql_packages_path();
+        if !path.exists() {
+            return Ok(CodeQLPacks::default());
+        }
+        CodeQLPacks::load(path).map_err(GHASError::Other)
+    }
+
+    /// Detect the languages CodeQL would use to create databases for a source tree.
+    ///
+    /// CodeQL does not provide a command to resolve languages from a folder, so this
+    /// counts the files in the source tree and maps their extensions using each extractor's
+    /// `file_types`. Languages are sorted by the number of matching files (most first) and
+    /// secondary languages (yaml, xml, etc.) are excluded.
+    pub async fn resolve_database_languages(
+        &self,
+        source: &Path,
+    ) -> Result<Vec<CodeQLLanguage>, GHASError> {
+        let languages = CodeQLLanguages::from(self.get_extractors().await?);
+
+        Ok(languages
+            .detect(source)
+            .into_iter()
+            .map(|(l, _)| l)
+            .filter(|l| !l.is_secondary())
+            .collect())
+    }
+
+    /// Resolve the queries a query suite / pack / query spec expands to
+    /// (`codeql resolve queries`).
+    ///
+    /// An error is returned if the spec doesn't expand to any queries (e.g. the
+    /// suite name is wrong) so analyses producing no results fail fast.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ghastoolkit::CodeQL;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let codeql = CodeQL::default();
+    ///
+    /// let queries = codeql
+    ///     .resolve_suite("codeql/python-queries:codeql-suites/python-code-scanning.qls")
+    ///     .await
+    ///     .expect("Failed to resolve suite");
+    /// # }
+    /// ```
+    pub async fn resolve_suite(
+        &self,
+        spec: impl Into<CodeQLQueries>,
+    ) -> Result<Vec<PathBuf>, GHASError> {
+        let spec = spec.into().to_string();
+
+        let mut args = vec![
+            String::from("resolve"),
+            String::from("queries"),
+            String::from("--format=json"),
+        ];
+        self.add_additional_packs(&mut args);
+        args.push(spec.clone());
+
+        let output = self.run(args.iter().map(|a| a.as_str()).collect()).await?;
+        let queries: Vec<PathBuf> = serde_json::from_str(&output)?;
+
+        if queries.is_empty() {
+            return Err(GHASError::NotFound {
+                message: format!("No queries found for '{}'", spec),
+                source: None,
+            });
+        }
+        Ok(queries)
+    }
+
+    /// Get all languages supported by the CodeQL CLI
+    pub async fn get_all_languages(&self) -> Result<Vec<CodeQLLanguage>, GHASError> {
+        let languages = self.resolve_languages().await?;
+
+        let mut result = Vec::new();
+        for language in languages.keys() {
+            // allow custom languages if they come from CodeQL CLI
+            result.push(CodeQLLanguage::from((language.as_str(), true)));
+        }
+        result.sort();
+        Ok(result)
+    }
+
+    /// Run `codeql resolve languages`, the result is cached for the lifetime of
+    /// the instance (see `refresh_languages()`)
+    async fn resolve_languages(&self) -> Result<ResolvedLanguages, GHASError> {
+        if let Some(languages) = self.languages.get() {
+            return Ok(languages.clone());
+        }
+
+        let args = self.resolve_languages_cmd();
+        let output = self.run(args.iter().map(|a| a.as_str()).collect()).await?;
+        let languages: ResolvedLanguages = serde_json::from_str(&output)?;
+
+        let _ = self.languages.set(languages.clone());
+        Ok(languages)
+    }
+
+    /// Create the `codeql resolve languages` command
+    fn resolve_languages_cmd(&self) -> Vec<String> {
+        let mut args = vec![
+            String::from("resolve"),
+            String::from("languages"),
+            String::from("--format=json"),
+        ];
+        self.add_search_path(&mut args);
+        args
+    }
+
+    /// Clear the cached languages so the next call resolves them from the CodeQL CLI again
+    /// (e.g. after installing new extractor packs)
+    pub fn refresh_languages(&mut self) {
+        self.languages = OnceLock::new();
     }
 }
 
+/// Parse a CodeQL version string (`2.16.0`, `v2.16.0`, or `2.16.0+abcdef`)
+fn parse_version(version: &str) -> Option<semver::Version> {
+    semver::Version::parse(version.trim().trim_start_matches('v')).ok()
+}
+
 impl Display for CodeQL {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(version) = &self.version {
@@ -250,6 +950,10 @@ impl Default for CodeQL {
             ram: None,
             search_path: Vec::new(),
             additional_packs: Vec::new(),
+            env: HashMap::new(),
+            offline: false,
+            registries: Vec::new(),
+            languages: OnceLock::new(),
         }
     }
 }
@@ -264,6 +968,10 @@ pub struct CodeQLBuilder {
 
     search_paths: Vec<PathBuf>,
     additional_packs: Vec<String>,
+
+    env: HashMap<String, String>,
+    offline: bool,
+    registries: Vec<(String, String)>,
 }
 
 impl CodeQLBuilder {
@@ -326,6 +1034,45 @@ impl CodeQLBuilder {
         self
     }
 
+    /// Add an environment variable which is passed to every CodeQL CLI command
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+
+    /// Add a container registry and token used to download / publish CodeQL packs.
+    ///
+    /// CodeQL reads these from the `CODEQL_REGISTRIES_AUTH` environment variable which
+    /// is a comma-separated list of `<registry_url>=<token>` pairs. For GitHub Enterprise
+    /// Server the registry URL is `https://containers.<hostname>/v2/`.
+    ///
+    /// ```rust
+    /// use ghastoolkit::codeql::cli::CodeQL;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let codeql = CodeQL::init()
+    ///     .registry("https://ghcr.io/v2/", "ghp_token")
+    ///     .registry("https://containers.github.geekmasher.dev/v2/", "ghes_token")
+    ///     .build()
+    ///     .await
+    ///     .expect("Failed to create CodeQL instance");
+    /// # }
+    /// ```
+    pub fn registry(mut self, host: &str, token: &str) -> Self {
+        self.registries.push((host.to_string(), token.to_string()));
+        self
+    }
+
+    /// Run CodeQL in offline mode (for air-gapped / sandboxed environments).
+    ///
+    /// Registry credentials (`CODEQL_REGISTRIES_AUTH`) are not passed to the CLI,
+    /// packs are not downloaded while analyzing, and `pack download` commands are refused.
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
     /// Build the CodeQL instance
     pub async fn build(&self) -> Result<CodeQL, GHASError> {
         let path: PathBuf = match self.path {
@@ -338,13 +1085,270 @@ impl CodeQLBuilder {
 
         let version: Option<String> = CodeQL::get_version(&path).await.ok();
 
+        let mut search_path = self.search_paths.clone();
+        CodeQL::add_bundle_search_path(&path, &mut search_path);
+
         Ok(CodeQL {
             version,
             path,
             threads: self.threads,
             ram: self.ram.into(),
             additional_packs: self.additional_packs.clone(),
-            search_path: self.search_paths.clone(),
+            search_path,
+            env: self.env.clone(),
+            offline: self.offline,
+            registries: self.registries.clone(),
+            languages: OnceLock::new(),
         })
     }
 }
+
+/// Possible CodeQL binaries in a directory (`codeql` and `codeql.exe` for Windows)
+fn codeql_binaries(dir: &Path) -> [PathBuf; 2] {
+    [dir.join("codeql"), dir.join("codeql.exe")]
+}
+
+/// Check if a path is an executable file
+fn is_executable(path: &Path) -> bool {
+    match std::fs::metadata(path) {
+        #[cfg(unix)]
+        Ok(metadata) => {
+            use std::os::unix::fs::PermissionsExt;
+            metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+        }
+        #[cfg(not(unix))]
+        Ok(metadata) => metadata.is_file(),
+        Err(_) => false,
+    }
+}
+
+/// Get the number of a VS Code extension distribution (`.../distribution<N>/codeql/codeql`)
+fn distribution_number(path: &Path) -> u64 {
+    path.ancestors()
+        .filter_map(|p| p.file_name()?.to_str()?.strip_prefix("distribution"))
+        .find_map(|n| n.parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{CodeQL, CodeQLLanguage, ResolvedLanguages};
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_codeql_in() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+
+        let create = |path: PathBuf, mode: u32| {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+            path
+        };
+        let not_executable = create(root.join(".codeql/codeql/codeql"), 0o644);
+        let storage = root.join(".config/Code/User/globalStorage/github.vscode-codeql");
+        let old = create(storage.join("distribution2/codeql/codeql"), 0o755);
+        let new = create(storage.join("distribution10/codeql/codeql"), 0o755);
+
+        let locations = CodeQL::common_locations(Some(root));
+        assert_eq!(locations[0], not_executable);
+        let new_index = locations.iter().position(|p| p == &new).unwrap();
+        let old_index = locations.iter().position(|p| p == &old).unwrap();
+        assert!(new_index < old_index);
+
+        let candidates = vec![root.join("missing"), not_executable, new.clone(), old];
+        assert_eq!(CodeQL::find_codeql_in(&candidates), Some(new));
+        assert_eq!(CodeQL::find_codeql_in(&[root.join("missing")]), None);
+    }
+
+    #[tokio::test]
+    async fn test_scan_all_current_dir() {
+        let codeql = CodeQL {
+            path: PathBuf::from("/ghastoolkit/missing/codeql"),
+            ..Default::default()
+        };
+
+        // `./` has no directory name, the name comes from the resolved directory
+        let result = codeql.scan_all("./", vec![CodeQLLanguage::Python]).await;
+        assert!(result.is_err());
+        let result = codeql.scan_all("/", vec![CodeQLLanguage::Python]).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Could not determine database name"));
+    }
+
+    #[tokio::test]
+    async fn test_scan_repositories_without_codeql() {
+        let codeql = CodeQL {
+            path: PathBuf::from("/ghastoolkit/missing/codeql"),
+            ..Default::default()
+        };
+
+        let repositories = vec![
+            (
+                crate::Repository::new("geekmasher", "first"),
+                PathBuf::from("/tmp/first"),
+            ),
+            (
+                crate::Repository::new("geekmasher", "second"),
+                PathBuf::from("/tmp/second"),
+            ),
+        ];
+        let results = codeql
+            .scan_repositories(&crate::GitHub::default(), repositories, 0)
+            .await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0.name(), "first");
+        assert_eq!(results[1].0.name(), "second");
+        assert!(results.iter().all(|(_, result)| result.is_err()));
+    }
+
+    #[test]
+    fn test_effective_search_paths() {
+        let mut codeql = CodeQL::default();
+        assert!(codeql.effective_search_paths().is_empty());
+        assert_eq!(
+            codeql.resolve_languages_cmd(),
+            vec!["resolve", "languages", "--format=json"]
+        );
+
+        codeql.search_path.push(PathBuf::from("/opt/extractors"));
+        codeql.search_path.push(PathBuf::from("/opt/packs"));
+        let paths = vec![
+            PathBuf::from("/opt/extractors"),
+            PathBuf::from("/opt/packs"),
+        ];
+        assert_eq!(codeql.effective_search_paths(), paths);
+        let joined = std::env::join_paths(&paths).unwrap();
+        assert_eq!(
+            codeql.resolve_languages_cmd(),
+            vec![
+                String::from("resolve"),
+                String::from("languages"),
+                String::from("--format=json"),
+                format!("--search-path={}", joined.to_string_lossy()),
+            ]
+        );
+
+        let mut languages = ResolvedLanguages::new();
+        languages.insert(
+            String::from("rust"),
+            vec![String::from("/opt/extractors/rust")],
+        );
+        languages.insert(
+            String::from("python"),
+            vec![String::from("/opt/codeql/python")],
+        );
+        codeql.languages.set(languages).unwrap();
+
+        // Resolved extractor directories are not search paths
+        assert_eq!(codeql.effective_search_paths(), paths);
+    }
+
+    #[test]
+    fn test_bundle_search_path() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let dist = tempdir.path().to_path_buf();
+        let binary = dist.join("codeql");
+
+        assert_eq!(
+            CodeQL::bundle_dist(&binary, Some(&dist)),
+            Some(dist.clone())
+        );
+        assert_eq!(CodeQL::bundle_dist(&binary, None), None);
+        assert_eq!(
+            CodeQL::bundle_dist(&PathBuf::from("/usr/bin/codeql"), Some(&dist)),
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_codeql_in_paths() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        std::fs::create_dir_all(root.join("bin")).unwrap();
+        std::fs::create_dir_all(root.join("windows")).unwrap();
+
+        let exe = root.join("windows").join("codeql.exe");
+        std::fs::write(&exe, "").unwrap();
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let paths = std::env::join_paths([root.join("bin"), root.join("windows")]).unwrap();
+        assert_eq!(CodeQL::find_codeql_in_paths(&paths), Some(exe));
+
+        let paths = std::env::join_paths([root.join("bin")]).unwrap();
+        assert_eq!(CodeQL::find_codeql_in_paths(&paths), None);
+    }
+
+    #[tokio::test]
+    async fn test_registries_auth() {
+        let codeql = CodeQL::init()
+            .registry("https://ghcr.io/v2/", "token1")
+            .registry("https://containers.github.geekmasher.dev/v2/", "token2")
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            codeql.registries_auth(),
+            Some(String::from(
+                "https://ghcr.io/v2/=token1,https://containers.github.geekmasher.dev/v2/=token2"
+            ))
+        );
+        assert_eq!(CodeQL::default().registries_auth(), None);
+    }
+
+    #[test]
+    fn test_version() {
+        let mut codeql = CodeQL::default();
+        assert_eq!(codeql.version_tuple(), None);
+        assert!(!codeql.version_at_least("2.0.0"));
+
+        codeql.version = Some(String::from("2.16.0"));
+        assert_eq!(codeql.version_tuple(), Some((2, 16, 0)));
+        assert!(codeql.version_at_least("2.16.0"));
+        assert!(codeql.version_at_least("2.9.4"));
+        assert!(!codeql.version_at_least("2.16.1"));
+        assert!(!codeql.version_at_least("invalid"));
+
+        codeql.version = Some(String::from("2.16.0+abcdef"));
+        assert_eq!(codeql.version_tuple(), Some((2, 16, 0)));
+        assert!(codeql.version_at_least("2.16.0"));
+    }
+
+    #[test]
+    fn test_extractor_root() {
+        assert_eq!(
+            CodeQL::extractor_root(r#""/opt/codeql/rust""#).unwrap(),
+            PathBuf::from("/opt/codeql/rust")
+        );
+        assert_eq!(
+            CodeQL::extractor_root(r#"{"extractor_root": "/opt/codeql/rust"}"#).unwrap(),
+            PathBuf::from("/opt/codeql/rust")
+        );
+        assert!(CodeQL::extractor_root("[]").is_err());
+        assert!(CodeQL::extractor_root("not json").is_err());
+    }
+
+    #[test]
+    fn test_refresh_languages() {
+        let mut codeql = CodeQL::default();
+        let _ = codeql
+            .languages
+            .set([(String::from("python"), vec![])].into());
+        assert!(codeql.languages.get().is_some());
+
+        codeql.refresh_languages();
+        assert!(codeql.languages.get().is_none());
+    }
+}
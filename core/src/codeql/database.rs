@@ -2,25 +2,43 @@
 //! This structure is used to interact with CodeQL databases.
 //! It provides methods to validate, build, and handle databases.
 use std::{
+    collections::HashMap,
+    ffi::OsStr,
     fmt::Display,
+    io::{Read, Seek},
     path::{Path, PathBuf},
 };
 
 use log::debug;
+use walkdir::WalkDir;
 
 use crate::{
-    codeql::{database::config::CodeQLDatabaseConfig, CodeQLLanguage},
+    codeql::{
+        database::{
+            config::CodeQLDatabaseConfig,
+            diagnostics::{Diagnostic, DiagnosticSeverity},
+            metrics::DatabaseMetrics,
+        },
+        BuildMode, CodeQLLanguage,
+    },
     CodeQLDatabases, GHASError, Repository,
 };
 
 /// CodeQL Database Configuration file
 pub mod config;
+/// CodeQL Database Diagnostics
+pub mod diagnostics;
 /// CodeQL Database Handler
 pub mod handler;
+/// CodeQL Database Metrics
+pub mod metrics;
 /// CodeQL Queries
 pub mod queries;
 
 /// CodeQL Database
+///
+/// Databases are equal (and hash the same) if they have the same name, language and path,
+/// the configuration is not compared.
 #[derive(Debug, Clone, Default)]
 pub struct CodeQLDatabase {
     name: String,
@@ -52,6 +70,16 @@ impl CodeQLDatabase {
         self.language.language()
     }
 
+    /// Get the database name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the repository the database is associated with
+    pub fn repository(&self) -> Option<&Repository> {
+        self.repository.as_ref()
+    }
+
     /// Get the database path (root directory)
     pub fn path(&self) -> &PathBuf {
         &self.path
@@ -91,6 +119,46 @@ impl CodeQLDatabase {
         None
     }
 
+    /// Get the commit SHA the database was created from (if recorded in the
+    /// database creation metadata)
+    pub fn commit_sha(&self) -> Option<&str> {
+        self.config
+            .as_ref()
+            .and_then(|c| c.creation_metadata.as_ref())
+            .and_then(|m| m.sha.as_deref())
+    }
+
+    /// Check if the database has been finalised (and can be analyzed).
+    /// Databases created by older CLIs which don't record this are assumed to be finalised.
+    pub fn finalised(&self) -> bool {
+        self.config
+            .as_ref()
+            .map(|c| c.finalised.unwrap_or(true))
+            .unwrap_or(false)
+    }
+
+    /// Get the build mode used to create the database
+    pub fn build_mode(&self) -> Option<BuildMode> {
+        self.config.as_ref().and_then(|c| c.build_mode)
+    }
+
+    /// Get the source location prefix (source root) recorded in the database
+    pub fn source_location_prefix(&self) -> Option<&Path> {
+        self.config
+            .as_ref()
+            .and_then(|c| c.source_location_prefix.as_deref())
+            .map(Path::new)
+    }
+
+    /// Get the diagnostics written to the database (`<database>/diagnostic`) by the
+    /// extractors and CLI while creating the database.
+    ///
+    /// Diagnostics can show an extractor partially failed (e.g. some files didn't
+    /// compile) even though the database was created.
+    pub fn diagnostics(&self) -> Result<Vec<Diagnostic>, GHASError> {
+        Diagnostic::load_dir(&self.path.join("diagnostic"))
+    }
+
     /// Get the number of lines of code in the database
     pub fn lines_of_code(&self) -> usize {
         if let Some(config) = &self.config {
@@ -99,6 +167,63 @@ impl CodeQLDatabase {
         0
     }
 
+    /// Get the baseline lines of code for each language in the database.
+    ///
+    /// This is read from the database `baseline-info.json` file, if that isn't present
+    /// the baseline lines of code is returned for the primary language.
+    pub fn lines_of_code_by_language(&self) -> HashMap<String, usize> {
+        if let Some(info) = self.baseline_info() {
+            return info
+                .languages
+                .into_iter()
+                .map(|(language, info)| (language, info.lines_of_code))
+                .collect();
+        }
+
+        let mut lines = HashMap::new();
+        lines.insert(self.language().to_string(), self.lines_of_code());
+        lines
+    }
+
+    /// Get the metrics of the database: lines of code, number of files extracted and the
+    /// number of extraction errors / warnings (from the diagnostics).
+    pub fn metrics(&self) -> Result<DatabaseMetrics, GHASError> {
+        let lines_of_code_by_language = self.lines_of_code_by_language();
+        let files = self
+            .baseline_info()
+            .map(|info| info.languages.values().map(|l| l.files.len()).sum())
+            .unwrap_or_default();
+
+        let diagnostics = self.diagnostics()?;
+        let count = |severity: DiagnosticSeverity| {
+            diagnostics
+                .iter()
+                .filter(|d| d.severity == severity)
+                .count()
+        };
+
+        Ok(DatabaseMetrics {
+            lines_of_code: lines_of_code_by_language.values().sum(),
+            lines_of_code_by_language,
+            files,
+            extraction_errors: count(DiagnosticSeverity::Error),
+            extraction_warnings: count(DiagnosticSeverity::Warning),
+        })
+    }
+
+    /// Read the database `baseline-info.json` file (if present)
+    fn baseline_info(&self) -> Option<BaselineInfo> {
+        let path = self.path.join("baseline-info.json");
+        let data = std::fs::read_to_string(&path).ok()?;
+        match serde_json::from_str::<BaselineInfo>(&data) {
+            Ok(info) => Some(info),
+            Err(e) => {
+                debug!("Failed to parse {} :: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
     /// Reload the database configuration
     pub fn reload(&mut self) -> Result<(), GHASError> {
         debug!("Reloading CodeQL Database Configuration");
@@ -113,44 +238,210 @@ impl CodeQLDatabase {
         }
     }
 
-    /// Load a database from a directory
+    /// Load a database from a directory (or its `codeql-database.yml` file).
+    ///
+    /// If the directory is not a database, the directories below it are searched and the
+    /// database is loaded if exactly one is found. An error is returned if there are
+    /// multiple databases, use [`CodeQLDatabases::load`] to load all of them.
     pub fn load(path: String) -> Result<CodeQLDatabase, GHASError> {
         let mut config_path = std::path::PathBuf::from(path.clone());
 
         if !config_path.exists() {
-            return Err(GHASError::CodeQLDatabaseError(
-                "Could not find codeql-database.yml".to_string(),
-            ));
+            return Err(GHASError::NotFound {
+                message: "Could not find codeql-database.yml".to_string(),
+                source: None,
+            });
         }
 
         // If the path is a file, we need to pop it to get the directory
         if config_path.is_file() && config_path.ends_with("codeql-database.yml") {
             debug!("Loading CodeQL Database from: {}", config_path.display());
             CodeQLDatabase::load_database_config(&config_path)
-        } else {
-            // If the path is a directory, we need to find the configuration file
+        } else if config_path.join("codeql-database.yml").is_file() {
             debug!("Loading CodeQL Database from: {}", config_path.display());
             config_path.push("codeql-database.yml");
             CodeQLDatabase::load_database_config(&config_path)
+        } else {
+            // Only descend into the directory if it contains a single database
+            let mut dirs = CodeQLDatabases::find_database_dirs(&config_path);
+            match dirs.len() {
+                0 => Err(GHASError::NotFound {
+                    message: format!(
+                        "Could not find codeql-database.yml in {}",
+                        config_path.display()
+                    ),
+                    source: None,
+                }),
+                1 => {
+                    let mut config_path = dirs.remove(0);
+                    debug!("Loading CodeQL Database from: {}", config_path.display());
+                    config_path.push("codeql-database.yml");
+                    CodeQLDatabase::load_database_config(&config_path)
+                }
+                count => Err(GHASError::CodeQLDatabaseError(format!(
+                    "Found {} databases in {}, use CodeQLDatabases::load to load all of them",
+                    count,
+                    config_path.display()
+                ))),
+            }
         }
     }
 
+    /// Load a database from a zip archive, extracting it into `extract_to`.
+    ///
+    /// `extract_to` must not exist or be an empty directory, an error is returned
+    /// otherwise (existing files are never overwritten). If the archive can't be
+    /// extracted or doesn't contain a database, only the extracted files are removed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ghastoolkit::CodeQLDatabase;
+    ///
+    /// let database = CodeQLDatabase::from_archive("python.zip", "/tmp/databases/python")
+    ///     .expect("Failed to load database");
+    /// println!("Database :: {}", database);
+    /// ```
+    pub fn from_archive(
+        archive: impl AsRef<Path>,
+        extract_to: impl AsRef<Path>,
+    ) -> Result<CodeQLDatabase, GHASError> {
+        let file = std::fs::File::open(archive.as_ref())?;
+        CodeQLDatabase::from_archive_reader(file, extract_to)
+    }
+
+    /// Load a database from a zip archive reader (e.g. an in-memory archive),
+    /// extracting it into `extract_to`. See [`CodeQLDatabase::from_archive`].
+    pub fn from_archive_reader<R: Read + Seek>(
+        reader: R,
+        extract_to: impl AsRef<Path>,
+    ) -> Result<CodeQLDatabase, GHASError> {
+        let output = extract_to.as_ref();
+        // Name the database after the directory it was extracted to
+        let name = output
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        CodeQLDatabase::extract_archive(reader, output, |path| {
+            CodeQLDatabase::init()
+                .name(name)
+                .path(path.display().to_string())
+                .build()
+        })
+    }
+
+    /// Extract a CodeQL database archive into `output`, find the database root and
+    /// load it using `load`.
+    ///
+    /// The output directory must not exist or be empty, existing files are never
+    /// overwritten or removed. If the extraction or loading fails, only what this call
+    /// extracted is removed.
+    pub(crate) fn extract_archive<R, F>(
+        reader: R,
+        output: &Path,
+        load: F,
+    ) -> Result<CodeQLDatabase, GHASError>
+    where
+        R: Read + Seek,
+        F: FnOnce(&Path) -> Result<CodeQLDatabase, GHASError>,
+    {
+        debug!("Extracting CodeQL database :: {}", output.display());
+        let created = if output.exists() {
+            if std::fs::read_dir(output)?.next().is_some() {
+                return Err(GHASError::Config(format!(
+                    "Can not extract a CodeQL database into a non-empty directory: {}",
+                    output.display()
+                )));
+            }
+            false
+        } else {
+            std::fs::create_dir_all(output)?;
+            true
+        };
+
+        let database = zip::ZipArchive::new(reader)
+            .and_then(|mut zip| zip.extract(output))
+            .map_err(GHASError::from)
+            .and_then(|_| {
+                // The archive normally contains a single top-level directory with the database
+                WalkDir::new(output)
+                    .max_depth(2)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .find(|e| e.file_name() == "codeql-database.yml")
+                    .and_then(|e| e.path().parent().map(|p| p.to_path_buf()))
+                    .ok_or_else(|| GHASError::NotFound {
+                        message: format!(
+                            "Could not find codeql-database.yml in {}",
+                            output.display()
+                        ),
+                        source: None,
+                    })
+            })
+            .and_then(|path| match load(&path) {
+                Ok(database) if database.config.is_some() => Ok(database),
+                Ok(_) => Err(GHASError::Parse(format!(
+                    "Invalid CodeQL database configuration in {}",
+                    path.display()
+                ))),
+                Err(e) => Err(e),
+            });
+
+        if database.is_err() {
+            // Only remove what was extracted (the directory was empty or did not exist)
+            if created {
+                std::fs::remove_dir_all(output)?;
+            } else {
+                for entry in std::fs::read_dir(output)? {
+                    let path = entry?.path();
+                    if path.is_dir() {
+                        std::fs::remove_dir_all(path)?;
+                    } else {
+                        std::fs::remove_file(path)?;
+                    }
+                }
+            }
+        }
+        database
+    }
+
     fn load_database_config(path: &PathBuf) -> Result<CodeQLDatabase, GHASError> {
         if !path.exists() {
-            Err(GHASError::CodeQLDatabaseError(
-                "Could not find codeql-database.yml".to_string(),
-            ))
+            Err(GHASError::NotFound {
+                message: "Could not find codeql-database.yml".to_string(),
+                source: None,
+            })
         } else {
             let config = CodeQLDatabaseConfig::read(path)?;
-            CodeQLDatabase::init()
-                .source(config.source_location_prefix.clone().unwrap_or_default())
-                .language(config.primary_language.clone())
-                .config(config.clone())
-                .build()
+            let root = path.parent().unwrap_or(Path::new("."));
+
+            let mut builder =
+                CodeQLDatabase::init().source(config.source_location_prefix.unwrap_or_default());
+            if builder.name.is_empty() {
+                // No source root, name the database after its directory
+                if let Some(name) = root.file_name() {
+                    builder = builder.name(name.to_string_lossy());
+                }
+            }
+            builder.path(root.display().to_string()).build()
         }
     }
 }
 
+/// Database baseline information (`baseline-info.json`)
+#[derive(Debug, serde::Deserialize)]
+struct BaselineInfo {
+    languages: HashMap<String, BaselineLanguageInfo>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct BaselineLanguageInfo {
+    #[serde(rename = "linesOfCode", default)]
+    lines_of_code: usize,
+    #[serde(default)]
+    files: Vec<String>,
+}
+
 impl From<String> for CodeQLDatabase {
     fn from(path: String) -> Self {
         CodeQLDatabase::load(path).expect("Failed to load CodeQL Database")
@@ -177,6 +468,22 @@ impl From<&Path> for CodeQLDatabase {
     }
 }
 
+impl PartialEq for CodeQLDatabase {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path && self.language == other.language && self.name == other.name
+    }
+}
+
+impl Eq for CodeQLDatabase {}
+
+impl std::hash::Hash for CodeQLDatabase {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.path.hash(state);
+        self.language.hash(state);
+        self.name.hash(state);
+    }
+}
+
 impl Display for CodeQLDatabase {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let version = self.version();
@@ -238,16 +545,20 @@ impl CodeQLDatabaseBuilder {
             let config = match CodeQLDatabaseConfig::read(&config_path) {
                 Ok(config) => config,
                 Err(e) => {
-                    debug!("Failed to load database configuration: {}", e);
+                    debug!(
+                        "Failed to load database configuration: {}",
+                        e.display_chain()
+                    );
                     return self;
                 }
             };
             debug!("Loaded database configuration: {:?}", &path);
 
-            self.language = CodeQLLanguage::from(config.primary_language);
-            if let Some(source) = config.source_location_prefix {
+            self.language = CodeQLLanguage::from(config.primary_language.clone());
+            if let Some(source) = &config.source_location_prefix {
                 self.source = Some(PathBuf::from(source));
             }
+            self.config = Some(config);
         }
         self
     }
@@ -255,23 +566,31 @@ impl CodeQLDatabaseBuilder {
     /// Set the source root for database creation / mapping
     pub fn source(mut self, source: String) -> Self {
         if !source.is_empty() {
-            self.source = Some(PathBuf::from(source));
+            let source = PathBuf::from(source);
             if self.name.is_empty() {
-                // TODO(geekmasher): This is a bit of a hack, but it works for now
-                self.name = self
-                    .source
-                    .clone()
-                    .unwrap()
-                    .file_name()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string();
+                self.name = CodeQLDatabaseBuilder::source_name(&source).unwrap_or_default();
             }
+            self.source = Some(source);
         }
         self
     }
 
+    /// Get the database name for a source root (the name of the directory).
+    ///
+    /// Paths without a directory name (`./`, `..`) are resolved first, `None` is
+    /// returned for the root directory.
+    pub(crate) fn source_name(source: &Path) -> Option<String> {
+        if let Some(name) = source.file_name().and_then(OsStr::to_str) {
+            return Some(name.to_string());
+        }
+        let name = match source.file_name() {
+            // Not UTF-8
+            Some(name) => name.to_os_string(),
+            None => source.canonicalize().ok()?.file_name()?.to_os_string(),
+        };
+        Some(name.to_string_lossy().into_owned())
+    }
+
     /// Set the language of the database
     pub fn language(mut self, language: impl Into<String>) -> Self {
         self.language = CodeQLLanguage::from(language.into());
@@ -337,9 +656,70 @@ impl CodeQLDatabaseBuilder {
 
 #[cfg(test)]
 mod tests {
-    use crate::CodeQLDatabase;
+    use crate::{
+        codeql::{
+            database::config::CodeQLDatabaseConfig,
+            test_utils::{database_archive, database_config},
+        },
+        CodeQLDatabase, GHASError,
+    };
     use std::path::PathBuf;
 
+    #[test]
+    fn test_from_archive() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+
+        let path = root.join("python.zip");
+        std::fs::write(
+            &path,
+            database_archive(database_config("python").as_bytes()),
+        )
+        .unwrap();
+
+        let output = root.join("python");
+        let database = CodeQLDatabase::from_archive(&path, &output).unwrap();
+        assert_eq!(database.language(), "python");
+        assert_eq!(database.path(), &output.join("codeql_db"));
+
+        // Never extract into (or remove) a non-empty directory
+        assert!(matches!(
+            CodeQLDatabase::from_archive(&path, root),
+            Err(GHASError::Config(_))
+        ));
+        assert!(path.exists());
+        assert!(output.join("codeql_db").exists());
+
+        // In-memory archive
+        let output = root.join("memory");
+        let reader = std::io::Cursor::new(database_archive(database_config("java").as_bytes()));
+        let database = CodeQLDatabase::from_archive_reader(reader, &output).unwrap();
+        assert_eq!(database.language(), "java");
+    }
+
+    #[test]
+    fn test_from_archive_cleanup() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path().join("database");
+
+        // Not a zip archive
+        let reader = std::io::Cursor::new(b"PK\x03\x04 not a zip".to_vec());
+        assert!(CodeQLDatabase::from_archive_reader(reader, &root).is_err());
+        assert!(!root.exists());
+
+        // Invalid database configuration
+        let reader = std::io::Cursor::new(database_archive(b"- not a config"));
+        assert!(CodeQLDatabase::from_archive_reader(reader, &root).is_err());
+        assert!(!root.exists());
+
+        // An existing empty directory is kept, only the extracted files are removed
+        std::fs::create_dir_all(&root).unwrap();
+        let reader = std::io::Cursor::new(database_archive(b"- not a config"));
+        assert!(CodeQLDatabase::from_archive_reader(reader, &root).is_err());
+        assert!(root.exists());
+        assert_eq!(std::fs::read_dir(&root).unwrap().count(), 0);
+    }
+
     #[test]
     fn test_default_database_path() {
         let base = match std::env::var("HOME") {
@@ -383,4 +763,225 @@ mod tests {
 
         assert_eq!(db2.name, "test-repo");
     }
+
+    #[test]
+    fn test_database_name_from_source() {
+        // `./` is resolved to the current directory
+        let cwd = std::env::current_dir().unwrap();
+        let db = CodeQLDatabase::init()
+            .source(String::from("./"))
+            .language("python")
+            .build()
+            .expect("Failed to build database");
+        assert_eq!(
+            db.name,
+            cwd.file_name().unwrap().to_str().unwrap().to_string()
+        );
+
+        // The root directory doesn't have a name
+        let error = CodeQLDatabase::init()
+            .source(String::from("/"))
+            .language("python")
+            .build()
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Could not determine database name"));
+        let db = CodeQLDatabase::init()
+            .name("root")
+            .source(String::from("/"))
+            .build()
+            .expect("Failed to build database");
+        assert_eq!(db.name, "root");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_database_name_non_utf8() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        use crate::codeql::database::CodeQLDatabaseBuilder;
+
+        let source = PathBuf::from(OsStr::from_bytes(b"/tmp/test-\xffrepo"));
+        assert_eq!(
+            CodeQLDatabaseBuilder::source_name(&source),
+            Some(String::from("test-\u{fffd}repo"))
+        );
+    }
+
+    #[test]
+    fn test_database_commit_sha() {
+        let config: CodeQLDatabaseConfig = serde_yaml::from_str(
+            r#"---
+sourceLocationPrefix: /tmp/test-repo
+primaryLanguage: python
+baselineLinesOfCode: 10
+unicodeNewlines: false
+columnKind: utf32
+creationMetadata:
+  sha: 2ee6f5d4a2bd3bd3d4b47a0e4f0f18da2a1b5b33
+  cliVersion: 2.19.0
+  creationTime: 2024-10-01T12:00:00.000000Z
+"#,
+        )
+        .unwrap();
+
+        let db = CodeQLDatabase::init()
+            .name("test-repo")
+            .config(config)
+            .build()
+            .expect("Failed to build database");
+        assert_eq!(
+            db.commit_sha(),
+            Some("2ee6f5d4a2bd3bd3d4b47a0e4f0f18da2a1b5b33")
+        );
+        assert!(db.finalised());
+        assert_eq!(db.build_mode(), None);
+        assert_eq!(
+            db.source_location_prefix(),
+            Some(std::path::Path::new("/tmp/test-repo"))
+        );
+
+        let db = CodeQLDatabase::init()
+            .name("test-repo")
+            .build()
+            .expect("Failed to build database");
+        assert_eq!(db.commit_sha(), None);
+        assert!(!db.finalised());
+    }
+
+    #[test]
+    fn test_database_unfinalised() {
+        let config: CodeQLDatabaseConfig = serde_yaml::from_str(
+            r#"---
+sourceLocationPrefix: /tmp/test-repo
+primaryLanguage: java
+baselineLinesOfCode: 0
+unicodeNewlines: false
+columnKind: utf16
+finalised: false
+buildMode: autobuild
+"#,
+        )
+        .unwrap();
+
+        let db = CodeQLDatabase::init()
+            .name("test-repo")
+            .config(config)
+            .build()
+            .expect("Failed to build database");
+        assert!(!db.finalised());
+        assert_eq!(db.build_mode(), Some(crate::codeql::BuildMode::Autobuild));
+    }
+
+    #[test]
+    fn test_database_diagnostics() {
+        use crate::codeql::database::diagnostics::DiagnosticSeverity;
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        let diagnostic = root.join("diagnostic").join("extractors").join("java");
+        std::fs::create_dir_all(&diagnostic).unwrap();
+        std::fs::write(
+            diagnostic.join("compile.json"),
+            r#"{
+  "timestamp": "2024-10-01T12:00:00.000Z",
+  "source": { "id": "java/extractor/compile-error", "name": "Compilation error", "extractorName": "java" },
+  "severity": "warning",
+  "plaintextMessage": "Failed to compile 2 files",
+  "visibility": { "statusPage": true },
+  "location": { "file": "src/Main.java", "startLine": 10 }
+}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            diagnostic.join("other.jsonl"),
+            concat!(
+                r#"{"source": {"id": "a", "name": "A"}, "severity": "error", "markdownMessage": "**A**"}"#,
+                "\n",
+                r#"{"source": {"id": "b", "name": "B"}, "severity": "unknown-level"}"#,
+                "\n",
+                r#"{"source": "#,
+                "\n"
+            ),
+        )
+        .unwrap();
+        // Malformed diagnostics are skipped
+        std::fs::write(diagnostic.join("broken.json"), "{ not json").unwrap();
+
+        let db = CodeQLDatabase::init()
+            .name("test")
+            .path(root.display().to_string())
+            .build()
+            .unwrap();
+        let mut diagnostics = db.diagnostics().unwrap();
+        diagnostics.sort_by(|a, b| a.source.id.cmp(&b.source.id));
+
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].message(), "**A**");
+        assert_eq!(diagnostics[1].severity, DiagnosticSeverity::Unknown);
+        assert_eq!(diagnostics[2].source.id, "java/extractor/compile-error");
+        assert_eq!(diagnostics[2].message(), "Failed to compile 2 files");
+        assert_eq!(
+            diagnostics[2].location.as_ref().unwrap().file.as_deref(),
+            Some("src/Main.java")
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+        assert!(db.diagnostics().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_lines_of_code_by_language() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        std::fs::write(
+            root.join("codeql-database.yml"),
+            "primaryLanguage: javascript\nbaselineLinesOfCode: 150\nunicodeNewlines: false\ncolumnKind: utf16\n",
+        )
+        .unwrap();
+
+        let db = CodeQLDatabase::init()
+            .name("test")
+            .path(root.display().to_string())
+            .build()
+            .unwrap();
+        let lines = db.lines_of_code_by_language();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines.get("javascript"), Some(&150));
+
+        std::fs::write(
+            root.join("baseline-info.json"),
+            r#"{"languages": {
+                "javascript": {"displayName": "JavaScript", "files": ["a.js"], "linesOfCode": 100, "name": "javascript"},
+                "typescript": {"displayName": "TypeScript", "files": ["b.ts"], "linesOfCode": 50, "name": "typescript"}
+            }}"#,
+        )
+        .unwrap();
+        let lines = db.lines_of_code_by_language();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines.get("javascript"), Some(&100));
+        assert_eq!(lines.get("typescript"), Some(&50));
+
+        let diagnostic = root.join("diagnostic");
+        std::fs::create_dir_all(&diagnostic).unwrap();
+        std::fs::write(
+            diagnostic.join("extractor.jsonl"),
+            concat!(
+                r#"{"source": {"id": "a", "name": "A"}, "severity": "error"}"#,
+                "\n",
+                r#"{"source": {"id": "b", "name": "B"}, "severity": "warning"}"#,
+                "\n",
+                r#"{"source": {"id": "c", "name": "C"}, "severity": "error"}"#,
+                "\n"
+            ),
+        )
+        .unwrap();
+        let metrics = db.metrics().unwrap();
+        assert_eq!(metrics.lines_of_code, 150);
+        assert_eq!(metrics.files, 2);
+        assert_eq!(metrics.extraction_errors, 2);
+        assert_eq!(metrics.extraction_warnings, 1);
+    }
 }
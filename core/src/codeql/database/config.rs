@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
-use crate::GHASError;
+use crate::{codeql::BuildMode, GHASError};
 
 /// CodeQL Database Configuration which is stored in the database directory
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -25,6 +25,12 @@ pub struct CodeQLDatabaseConfig {
     /// Database creation metadata
     #[serde(rename = "creationMetadata")]
     pub creation_metadata: Option<CodeQLDatabaseConfigMetadata>,
+    /// If the database has been finalised (older CLIs might not record this)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finalised: Option<bool>,
+    /// Build mode used to create the database
+    #[serde(rename = "buildMode", default, skip_serializing_if = "Option::is_none")]
+    pub build_mode: Option<BuildMode>,
 }
 
 impl CodeQLDatabaseConfig {
@@ -0,0 +1,134 @@
+use std::path::Path;
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::GHASError;
+
+/// CodeQL Diagnostic (written by the extractors / CLI in the database `diagnostic` directory)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// Timestamp of the diagnostic
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    /// Source of the diagnostic
+    pub source: DiagnosticSource,
+    /// Severity of the diagnostic
+    #[serde(default)]
+    pub severity: DiagnosticSeverity,
+    /// Markdown message
+    #[serde(rename = "markdownMessage", default)]
+    pub markdown_message: Option<String>,
+    /// Plain text message
+    #[serde(rename = "plaintextMessage", default)]
+    pub plaintext_message: Option<String>,
+    /// Location the diagnostic is about (if any)
+    #[serde(default)]
+    pub location: Option<DiagnosticLocation>,
+}
+
+impl Diagnostic {
+    /// Get the message of the diagnostic (plain text, else markdown)
+    pub fn message(&self) -> &str {
+        self.plaintext_message
+            .as_deref()
+            .or(self.markdown_message.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Load all the diagnostics from a directory (`.json` and `.jsonl` files).
+    /// Malformed diagnostics are logged and skipped.
+    pub(crate) fn load_dir(path: &Path) -> Result<Vec<Diagnostic>, GHASError> {
+        let mut diagnostics = Vec::new();
+        if !path.exists() {
+            return Ok(diagnostics);
+        }
+
+        for entry in WalkDir::new(path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let extension = entry.path().extension().and_then(|e| e.to_str());
+            match extension {
+                Some("json") => {
+                    debug!("Loading diagnostic :: {}", entry.path().display());
+                    let data = std::fs::read_to_string(entry.path())?;
+                    match serde_json::from_str(&data) {
+                        Ok(diagnostic) => diagnostics.push(diagnostic),
+                        Err(err) => warn!(
+                            "Skipping malformed diagnostic :: {} ({})",
+                            entry.path().display(),
+                            err
+                        ),
+                    }
+                }
+                Some("jsonl") => {
+                    debug!("Loading diagnostics :: {}", entry.path().display());
+                    let data = std::fs::read_to_string(entry.path())?;
+                    for line in data.lines().filter(|l| !l.trim().is_empty()) {
+                        match serde_json::from_str(line) {
+                            Ok(diagnostic) => diagnostics.push(diagnostic),
+                            Err(err) => warn!(
+                                "Skipping malformed diagnostic :: {} ({})",
+                                entry.path().display(),
+                                err
+                            ),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(diagnostics)
+    }
+}
+
+/// CodeQL Diagnostic Source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticSource {
+    /// Identifier of the diagnostic (e.g. `cpp/autobuilder/compilation-failed`)
+    pub id: String,
+    /// Name of the diagnostic
+    pub name: String,
+    /// Name of the extractor which created the diagnostic
+    #[serde(rename = "extractorName", default)]
+    pub extractor_name: Option<String>,
+}
+
+/// CodeQL Diagnostic Severity
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    /// Note
+    #[default]
+    Note,
+    /// Warning
+    Warning,
+    /// Error
+    Error,
+    /// Unknown severity
+    #[serde(other)]
+    Unknown,
+}
+
+/// CodeQL Diagnostic Location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticLocation {
+    /// File path (relative to the source root)
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Start Line
+    #[serde(rename = "startLine", default)]
+    pub start_line: Option<usize>,
+    /// Start Column
+    #[serde(rename = "startColumn", default)]
+    pub start_column: Option<usize>,
+    /// End Line
+    #[serde(rename = "endLine", default)]
+    pub end_line: Option<usize>,
+    /// End Column
+    #[serde(rename = "endColumn", default)]
+    pub end_column: Option<usize>,
+}
@@ -1,8 +1,15 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use log::debug;
 
 use crate::{
-    codeql::{database::queries::CodeQLQueries, CodeQLLanguage},
-    utils::sarif::Sarif,
+    codeql::{
+        database::{
+            config::CodeQLDatabaseConfig, metrics::DatabaseMetrics, queries::CodeQLQueries,
+        },
+        BuildMode, CodeQLExtractor, CodeQLLanguage,
+    },
+    utils::{sarif::Sarif, spans::SpanTimer},
     CodeQL, CodeQLDatabase, CodeQLDatabases, GHASError,
 };
 
@@ -23,6 +30,18 @@ pub struct CodeQLDatabaseHandler<'db, 'ql> {
     output_format: String,
     /// Overwrite the database if it exists
     overwrite: bool,
+    /// SARIF Category for the analysis
+    category: Option<String>,
+    /// Threat Model to use for the analysis (e.g. `remote`, `local`)
+    threat_model: Option<String>,
+    /// Download any missing query packs before the analysis
+    download_packs: bool,
+    /// Build mode used to create the database
+    build_mode: Option<BuildMode>,
+    /// Query suite to run (e.g. `security-extended`)
+    suite: Option<String>,
+    /// Query pack the suite is resolved against (scope, name)
+    suite_pack: Option<(String, String)>,
 }
 
 impl<'db, 'ql> CodeQLDatabaseHandler<'db, 'ql> {
@@ -37,6 +56,12 @@ impl<'db, 'ql> CodeQLDatabaseHandler<'db, 'ql> {
             output: CodeQLDatabaseHandler::default_results(database),
             output_format: String::from("sarif-latest"),
             overwrite: false,
+            category: None,
+            threat_model: None,
+            download_packs: false,
+            build_mode: None,
+            suite: None,
+            suite_pack: None,
         }
     }
 
@@ -58,54 +83,274 @@ impl<'db, 'ql> CodeQLDatabaseHandler<'db, 'ql> {
         self
     }
 
+    /// Set the query suite to run (e.g. `security-extended`), replacing the queries.
+    ///
+    /// Suite names are resolved to `codeql-suites/<language>-<suite>.qls` in the
+    /// language's query pack (`codeql/<language>-queries`, see [`suite_pack`](Self::suite_pack)).
+    /// A path to a `.qls` file is used as-is within the pack.
+    pub fn suite(mut self, suite: impl Into<String>) -> Self {
+        self.suite = Some(suite.into());
+        self
+    }
+
+    /// Set the query pack the suite is resolved against (e.g. a custom query pack
+    /// that ships the standard suite names)
+    pub fn suite_pack(mut self, scope: impl Into<String>, name: impl Into<String>) -> Self {
+        self.suite_pack = Some((scope.into(), name.into()));
+        self
+    }
+
+    /// Get the queries to run (the suite if set, else the queries)
+    fn resolved_queries(&self) -> Result<CodeQLQueries, GHASError> {
+        let Some(suite) = &self.suite else {
+            return Ok(self.queries.clone());
+        };
+        let language = self.database.language();
+        let (scope, name) = match &self.suite_pack {
+            Some((scope, name)) => (scope.clone(), name.clone()),
+            None => (String::from("codeql"), format!("{}-queries", language)),
+        };
+        let path = if suite.ends_with(".qls") {
+            suite.clone()
+        } else {
+            format!("codeql-suites/{}-{}.qls", language, suite)
+        };
+
+        CodeQLQueries::builder()
+            .scope(scope)
+            .name(name)
+            .suite(path)
+            .build()
+    }
+
     /// Overwrite the database if it exists
     pub fn overwrite(mut self) -> Self {
         self.overwrite = true;
         self
     }
 
+    /// Set the SARIF category for the analysis.
+    ///
+    /// The category can contain the `{language}` placeholder which is replaced with
+    /// the database language (e.g. `/language:{language}`) so analyses for multiple
+    /// languages don't collide when uploaded.
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Set the threat model for the analysis (e.g. `remote`, `local`)
+    pub fn threat_model(mut self, threat_model: impl Into<String>) -> Self {
+        self.threat_model = Some(threat_model.into());
+        self
+    }
+
+    /// Download any missing query packs before running the analysis
+    pub fn download_packs(mut self, download: bool) -> Self {
+        self.download_packs = download;
+        self
+    }
+
+    /// Set the build mode used to create the database (`--build-mode`).
+    ///
+    /// The mode is checked against the modes the language's extractor supports
+    /// before the database is created.
+    pub fn build_mode(mut self, build_mode: BuildMode) -> Self {
+        self.build_mode = Some(build_mode);
+        self
+    }
+
     /// Create a new CodeQL Database using the provided database
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "codeql.database.create",
+            skip_all,
+            fields(
+                database = %self.database.name(),
+                language = %self.database.language(),
+                duration_ms = tracing::field::Empty
+            )
+        )
+    )]
     pub async fn create(&mut self) -> Result<(), GHASError> {
+        let _timer = SpanTimer::start();
         let args = self.create_cmd()?;
 
+        if self.build_mode.is_some() {
+            match self
+                .codeql
+                .resolve_extractor(self.database.language())
+                .await
+            {
+                Ok(extractor) => self.check_build_mode(&extractor)?,
+                Err(e) => debug!(
+                    "Unable to resolve extractor to check build mode :: {}",
+                    e.display_chain()
+                ),
+            }
+        }
+
         // Create path
         if !self.database.path().exists() {
             std::fs::create_dir_all(self.database.path())?;
         }
 
-        self.codeql.run(args).await?;
+        self.codeql
+            .run(args.iter().map(|a| a.as_str()).collect())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Initialize the database without extracting the source code
+    /// (`codeql database init`).
+    ///
+    /// This is the first step of the manual flow: [`init`](Self::init), then
+    /// [`autobuild`](Self::autobuild) (or tracing your own build), and finally
+    /// [`finalize`](Self::finalize).
+    pub async fn init(&mut self) -> Result<(), GHASError> {
+        let args = self.init_cmd()?;
 
+        if !self.database.path().exists() {
+            std::fs::create_dir_all(self.database.path())?;
+        }
+
+        self.codeql
+            .run(args.iter().map(|a| a.as_str()).collect())
+            .await?;
         Ok(())
     }
 
+    /// Build the source code using CodeQL's autobuilder for the database language,
+    /// tracing the build into an initialized database (`codeql database trace-command`).
+    ///
+    /// Interpreted languages (e.g. python) don't need to be built so this does nothing.
+    pub async fn autobuild(&self) -> Result<(), GHASError> {
+        if !self.database.language.is_compiled() {
+            debug!(
+                "Skipping autobuild for interpreted language :: {}",
+                self.database.language()
+            );
+            return Ok(());
+        }
+
+        let root = self
+            .codeql
+            .resolve_extractor_root(self.database.language())
+            .await?;
+        let args = self.autobuild_cmd(&CodeQLDatabaseHandler::autobuilder(&root))?;
+
+        self.codeql
+            .run(args.iter().map(|a| a.as_str()).collect())
+            .await?;
+        Ok(())
+    }
+
+    /// Finalize an initialized database (`codeql database finalize`)
+    pub async fn finalize(&self) -> Result<(), GHASError> {
+        let args = self.finalize_cmd()?;
+
+        self.codeql
+            .run(args.iter().map(|a| a.as_str()).collect())
+            .await?;
+        Ok(())
+    }
+
+    /// Path to the autobuild script of an extractor
+    fn autobuilder(extractor_root: &Path) -> PathBuf {
+        let script = if cfg!(windows) {
+            "autobuild.cmd"
+        } else {
+            "autobuild.sh"
+        };
+        extractor_root.join("tools").join(script)
+    }
+
+    /// Check the extractor supports the requested build mode
+    fn check_build_mode(&self, extractor: &CodeQLExtractor) -> Result<(), GHASError> {
+        match &self.build_mode {
+            Some(mode) if !extractor.supports(mode) => Err(GHASError::Unsupported(format!(
+                "The `{}` extractor does not support build mode `{}` (supported: {})",
+                extractor.name,
+                mode,
+                extractor
+                    .build_modes()
+                    .iter()
+                    .map(|m| m.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ))),
+            _ => Ok(()),
+        }
+    }
+
     /// Create the command to create the database
-    fn create_cmd(&self) -> Result<Vec<&str>, GHASError> {
-        let mut args = vec!["database", "create"];
+    fn create_cmd(&self) -> Result<Vec<String>, GHASError> {
+        self.database_cmd("create")
+    }
+
+    /// Create the command to initialize the database
+    fn init_cmd(&self) -> Result<Vec<String>, GHASError> {
+        self.database_cmd("init")
+    }
+
+    /// Create the command to run the autobuilder in the database source root
+    fn autobuild_cmd(&self, autobuilder: &Path) -> Result<Vec<String>, GHASError> {
+        let source = match &self.database.source {
+            Some(source) => path_arg(source, "source root")?,
+            None => return Err(GHASError::Config("No source root provided".to_string())),
+        };
+        Ok(vec![
+            String::from("database"),
+            String::from("trace-command"),
+            format!("--working-dir={}", source),
+            path_arg(&self.database.path, "database path")?,
+            String::from("--"),
+            path_arg(autobuilder, "autobuilder path")?,
+        ])
+    }
+
+    /// Create the command to finalize the database
+    fn finalize_cmd(&self) -> Result<Vec<String>, GHASError> {
+        Ok(vec![
+            String::from("database"),
+            String::from("finalize"),
+            path_arg(&self.database.path, "database path")?,
+        ])
+    }
+
+    /// Create the `database create` / `database init` command
+    fn database_cmd(&self, subcommand: &str) -> Result<Vec<String>, GHASError> {
+        let mut args = vec![String::from("database"), subcommand.to_string()];
 
         // Check if language is set
         if self.database.language != CodeQLLanguage::None {
-            args.extend(vec!["-l", &self.database.language()]);
+            args.extend(vec![
+                String::from("-l"),
+                self.database.language().to_string(),
+            ]);
         } else {
-            return Err(GHASError::CodeQLDatabaseError(
-                "No language provided".to_string(),
-            ));
+            return Err(GHASError::Config("No language provided".to_string()));
         }
         // Add source root
         if let Some(source) = &self.database.source {
-            args.extend(vec!["-s", source.to_str().expect("Invalid Source Root")]);
+            args.extend(vec![String::from("-s"), path_arg(source, "source root")?]);
         } else {
-            return Err(GHASError::CodeQLDatabaseError(
-                "No source root provided".to_string(),
-            ));
+            return Err(GHASError::Config("No source root provided".to_string()));
+        }
+        if let Some(build_mode) = &self.build_mode {
+            args.push(format!("--build-mode={}", build_mode));
         }
         // Overwrite the database if it exists
         if self.overwrite {
-            args.push("--overwrite");
+            args.push(String::from("--overwrite"));
         }
+        self.codeql.add_search_path(&mut args);
+        self.codeql.add_additional_packs(&mut args);
 
         // Add the path to the database
-        let path = self.database.path.to_str().expect("Invalid Database Path");
-        args.push(path);
+        args.push(path_arg(&self.database.path, "database path")?);
 
         Ok(args)
     }
@@ -132,31 +377,491 @@ impl<'db, 'ql> CodeQLDatabaseHandler<'db, 'ql> {
 
         path
     }
-    /// Analyze the database
+
+    /// Get the metrics of the database (lines of code, files extracted and extraction
+    /// errors), see [`CodeQLDatabase::metrics`].
+    ///
+    /// Combined with the number of SARIF results, this gives the coverage of a scan.
+    pub fn metrics(&self) -> Result<DatabaseMetrics, GHASError> {
+        self.database.metrics()
+    }
+
+    /// Analyze the database. The database must have been created and finalised.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "codeql.database.analyze",
+            skip_all,
+            fields(
+                database = %self.database.name(),
+                language = %self.database.language(),
+                duration_ms = tracing::field::Empty
+            )
+        )
+    )]
     pub async fn analyze(&self) -> Result<Sarif, GHASError> {
+        let _timer = SpanTimer::start();
+        // Read the on-disk configuration as the database might have just been created
+        let config_path = self.database.configuration_path();
+        if !config_path.exists() {
+            return Err(GHASError::CodeQLDatabaseError(format!(
+                "Database has not been created: {}",
+                self.database.path().display()
+            )));
+        }
+        let config = CodeQLDatabaseConfig::read(&config_path)?;
+        if config.finalised == Some(false) {
+            return Err(GHASError::CodeQLDatabaseError(format!(
+                "Database is not finalised and can not be analyzed: {}",
+                self.database.path().display()
+            )));
+        }
+
         let args = self.analyze_cmd()?;
 
-        self.codeql.run(args).await?;
+        self.codeql
+            .run(args.iter().map(|a| a.as_str()).collect())
+            .await?;
         Sarif::try_from(self.output.clone())
     }
 
-    pub(crate) fn analyze_cmd(&self) -> Result<Vec<&str>, GHASError> {
-        let mut args = vec!["database", "analyze"];
+    /// Interpret the query results already stored in the database into the given
+    /// format (e.g. `sarif-latest`, `csv`) without re-running the queries
+    /// (`codeql database interpret-results`).
+    ///
+    /// The handler's queries and category are used. The threat model is not used
+    /// as it only changes how the queries are evaluated.
+    pub async fn interpret_results(
+        &self,
+        output: impl Into<PathBuf>,
+        format: impl Into<String>,
+    ) -> Result<(), GHASError> {
+        let args = self.interpret_results_cmd(&output.into(), &format.into())?;
+
+        self.codeql
+            .run(args.iter().map(|a| a.as_str()).collect())
+            .await?;
+        Ok(())
+    }
+
+    fn interpret_results_cmd(&self, output: &Path, format: &str) -> Result<Vec<String>, GHASError> {
+        let mut args = vec![String::from("database"), String::from("interpret-results")];
+
+        args.extend(vec![
+            String::from("--output"),
+            path_arg(output, "output path")?,
+        ]);
+        args.extend(vec![String::from("--format"), format.to_string()]);
+
+        if let Some(category) = &self.category {
+            let category = category.replace("{language}", self.database.language());
+            args.push(format!("--sarif-category={}", category));
+        }
+        self.codeql.add_additional_packs(&mut args);
+
+        args.push(path_arg(&self.database.path, "database path")?);
+
+        let queries = self.resolved_queries()?.to_string();
+        if !queries.is_empty() {
+            args.push(queries);
+        }
+
+        Ok(args)
+    }
+
+    pub(crate) fn analyze_cmd(&self) -> Result<Vec<String>, GHASError> {
+        let mut args = vec![String::from("database"), String::from("analyze")];
 
         // Output and Format
-        if let Some(path) = &self.output.to_str() {
-            args.extend(vec!["--output", path]);
-        } else {
-            return Err(GHASError::CodeQLDatabaseError(
-                "No output path provided".to_string(),
-            ));
+        args.extend(vec![
+            String::from("--output"),
+            path_arg(&self.output, "output path")?,
+        ]);
+        args.extend(vec![String::from("--format"), self.output_format.clone()]);
+
+        if let Some(category) = &self.category {
+            let category = category.replace("{language}", self.database.language());
+            args.push(format!("--sarif-category={}", category));
+        }
+        if let Some(threat_model) = &self.threat_model {
+            args.push(format!("--threat-model={}", threat_model));
+        }
+        if self.download_packs && !self.codeql.is_offline() {
+            args.push(String::from("--download"));
         }
-        args.extend(vec!["--format", self.output_format.as_str()]);
+        self.codeql.add_search_path(&mut args);
+        self.codeql.add_additional_packs(&mut args);
 
         // Add the path to the database
-        let path = self.database.path.to_str().expect("Invalid Database Path");
-        args.push(path);
+        args.push(path_arg(&self.database.path, "database path")?);
+
+        // Queries / Packs / Suites
+        let queries = self.resolved_queries()?.to_string();
+        if !queries.is_empty() {
+            args.push(queries);
+        }
 
         Ok(args)
     }
 }
+
+/// Convert a path to a CodeQL CLI argument (the path must be valid UTF-8)
+fn path_arg(path: &Path, name: &str) -> Result<String, GHASError> {
+    path.to_str().map(|p| p.to_string()).ok_or_else(|| {
+        GHASError::CodeQLDatabaseError(format!("Invalid {} (not UTF-8): {}", name, path.display()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database() -> CodeQLDatabase {
+        CodeQLDatabase::init()
+            .name("test-repo")
+            .language("python")
+            .path(String::from("/tmp/codeql/python-test-repo"))
+            .source(String::from("/tmp/test-repo"))
+            .build()
+            .expect("Failed to build database")
+    }
+
+    #[test]
+    fn test_build_mode() {
+        let codeql = CodeQL::default();
+        let database = database();
+        let extractor = CodeQLExtractor::load(
+            "name: python\ndisplay_name: Python\nversion: 1.0.0\nfile_types: []\nbuild_modes:\n  - manual\n",
+        )
+        .unwrap();
+
+        let handler = codeql.database(&database).build_mode(BuildMode::None);
+        assert!(handler
+            .create_cmd()
+            .unwrap()
+            .contains(&String::from("--build-mode=none")));
+        let error = handler.check_build_mode(&extractor).unwrap_err();
+        assert!(error.to_string().contains("supported: manual"));
+
+        let handler = codeql.database(&database).build_mode(BuildMode::Manual);
+        assert!(handler.check_build_mode(&extractor).is_ok());
+    }
+
+    #[test]
+    fn test_create_cmd() {
+        let codeql = CodeQL::default();
+        let database = database();
+
+        let args = codeql.database(&database).overwrite().create_cmd().unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "database",
+                "create",
+                "-l",
+                "python",
+                "-s",
+                "/tmp/test-repo",
+                "--overwrite",
+                "/tmp/codeql/python-test-repo"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_path_cmds() {
+        let codeql = CodeQL::init()
+            .search_path("/opt/extractors")
+            .search_path("/opt/packs")
+            .build()
+            .await
+            .unwrap();
+        let database = database();
+        let handler = codeql
+            .database(&database)
+            .output(PathBuf::from("/tmp/results.sarif"));
+        let search_path = format!(
+            "--search-path={}",
+            std::env::join_paths(["/opt/extractors", "/opt/packs"])
+                .unwrap()
+                .to_string_lossy()
+        );
+
+        let args = handler.create_cmd().unwrap();
+        assert_eq!(
+            args,
+            vec![
+                String::from("database"),
+                String::from("create"),
+                String::from("-l"),
+                String::from("python"),
+                String::from("-s"),
+                String::from("/tmp/test-repo"),
+                search_path.clone(),
+                String::from("/tmp/codeql/python-test-repo"),
+            ]
+        );
+        assert!(handler.init_cmd().unwrap().contains(&search_path));
+
+        let args = handler.analyze_cmd().unwrap();
+        assert_eq!(
+            args,
+            vec![
+                String::from("database"),
+                String::from("analyze"),
+                String::from("--output"),
+                String::from("/tmp/results.sarif"),
+                String::from("--format"),
+                String::from("sarif-latest"),
+                search_path,
+                String::from("/tmp/codeql/python-test-repo"),
+                String::from("codeql/python-queries"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_manual_cmds() {
+        let codeql = CodeQL::default();
+        let database = CodeQLDatabase::init()
+            .name("test-repo")
+            .language("java")
+            .path(String::from("/tmp/codeql/java-test-repo"))
+            .source(String::from("/tmp/test-repo"))
+            .build()
+            .unwrap();
+        let handler = codeql.database(&database);
+
+        let args = handler.init_cmd().unwrap();
+        assert_eq!(&args[..2], &["database", "init"]);
+        assert_eq!(args.last().unwrap(), "/tmp/codeql/java-test-repo");
+
+        let autobuilder = CodeQLDatabaseHandler::autobuilder(Path::new("/opt/codeql/java"));
+        assert!(autobuilder.starts_with("/opt/codeql/java/tools"));
+        let args = handler.autobuild_cmd(&autobuilder).unwrap();
+        assert_eq!(
+            &args[..5],
+            &[
+                "database",
+                "trace-command",
+                "--working-dir=/tmp/test-repo",
+                "/tmp/codeql/java-test-repo",
+                "--"
+            ]
+        );
+
+        assert_eq!(
+            handler.finalize_cmd().unwrap(),
+            vec!["database", "finalize", "/tmp/codeql/java-test-repo"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_autobuild_interpreted() {
+        // Python doesn't need to be built so the CodeQL CLI is never called
+        let codeql = CodeQL::default();
+        let database = database();
+        assert!(codeql.database(&database).autobuild().await.is_ok());
+    }
+
+    #[test]
+    fn test_analyze_cmd() {
+        let codeql = CodeQL::default();
+        let database = database();
+
+        let args = codeql
+            .database(&database)
+            .output(PathBuf::from("/tmp/results.sarif"))
+            .category("/language:python")
+            .threat_model("local")
+            .download_packs(true)
+            .analyze_cmd()
+            .unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "database",
+                "analyze",
+                "--output",
+                "/tmp/results.sarif",
+                "--format",
+                "sarif-latest",
+                "--sarif-category=/language:python",
+                "--threat-model=local",
+                "--download",
+                "/tmp/codeql/python-test-repo",
+                "codeql/python-queries"
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_paths() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let codeql = CodeQL::default();
+        let odd = PathBuf::from(OsStr::from_bytes(b"/tmp/codeql/\xff\xfe-db"));
+
+        let mut database = database();
+        database.path = odd.clone();
+        let handler = codeql.database(&database);
+        let error = handler.create_cmd().unwrap_err();
+        assert!(error.to_string().contains("database path"));
+        assert!(handler.analyze_cmd().is_err());
+        assert!(handler.finalize_cmd().is_err());
+
+        let mut database = self::database();
+        database.source = Some(odd.clone());
+        let error = codeql.database(&database).create_cmd().unwrap_err();
+        assert!(error.to_string().contains("source root"));
+
+        let database = self::database();
+        assert!(codeql
+            .database(&database)
+            .output(odd)
+            .analyze_cmd()
+            .is_err());
+    }
+
+    #[test]
+    fn test_suite() {
+        let codeql = CodeQL::default();
+        let database = database();
+
+        let args = codeql
+            .database(&database)
+            .suite("security-extended")
+            .analyze_cmd()
+            .unwrap();
+        assert_eq!(
+            args.last().unwrap(),
+            "codeql/python-queries:codeql-suites/python-security-extended.qls"
+        );
+
+        let args = codeql
+            .database(&database)
+            .suite("security-extended")
+            .suite_pack("octo-org", "python-queries")
+            .analyze_cmd()
+            .unwrap();
+        assert_eq!(
+            args.last().unwrap(),
+            "octo-org/python-queries:codeql-suites/python-security-extended.qls"
+        );
+
+        let args = codeql
+            .database(&database)
+            .suite_pack("octo-org", "custom-queries")
+            .suite("suites/security.qls")
+            .analyze_cmd()
+            .unwrap();
+        assert_eq!(
+            args.last().unwrap(),
+            "octo-org/custom-queries:suites/security.qls"
+        );
+
+        // Without a suite the queries are used
+        let args = codeql
+            .database(&database)
+            .suite_pack("octo-org", "custom-queries")
+            .analyze_cmd()
+            .unwrap();
+        assert_eq!(args.last().unwrap(), "codeql/python-queries");
+    }
+
+    #[test]
+    fn test_interpret_results_cmd() {
+        let codeql = CodeQL::default();
+        let database = database();
+
+        let args = codeql
+            .database(&database)
+            .category("/language:{language}")
+            .threat_model("local")
+            .interpret_results_cmd(Path::new("/tmp/results.csv"), "csv")
+            .unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "database",
+                "interpret-results",
+                "--output",
+                "/tmp/results.csv",
+                "--format",
+                "csv",
+                "--sarif-category=/language:python",
+                "/tmp/codeql/python-test-repo",
+                "codeql/python-queries"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_analyze_cmd_category_template() {
+        let codeql = CodeQL::default();
+        let database = database();
+
+        let handler = codeql
+            .database(&database)
+            .output(PathBuf::from("/tmp/results.sarif"))
+            .category("/language:{language}");
+
+        let args = handler.analyze_cmd().unwrap();
+        assert!(args.contains(&String::from("--sarif-category=/language:python")));
+
+        // The category is only used when analyzing the database
+        let args = handler.create_cmd().unwrap();
+        assert!(!args.iter().any(|a| a.starts_with("--sarif-category")));
+    }
+
+    #[tokio::test]
+    async fn test_analyze_cmd_offline() {
+        let codeql = CodeQL::init()
+            .offline(true)
+            .env("CODEQL_RAM", "2048")
+            .build()
+            .await
+            .unwrap();
+        let database = database();
+
+        assert!(codeql.is_offline());
+        assert_eq!(codeql.env().get("CODEQL_RAM"), Some(&String::from("2048")));
+
+        let args = codeql
+            .database(&database)
+            .output(PathBuf::from("/tmp/results.sarif"))
+            .download_packs(true)
+            .analyze_cmd()
+            .unwrap();
+        assert!(!args.contains(&String::from("--download")));
+    }
+
+    #[tokio::test]
+    async fn test_additional_packs() {
+        let codeql = CodeQL::init()
+            .additional_packs(String::from("/tmp/packs"))
+            .additional_packs(String::from("/tmp/other-packs"))
+            .build()
+            .await
+            .unwrap();
+        let database = database();
+        let handler = codeql
+            .database(&database)
+            .output(PathBuf::from("/tmp/results.sarif"));
+
+        let args = handler.analyze_cmd().unwrap();
+        assert!(args.contains(&String::from(
+            "--additional-packs=/tmp/packs:/tmp/other-packs"
+        )));
+        let args = handler.create_cmd().unwrap();
+        assert!(args.contains(&String::from(
+            "--additional-packs=/tmp/packs:/tmp/other-packs"
+        )));
+
+        // No additional packs set
+        let codeql = CodeQL::default();
+        let args = codeql.database(&database).analyze_cmd().unwrap();
+        assert!(!args.iter().any(|a| a.starts_with("--additional-packs")));
+    }
+}
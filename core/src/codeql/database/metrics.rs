@@ -0,0 +1,18 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Metrics of a CodeQL database (lines of code, files and extraction diagnostics)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatabaseMetrics {
+    /// Lines of code in the database (all languages)
+    pub lines_of_code: usize,
+    /// Lines of code for each language
+    pub lines_of_code_by_language: HashMap<String, usize>,
+    /// Number of files extracted (from `baseline-info.json`, 0 if it isn't present)
+    pub files: usize,
+    /// Number of extraction errors (error diagnostics)
+    pub extraction_errors: usize,
+    /// Number of extraction warnings (warning diagnostics)
+    pub extraction_warnings: usize,
+}
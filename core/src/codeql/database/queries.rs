@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+use std::{fmt::Display, path::PathBuf};
+
+use crate::GHASError;
 
 /// A collection of CodeQL Queries
 /// scope/name@range:path
@@ -19,10 +21,34 @@ impl CodeQLQueries {
             ..Default::default()
         }
     }
+
+    /// Create a new CodeQL Queries builder
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ghastoolkit::codeql::database::queries::CodeQLQueries;
+    ///
+    /// let queries = CodeQLQueries::builder()
+    ///     .scope("codeql")
+    ///     .name("python-queries")
+    ///     .range("^1.0.0")
+    ///     .suite("codeql-suites/python-code-scanning.qls")
+    ///     .build()
+    ///     .expect("Invalid queries");
+    ///
+    /// assert_eq!(
+    ///     queries.to_string(),
+    ///     "codeql/python-queries@^1.0.0:codeql-suites/python-code-scanning.qls"
+    /// );
+    /// ```
+    pub fn builder() -> CodeQLQueriesBuilder {
+        CodeQLQueriesBuilder::default()
+    }
 }
 
-impl ToString for CodeQLQueries {
-    fn to_string(&self) -> String {
+impl Display for CodeQLQueries {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut query = String::new();
 
         // Pack mode
@@ -43,14 +69,99 @@ impl ToString for CodeQLQueries {
         // Path
         if let Some(path) = &self.path {
             if query.is_empty() {
-                query = path.to_str().unwrap().to_string();
+                query = path.to_string_lossy().to_string();
             } else {
                 query += ":";
-                query += path.to_str().unwrap();
+                query += &path.to_string_lossy();
             }
         }
 
-        query
+        write!(f, "{}", query)
+    }
+}
+
+/// CodeQL Queries Builder
+#[derive(Debug, Default, Clone)]
+pub struct CodeQLQueriesBuilder {
+    scope: Option<String>,
+    name: Option<String>,
+    range: Option<String>,
+    suite: Option<PathBuf>,
+}
+
+impl CodeQLQueriesBuilder {
+    /// Set the pack scope (e.g. `codeql`)
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    /// Set the pack name (e.g. `python-queries`)
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the pack version range (e.g. `^1.0.0`)
+    pub fn range(mut self, range: impl Into<String>) -> Self {
+        self.range = Some(range.into());
+        self
+    }
+
+    /// Set the query suite (`.qls`) or query (`.ql`) path
+    pub fn suite(mut self, suite: impl Into<PathBuf>) -> Self {
+        self.suite = Some(suite.into());
+        self
+    }
+
+    /// Validate and build the CodeQL Queries
+    pub fn build(&self) -> Result<CodeQLQueries, GHASError> {
+        for part in [&self.scope, &self.name].into_iter().flatten() {
+            if part.is_empty() || part.contains(['/', '@', ':']) {
+                return Err(GHASError::Parse(format!(
+                    "Invalid pack scope / name: '{}'",
+                    part
+                )));
+            }
+        }
+        if self.scope.is_some() != self.name.is_some() {
+            return Err(GHASError::Config(String::from(
+                "Both the pack scope and name are required",
+            )));
+        }
+        if let Some(range) = &self.range {
+            if self.name.is_none() {
+                return Err(GHASError::Config(String::from(
+                    "A version range requires a pack",
+                )));
+            }
+            semver::VersionReq::parse(range).map_err(|e| {
+                GHASError::Parse(format!("Invalid version range '{}': {}", range, e))
+            })?;
+        }
+        if let Some(suite) = &self.suite {
+            match suite.extension().and_then(|e| e.to_str()) {
+                Some("qls") | Some("ql") => {}
+                _ => {
+                    return Err(GHASError::Unsupported(format!(
+                        "Suite must be a `.qls` or `.ql` file: {}",
+                        suite.display()
+                    )))
+                }
+            }
+        }
+        if self.name.is_none() && self.suite.is_none() {
+            return Err(GHASError::Config(String::from(
+                "A pack or suite is required",
+            )));
+        }
+
+        Ok(CodeQLQueries {
+            scope: self.scope.clone(),
+            name: self.name.clone(),
+            range: self.range.clone(),
+            path: self.suite.clone(),
+        })
     }
 }
 
@@ -145,6 +256,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_builder() {
+        let queries = CodeQLQueries::builder()
+            .scope("codeql")
+            .name("python-queries")
+            .range("0.9.0")
+            .suite("codeql-suites/python-code-scanning.qls")
+            .build()
+            .unwrap();
+        assert_eq!(
+            queries.to_string(),
+            "codeql/python-queries@0.9.0:codeql-suites/python-code-scanning.qls"
+        );
+
+        let queries = CodeQLQueries::builder()
+            .suite("./queries/custom.ql")
+            .build()
+            .unwrap();
+        assert_eq!(queries.to_string(), "./queries/custom.ql");
+
+        // Invalid
+        assert!(CodeQLQueries::builder().build().is_err());
+        assert!(CodeQLQueries::builder().scope("codeql").build().is_err());
+        assert!(CodeQLQueries::builder()
+            .scope("codeql")
+            .name("python-queries")
+            .range("not-a-range")
+            .build()
+            .is_err());
+        assert!(CodeQLQueries::builder()
+            .scope("codeql")
+            .name("python/queries")
+            .build()
+            .is_err());
+        assert!(CodeQLQueries::builder()
+            .scope("codeql")
+            .name("python-queries")
+            .suite("codeql-suites/README.md")
+            .build()
+            .is_err());
+    }
+
     #[test]
     fn test_pack_range() {
         let queries = CodeQLQueries::from("codeql/python-queries@0.9.0");
@@ -1,9 +1,18 @@
-use std::path::PathBuf;
+use std::{
+    collections::HashSet,
+    ffi::OsStr,
+    ops::Index,
+    path::{Path, PathBuf},
+};
 
 use log::debug;
 use walkdir::WalkDir;
 
-use crate::codeql::database::CodeQLDatabase;
+use crate::{
+    codeql::{database::CodeQLDatabase, CodeQLLanguage},
+    utils::spans::{self, SpanTimer},
+    GHASError, GitHub, Repository,
+};
 
 /// A list of CodeQL databases
 #[derive(Debug, Clone)]
@@ -11,14 +20,6 @@ pub struct CodeQLDatabases {
     databases: Vec<CodeQLDatabase>,
 }
 
-impl Iterator for CodeQLDatabases {
-    type Item = CodeQLDatabase;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.databases.pop()
-    }
-}
-
 impl CodeQLDatabases {
     /// Create a new list of databases
     pub fn new() -> Self {
@@ -39,6 +40,144 @@ impl CodeQLDatabases {
     pub fn len(&self) -> usize {
         self.databases.len()
     }
+    /// Iterate over the databases (in the order they were added) without consuming the list
+    pub fn iter(&self) -> impl Iterator<Item = &CodeQLDatabase> {
+        self.databases.iter()
+    }
+    /// Get a database by index
+    pub fn get(&self, index: usize) -> Option<&CodeQLDatabase> {
+        self.databases.get(index)
+    }
+
+    /// Find the first database for a language. Language aliases are supported
+    /// (e.g. `js` will find a `javascript` database).
+    pub fn find_by_language(&self, language: impl Into<CodeQLLanguage>) -> Option<&CodeQLDatabase> {
+        let language: CodeQLLanguage = language.into();
+        if language.is_none() {
+            return None;
+        }
+        self.databases
+            .iter()
+            .find(|d| d.language() == language.language())
+    }
+
+    /// Find all the databases associated with a repository (matching owner and name)
+    pub fn find_by_repository(&self, repository: &Repository) -> Vec<&CodeQLDatabase> {
+        self.databases
+            .iter()
+            .filter(|d| {
+                d.repository()
+                    .map(|r| r.owner() == repository.owner() && r.name() == repository.name())
+                    .unwrap_or(false)
+            })
+            .collect()
+    }
+
+    /// Download the CodeQL database for a repository and language from GitHub.
+    ///
+    /// The database is stored in the default databases path
+    /// (`<default_path>/<owner>/<repo>/<language>`) and the downloaded archive
+    /// is removed. Both GitHub Cloud and Enterprise Server are supported; if the
+    /// instance does not have the database endpoint (or no database exists) a
+    /// `GHASError::NotFound` is returned.
+    ///
+    /// The latest database GitHub has for the language is downloaded, use
+    /// [`CodeQLDatabase::commit_sha`] to get the commit it was created from.
+    ///
+    /// The downloaded database is validated (the configuration file exists and the
+    /// primary language matches the requested language).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ghastoolkit::{CodeQLDatabases, GitHub, Repository};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let github = GitHub::default();
+    /// let repository = Repository::parse("geekmasher/ghastoolkit-rs")?;
+    ///
+    /// let database = CodeQLDatabases::download_database(&github, &repository, "rust").await?;
+    /// println!("Downloaded :: {}", database);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download_database(
+        github: &GitHub,
+        repository: &Repository,
+        language: impl Into<CodeQLLanguage>,
+    ) -> Result<CodeQLDatabase, GHASError> {
+        CodeQLDatabases::download_database_with_progress(github, repository, language, |_, _| {})
+            .await
+    }
+
+    /// Download the CodeQL database for a repository and language from GitHub,
+    /// reporting the progress as `(downloaded, total)` bytes.
+    ///
+    /// See [`CodeQLDatabases::download_database`] for details.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "codeql.database.download",
+            skip_all,
+            fields(
+                repo = %repository,
+                language = tracing::field::Empty,
+                duration_ms = tracing::field::Empty
+            )
+        )
+    )]
+    pub async fn download_database_with_progress<F>(
+        github: &GitHub,
+        repository: &Repository,
+        language: impl Into<CodeQLLanguage>,
+        progress: F,
+    ) -> Result<CodeQLDatabase, GHASError>
+    where
+        F: FnMut(u64, Option<u64>),
+    {
+        let _timer = SpanTimer::start();
+        let language: CodeQLLanguage = language.into();
+        spans::record("language", &language);
+        if language.is_none() {
+            return Err(GHASError::CodeQLDatabaseError(
+                "Unknown language for CodeQL database download".to_string(),
+            ));
+        }
+
+        let mut output = CodeQLDatabases::default_path();
+        output.push(repository.owner());
+        output.push(repository.name());
+
+        let database = github
+            .code_scanning(repository)
+            .download_codeql_database_with_progress(language.language(), &output, false, progress)
+            .await?;
+
+        CodeQLDatabases::validate_download(&database, &language)?;
+        Ok(database)
+    }
+
+    /// Check a downloaded database is usable and is for the requested language
+    fn validate_download(
+        database: &CodeQLDatabase,
+        language: &CodeQLLanguage,
+    ) -> Result<(), GHASError> {
+        if !database.validate() {
+            return Err(GHASError::CodeQLDatabaseError(format!(
+                "Downloaded database is missing its configuration: {}",
+                database.configuration_path().display()
+            )));
+        }
+        if database.language() != language.language() {
+            return Err(GHASError::CodeQLDatabaseError(format!(
+                "Downloaded database language `{}` does not match requested language `{}`",
+                database.language(),
+                language.language()
+            )));
+        }
+        Ok(())
+    }
 
     /// Get the default path for CodeQL databases
     pub fn default_path() -> PathBuf {
@@ -76,22 +215,120 @@ impl CodeQLDatabases {
         }
     }
 
+    /// Remove duplicate databases (same name, language and path), keeping the first one
+    pub fn dedup(&mut self) {
+        let mut seen = HashSet::new();
+        self.databases.retain(|d| seen.insert(d.clone()));
+    }
+
     /// Walk directory to find all CodeQL databases.
+    ///
+    /// The path can be a glob pattern (e.g. `/databases/**/codeql-database.yml`), every
+    /// match is walked. A database found multiple times (overlapping matches) is only
+    /// loaded once.
+    ///
+    /// Directories inside a database are not searched, so a `codeql-database.yml` nested
+    /// in another database (e.g. in its scratch directory) is not loaded as a separate
+    /// database.
     pub fn load(path: String) -> CodeQLDatabases {
         debug!("Loading databases from: {}", path);
         let mut databases = CodeQLDatabases::new();
 
-        WalkDir::new(path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_name() == "codeql-database.yml")
-            .for_each(|path| {
-                let database = CodeQLDatabase::from(path.path());
-                databases.add(database);
-            });
+        let roots: Vec<PathBuf> = if path.contains(['*', '?', '[']) {
+            match glob::glob(&path) {
+                Ok(paths) => paths.filter_map(|p| p.ok()).collect(),
+                Err(e) => {
+                    debug!("Invalid glob pattern {} :: {}", path, e);
+                    Vec::new()
+                }
+            }
+        } else {
+            vec![PathBuf::from(path)]
+        };
+
+        let mut found: Vec<PathBuf> = roots
+            .iter()
+            .flat_map(|root| CodeQLDatabases::find_database_dirs(root))
+            .collect();
+        found.sort();
+        found.dedup();
+
+        // Skip databases nested inside another database
+        let mut roots: Vec<PathBuf> = Vec::new();
+        for path in found {
+            if roots.iter().any(|root| path.starts_with(root)) {
+                debug!("Skipping nested database :: {}", path.display());
+                continue;
+            }
+            roots.push(path);
+        }
+
+        for root in roots {
+            match CodeQLDatabase::load(root.display().to_string()) {
+                Ok(database) => databases.add(database),
+                Err(e) => debug!(
+                    "Failed to load database {} :: {}",
+                    root.display(),
+                    e.display_chain()
+                ),
+            }
+        }
 
+        databases.dedup();
         databases
     }
+
+    /// Find the database directories (containing a `codeql-database.yml`) under a path.
+    ///
+    /// The search doesn't descend into a database directory. If the path is a
+    /// `codeql-database.yml` file its directory is returned.
+    pub(crate) fn find_database_dirs(path: &Path) -> Vec<PathBuf> {
+        if path.is_file() {
+            return match path.file_name() == Some(OsStr::new("codeql-database.yml")) {
+                true => path.parent().map(Path::to_path_buf).into_iter().collect(),
+                false => Vec::new(),
+            };
+        }
+
+        let mut dirs = Vec::new();
+        let mut walker = WalkDir::new(path).into_iter();
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if entry.file_type().is_dir() && entry.path().join("codeql-database.yml").is_file() {
+                dirs.push(entry.path().to_path_buf());
+                walker.skip_current_dir();
+            }
+        }
+        dirs
+    }
+}
+
+impl IntoIterator for CodeQLDatabases {
+    type Item = CodeQLDatabase;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.databases.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a CodeQLDatabases {
+    type Item = &'a CodeQLDatabase;
+    type IntoIter = std::slice::Iter<'a, CodeQLDatabase>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.databases.iter()
+    }
+}
+
+impl Index<usize> for CodeQLDatabases {
+    type Output = CodeQLDatabase;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.databases[index]
+    }
 }
 
 impl From<String> for CodeQLDatabases {
@@ -108,15 +345,14 @@ impl From<&str> for CodeQLDatabases {
 
 impl From<PathBuf> for CodeQLDatabases {
     fn from(path: PathBuf) -> Self {
-        CodeQLDatabases::load(path.to_str().expect("Invalid path").to_string())
+        CodeQLDatabases::load(path.to_string_lossy().to_string())
     }
 }
 
 impl Default for CodeQLDatabases {
     fn default() -> Self {
         let path = CodeQLDatabases::default_path()
-            .to_str()
-            .expect("Invalid path")
+            .to_string_lossy()
             .to_string();
         CodeQLDatabases::load(format!("{}/**/codeql-database.yml", path))
     }
@@ -126,7 +362,7 @@ impl Default for CodeQLDatabases {
 mod tests {
     use std::path::PathBuf;
 
-    use crate::CodeQLDatabases;
+    use crate::{codeql::test_utils::write_database, CodeQLDatabase, CodeQLDatabases, Repository};
 
     #[test]
     fn test_default_codeql_path() {
@@ -143,4 +379,131 @@ mod tests {
 
         assert_eq!(path, home_path);
     }
+
+    #[test]
+    fn test_iter_preserves_order() {
+        let mut databases = CodeQLDatabases::new();
+        for name in ["first", "second", "third"] {
+            databases.add(
+                CodeQLDatabase::init()
+                    .name(name)
+                    .language("python")
+                    .build()
+                    .unwrap(),
+            );
+        }
+
+        let names: Vec<String> = databases.iter().map(|d| d.to_string()).collect();
+        assert_eq!(names.len(), 3);
+        // Iterating again yields the same databases
+        assert_eq!(databases.iter().count(), 3);
+        assert_eq!(databases.len(), 3);
+
+        assert_eq!(databases[0].to_string(), names[0]);
+        assert!(names[0].contains("first"));
+        assert!(databases.get(2).unwrap().to_string().contains("third"));
+        assert!(databases.get(3).is_none());
+
+        let consumed: Vec<CodeQLDatabase> = databases.into_iter().collect();
+        assert!(consumed[0].to_string().contains("first"));
+    }
+
+    #[test]
+    fn test_find() {
+        let repo = Repository::new("geekmasher", "ghastoolkit-rs");
+        let mut databases = CodeQLDatabases::new();
+        databases.add(
+            CodeQLDatabase::init()
+                .repository(&repo)
+                .language("javascript")
+                .build()
+                .unwrap(),
+        );
+        databases.add(
+            CodeQLDatabase::init()
+                .name("other")
+                .language("python")
+                .build()
+                .unwrap(),
+        );
+
+        let db = databases
+            .find_by_language("js")
+            .expect("Failed to find database");
+        assert_eq!(db.language(), "javascript");
+        assert!(databases.find_by_language("Python").is_some());
+        assert!(databases.find_by_language("go").is_none());
+
+        let dbs = databases.find_by_repository(&repo);
+        assert_eq!(dbs.len(), 1);
+        assert_eq!(dbs[0].name(), "ghastoolkit-rs");
+    }
+
+    #[test]
+    fn test_load_overlapping() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        for language in ["python", "javascript"] {
+            write_database(&root.join("geekmasher").join(language), language);
+        }
+
+        let databases = CodeQLDatabases::load(root.display().to_string());
+        assert_eq!(databases.len(), 2);
+
+        // `**` matches the root and every directory below it
+        let databases = CodeQLDatabases::load(format!("{}/**", root.display()));
+        assert_eq!(databases.len(), 2);
+        let databases = CodeQLDatabases::load(format!("{}/**/codeql-database.yml", root.display()));
+        assert_eq!(databases.len(), 2);
+
+        let mut databases = CodeQLDatabases::load(root.display().to_string());
+        databases.add(databases[0].clone());
+        assert_eq!(databases.len(), 3);
+        databases.dedup();
+        assert_eq!(databases.len(), 2);
+    }
+
+    #[test]
+    fn test_load_nested() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        let outer = root.join("python");
+        write_database(&outer, "python");
+        write_database(&outer.join("working").join("copy"), "python");
+
+        // The database nested in the scratch directory is not loaded
+        let databases = CodeQLDatabases::load(root.display().to_string());
+        assert_eq!(databases.len(), 1);
+        assert_eq!(databases[0].path(), &outer);
+        let databases = CodeQLDatabases::load(format!("{}/**/codeql-database.yml", root.display()));
+        assert_eq!(databases.len(), 1);
+
+        // A single database below a directory is loaded
+        let database = CodeQLDatabase::load(root.display().to_string()).unwrap();
+        assert_eq!(database.path(), &outer);
+
+        // Multiple databases below a directory are ambiguous
+        write_database(&root.join("javascript"), "javascript");
+        let error = CodeQLDatabase::load(root.display().to_string()).unwrap_err();
+        assert!(error.to_string().contains("CodeQLDatabases::load"));
+        assert_eq!(CodeQLDatabases::load(root.display().to_string()).len(), 2);
+    }
+
+    #[test]
+    fn test_validate_download() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        write_database(root, "python");
+
+        let database = CodeQLDatabase::init()
+            .name("test")
+            .path(root.display().to_string())
+            .build()
+            .unwrap();
+        assert!(CodeQLDatabases::validate_download(&database, &"python".into()).is_ok());
+        assert!(CodeQLDatabases::validate_download(&database, &"javascript".into()).is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+        assert!(CodeQLDatabases::validate_download(&database, &"python".into()).is_err());
+    }
 }
@@ -2,4 +2,4 @@
 
 pub mod models;
 
-pub use models::CodeQLExtractor;
+pub use models::{CodeQLExtractor, CodeQLExtractorOption, ColumnKind};
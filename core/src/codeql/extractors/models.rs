@@ -1,9 +1,11 @@
 //! CodeQL Extractor YAML Model
 
-use std::path::PathBuf;
+use std::{collections::HashMap, fmt::Display, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::codeql::BuildMode;
+
 /// CodeQL Extractor
 ///
 /// ```rust
@@ -30,10 +32,11 @@ use serde::{Deserialize, Serialize};
 /// # assert_eq!(extractor.name, "rust");
 /// # assert_eq!(extractor.display_name, "Rust");
 /// # assert_eq!(extractor.version, "0.1.0");
+/// # assert_eq!(extractor.column_kind_enum(), Some(ghastoolkit::codeql::extractors::ColumnKind::Utf8));
 ///
 ///
 /// ```
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeQLExtractor {
     /// The name of the extractor
     pub name: String,
@@ -55,6 +58,12 @@ pub struct CodeQLExtractor {
     pub scc_languages: Option<Vec<String>>,
     /// File types
     pub file_types: Vec<CodeQLExtractorFileType>,
+    /// Supported build modes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_modes: Option<Vec<BuildMode>>,
+    /// Extractor options (tunables which can be set using `--extractor-option`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<HashMap<String, CodeQLExtractorOption>>,
 }
 
 impl CodeQLExtractor {
@@ -75,10 +84,106 @@ impl CodeQLExtractor {
     pub fn languages(&self) -> Vec<String> {
         self.github_api_languages.clone().unwrap_or_default()
     }
+
+    /// Get the column kind of the extractor (how columns in locations are counted)
+    pub fn column_kind_enum(&self) -> Option<ColumnKind> {
+        self.column_kind.as_deref().and_then(ColumnKind::parse)
+    }
+
+    /// Get the extractor options
+    pub fn options(&self) -> HashMap<String, CodeQLExtractorOption> {
+        self.options.clone().unwrap_or_default()
+    }
+
+    /// Get the build modes the extractor supports
+    pub fn build_modes(&self) -> Vec<BuildMode> {
+        self.build_modes.clone().unwrap_or_default()
+    }
+
+    /// Check if the extractor supports a build mode. Extractors which don't list
+    /// their build modes are assumed to support any mode.
+    pub fn supports(&self, mode: &BuildMode) -> bool {
+        match &self.build_modes {
+            Some(modes) => modes.contains(mode),
+            None => true,
+        }
+    }
+
+    /// Get all the file extensions (lowercase, without the leading `.`) the extractor supports
+    pub fn extensions(&self) -> Vec<String> {
+        self.file_types
+            .iter()
+            .flat_map(|ft| ft.extensions.iter())
+            .map(|ext| ext.trim_start_matches('.').to_lowercase())
+            .collect()
+    }
+}
+
+/// Column kind of an extractor (the unit columns in locations are counted in)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColumnKind {
+    /// UTF-8 bytes
+    Utf8,
+    /// UTF-16 code units
+    Utf16,
+    /// UTF-32 code points (Unicode code points)
+    Utf32,
+}
+
+impl ColumnKind {
+    /// Parse a column kind (`utf8`, `utf16` or `utf32`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "utf8" => Some(ColumnKind::Utf8),
+            "utf16" => Some(ColumnKind::Utf16),
+            "utf32" => Some(ColumnKind::Utf32),
+            _ => None,
+        }
+    }
+
+    /// Get the matching SARIF `columnKind` value. SARIF has no UTF-8 column kind so
+    /// `None` is returned for `Utf8`.
+    pub fn sarif_column_kind(&self) -> Option<&'static str> {
+        match self {
+            ColumnKind::Utf8 => None,
+            ColumnKind::Utf16 => Some("utf16CodeUnits"),
+            ColumnKind::Utf32 => Some("unicodeCodePoints"),
+        }
+    }
+}
+
+impl Display for ColumnKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnKind::Utf8 => write!(f, "utf8"),
+            ColumnKind::Utf16 => write!(f, "utf16"),
+            ColumnKind::Utf32 => write!(f, "utf32"),
+        }
+    }
+}
+
+/// CodeQL Extractor Option
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CodeQLExtractorOption {
+    /// Title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Option type (`string`, `array` or `object`)
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub option_type: Option<String>,
+    /// Pattern the value must match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Nested options (for `object` options)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub properties: Option<HashMap<String, CodeQLExtractorOption>>,
 }
 
 /// CodeQL Extractor File Type
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeQLExtractorFileType {
     /// Name
     pub name: String,
@@ -87,3 +192,49 @@ pub struct CodeQLExtractorFileType {
     /// File Extensions
     pub extensions: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CodeQLExtractor, ColumnKind};
+
+    #[test]
+    fn test_column_kind_and_options() {
+        let extractor = CodeQLExtractor::load(
+            r#"
+name: "python"
+display_name: "Python"
+version: 1.0.0
+column_kind: "utf32"
+file_types: []
+options:
+  logging:
+    title: Options pertaining to logging.
+    type: object
+    properties:
+      verbosity:
+        title: Python extractor logging verbosity level.
+        type: string
+        pattern: "^(progress|debug|trace)$"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(extractor.column_kind_enum(), Some(ColumnKind::Utf32));
+        assert_eq!(
+            ColumnKind::Utf32.sarif_column_kind(),
+            Some("unicodeCodePoints")
+        );
+        assert_eq!(ColumnKind::Utf8.sarif_column_kind(), None);
+        assert_eq!(ColumnKind::parse("UTF16"), Some(ColumnKind::Utf16));
+        assert_eq!(ColumnKind::parse("other"), None);
+
+        let options = extractor.options();
+        let logging = options.get("logging").unwrap();
+        assert_eq!(logging.option_type.as_deref(), Some("object"));
+        let verbosity = &logging.properties.as_ref().unwrap()["verbosity"];
+        assert_eq!(
+            verbosity.pattern.as_deref(),
+            Some("^(progress|debug|trace)$")
+        );
+    }
+}
@@ -1,4 +1,64 @@
-use std::fmt::{Debug, Display};
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display},
+    path::Path,
+    str::FromStr,
+};
+
+use log::debug;
+
+use crate::{codeql::CodeQLExtractor, octokit::models::GitHubLanguages, GHASError};
+
+/// CodeQL database build mode (how the source code was extracted)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildMode {
+    /// No build is required (source is extracted directly)
+    None,
+    /// CodeQL autobuilder is used to build the code
+    #[serde(alias = "auto")]
+    Autobuild,
+    /// Manual build command(s) are used to build the code
+    Manual,
+}
+
+impl Display for BuildMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildMode::None => write!(f, "none"),
+            BuildMode::Autobuild => write!(f, "autobuild"),
+            BuildMode::Manual => write!(f, "manual"),
+        }
+    }
+}
+
+impl FromStr for BuildMode {
+    type Err = GHASError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "none" => Ok(BuildMode::None),
+            "autobuild" | "auto" => Ok(BuildMode::Autobuild),
+            "manual" => Ok(BuildMode::Manual),
+            _ => Err(GHASError::Parse(format!("Unknown build mode: {}", s))),
+        }
+    }
+}
+
+/// GitHub (linguist) languages supported by CodeQL, mapped to the CodeQL language name
+pub const CODEQL_LANGUAGES: &[(&str, &str)] = &[
+    ("C", "c"),
+    ("C++", "cpp"),
+    ("C#", "csharp"),
+    ("Go", "go"),
+    ("Java", "java"),
+    ("Kotlin", "kotlin"),
+    ("JavaScript", "javascript"),
+    ("TypeScript", "typescript"),
+    ("Python", "python"),
+    ("Ruby", "ruby"),
+    ("Swift", "swift"),
+];
 
 /// Languages supported by CodeQL.
 #[derive(Default, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -76,6 +136,47 @@ impl CodeQLLanguage {
         }
     }
 
+    /// Check if the language is compiled (needs to be built to create a database)
+    pub fn is_compiled(&self) -> bool {
+        matches!(
+            self,
+            CodeQLLanguage::C
+                | CodeQLLanguage::Cpp
+                | CodeQLLanguage::CSharp
+                | CodeQLLanguage::Go
+                | CodeQLLanguage::Java
+                | CodeQLLanguage::Kotlin
+                | CodeQLLanguage::Swift
+        )
+    }
+
+    /// Get the language name used by Code Scanning default setup (e.g. `java-kotlin`),
+    /// `None` if default setup does not support the language
+    pub fn default_setup_language(&self) -> Option<&str> {
+        match self {
+            CodeQLLanguage::C | CodeQLLanguage::Cpp => Some("c-cpp"),
+            CodeQLLanguage::CSharp => Some("csharp"),
+            CodeQLLanguage::Go => Some("go"),
+            CodeQLLanguage::Java | CodeQLLanguage::Kotlin => Some("java-kotlin"),
+            CodeQLLanguage::JavaScript | CodeQLLanguage::TypeScript => {
+                Some("javascript-typescript")
+            }
+            CodeQLLanguage::Python => Some("python"),
+            CodeQLLanguage::Swift => Some("swift"),
+            CodeQLLanguage::Ruby => Some("ruby"),
+            _ => None,
+        }
+    }
+
+    /// Get the CodeQL language for a GitHub (linguist) language name (e.g. `C++`), using
+    /// [`CODEQL_LANGUAGES`]. `None` if CodeQL does not support the language.
+    pub fn from_github_language(name: &str) -> Option<CodeQLLanguage> {
+        CODEQL_LANGUAGES
+            .iter()
+            .find(|(github, _)| github.eq_ignore_ascii_case(name.trim()))
+            .map(|(_, codeql)| CodeQLLanguage::from(*codeql))
+    }
+
     /// Check if the language is a secondary language
     pub fn is_secondary(&self) -> bool {
         matches!(self, CodeQLLanguage::Secondary(_))
@@ -130,6 +231,7 @@ impl Debug for CodeQLLanguage {
 
 impl From<(&str, bool)> for CodeQLLanguage {
     fn from((s, custom): (&str, bool)) -> Self {
+        let s = s.trim();
         match s.to_lowercase().as_str() {
             "c" => CodeQLLanguage::C,
             "cpp" | "c++" => CodeQLLanguage::Cpp,
@@ -143,7 +245,7 @@ impl From<(&str, bool)> for CodeQLLanguage {
             "swift" => CodeQLLanguage::Swift,
             "ruby" => CodeQLLanguage::Ruby,
             "properties" | "csv" | "yaml" | "xml" | "html" => {
-                CodeQLLanguage::Secondary(s.to_string())
+                CodeQLLanguage::Secondary(s.to_lowercase())
             }
             _ => {
                 if custom {
@@ -177,9 +279,179 @@ impl From<Option<String>> for CodeQLLanguage {
     }
 }
 
+/// A collection of CodeQL Languages backed by their extractors
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::Path;
+/// use ghastoolkit::CodeQL;
+/// use ghastoolkit::codeql::CodeQLLanguages;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let codeql = CodeQL::new().await;
+///
+/// let languages = CodeQLLanguages::from(codeql.get_extractors().await.unwrap());
+///
+/// for (language, count) in languages.detect(Path::new("./")) {
+///     println!("{} :: {} files", language, count);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CodeQLLanguages {
+    extractors: Vec<CodeQLExtractor>,
+}
+
+impl CodeQLLanguages {
+    /// Create a new collection of languages from a list of extractors
+    pub fn new(extractors: Vec<CodeQLExtractor>) -> Self {
+        Self { extractors }
+    }
+
+    /// Get the extractors
+    pub fn extractors(&self) -> &[CodeQLExtractor] {
+        &self.extractors
+    }
+
+    /// Get the languages (one per extractor)
+    pub fn languages(&self) -> Vec<CodeQLLanguage> {
+        self.extractors
+            .iter()
+            .map(|e| CodeQLLanguage::from((e.name.as_str(), true)))
+            .collect()
+    }
+
+    /// Map GitHub (linguist) languages to CodeQL languages, sorted by the number of bytes
+    /// (most first). Languages CodeQL does not support are excluded.
+    ///
+    /// The extractors' `github_api_languages` are checked first (so custom extractors are
+    /// supported), then the built-in [`CODEQL_LANGUAGES`] mapping.
+    pub fn from_github_languages(&self, languages: &GitHubLanguages) -> Vec<CodeQLLanguage> {
+        let mut languages: Vec<(&String, &u32)> = languages.iter().collect();
+        languages.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+        let mut result: Vec<CodeQLLanguage> = Vec::new();
+        for (name, _) in languages {
+            let language = self
+                .extractors
+                .iter()
+                .find(|e| e.languages().iter().any(|l| l.eq_ignore_ascii_case(name)))
+                .map(|e| CodeQLLanguage::from((e.name.as_str(), true)))
+                .or_else(|| CodeQLLanguage::from_github_language(name));
+
+            match language {
+                Some(language) if !result.contains(&language) => result.push(language),
+                Some(_) => {}
+                None => debug!("Language `{}` is not supported by CodeQL", name),
+            }
+        }
+        result
+    }
+
+    /// Detect the languages used in a source tree.
+    ///
+    /// Files are counted per extension and mapped to languages using each extractor's
+    /// `file_types`. The languages are returned sorted by the number of files (most first),
+    /// languages without any files are excluded.
+    ///
+    /// With the `gitignore` feature, `.gitignore` files are respected. Otherwise only the
+    /// `.git` directory is skipped.
+    pub fn detect(&self, root: &Path) -> Vec<(CodeQLLanguage, usize)> {
+        let counts = count_file_extensions(root);
+
+        let mut languages: Vec<(CodeQLLanguage, usize)> = self
+            .extractors
+            .iter()
+            .map(|extractor| {
+                let total = extractor
+                    .extensions()
+                    .iter()
+                    .map(|ext| counts.get(ext).copied().unwrap_or_default())
+                    .sum::<usize>();
+                (CodeQLLanguage::from((extractor.name.as_str(), true)), total)
+            })
+            .filter(|(_, total)| *total > 0)
+            .collect();
+
+        languages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        languages
+    }
+}
+
+impl From<Vec<CodeQLExtractor>> for CodeQLLanguages {
+    fn from(extractors: Vec<CodeQLExtractor>) -> Self {
+        Self::new(extractors)
+    }
+}
+
+/// Walk a directory and count the number of files per extension (lowercase, without the `.`).
+/// The `.git` directory is skipped.
+#[cfg(not(feature = "gitignore"))]
+pub(crate) fn count_file_extensions(root: &Path) -> HashMap<String, usize> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git")
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .for_each(|entry| {
+            if let Some(ext) = entry.path().extension() {
+                *counts
+                    .entry(ext.to_string_lossy().to_lowercase())
+                    .or_default() += 1;
+            }
+        });
+
+    counts
+}
+
+/// Walk a directory and count the number of files per extension (lowercase, without the `.`).
+/// Files ignored by `.gitignore` (and the `.git` directory) are skipped.
+#[cfg(feature = "gitignore")]
+pub(crate) fn count_file_extensions(root: &Path) -> HashMap<String, usize> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    ignore::WalkBuilder::new(root)
+        .hidden(false)
+        .filter_entry(|e| e.file_name() != ".git")
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().map(|ft| ft.is_file()).unwrap_or(false))
+        .for_each(|entry| {
+            if let Some(ext) = entry.path().extension() {
+                *counts
+                    .entry(ext.to_string_lossy().to_lowercase())
+                    .or_default() += 1;
+            }
+        });
+
+    counts
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::codeql::CodeQLLanguage;
+    use crate::codeql::{BuildMode, CodeQLExtractor, CodeQLLanguage, CodeQLLanguages};
+
+    #[test]
+    fn test_build_mode_parsing() {
+        assert_eq!(
+            "autobuild".parse::<BuildMode>().unwrap(),
+            BuildMode::Autobuild
+        );
+        assert_eq!("auto".parse::<BuildMode>().unwrap(), BuildMode::Autobuild);
+        assert_eq!("None".parse::<BuildMode>().unwrap(), BuildMode::None);
+        assert_eq!("manual".parse::<BuildMode>().unwrap(), BuildMode::Manual);
+        assert!("unknown".parse::<BuildMode>().is_err());
+
+        let modes: Vec<BuildMode> = serde_yaml::from_str("[auto, autobuild, none]").unwrap();
+        assert_eq!(
+            modes,
+            vec![BuildMode::Autobuild, BuildMode::Autobuild, BuildMode::None]
+        );
+    }
 
     #[test]
     fn test_parsing() {
@@ -211,6 +483,91 @@ mod tests {
         assert_eq!(cs.language(), "csharp");
     }
 
+    #[test]
+    fn test_case_insensitive() {
+        let ruby = CodeQLLanguage::from("Ruby");
+        assert_eq!(ruby, CodeQLLanguage::Ruby);
+        assert_eq!(ruby.pretty(), "Ruby");
+
+        let js = CodeQLLanguage::from("JS");
+        assert_eq!(js, CodeQLLanguage::JavaScript);
+        assert_eq!(js.pretty(), "JavaScript / TypeScript");
+
+        let cs = CodeQLLanguage::from("C#");
+        assert_eq!(cs, CodeQLLanguage::CSharp);
+        assert_eq!(cs.pretty(), "C#");
+
+        assert_eq!(CodeQLLanguage::from("Python"), CodeQLLanguage::Python);
+        assert_eq!(CodeQLLanguage::from("C++"), CodeQLLanguage::Cpp);
+        assert_eq!(CodeQLLanguage::from("TS"), CodeQLLanguage::TypeScript);
+
+        let yaml = CodeQLLanguage::from("YAML");
+        assert_eq!(yaml, CodeQLLanguage::Secondary(String::from("yaml")));
+        assert_eq!(yaml.language(), "yaml");
+    }
+
+    #[test]
+    fn test_github_languages() {
+        assert_eq!(
+            CodeQLLanguage::from_github_language("C++"),
+            Some(CodeQLLanguage::Cpp)
+        );
+        assert_eq!(
+            CodeQLLanguage::from_github_language("typescript"),
+            Some(CodeQLLanguage::TypeScript)
+        );
+        assert_eq!(CodeQLLanguage::from_github_language("Markdown"), None);
+
+        assert_eq!(
+            CodeQLLanguage::Kotlin.default_setup_language(),
+            Some("java-kotlin")
+        );
+        assert_eq!(
+            CodeQLLanguage::Secondary(String::from("yaml")).default_setup_language(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_from_github_languages() {
+        let languages = [
+            ("Markdown", 500),
+            ("Rust", 300),
+            ("Python", 200),
+            ("C++", 400),
+        ]
+        .into_iter()
+        .map(|(name, bytes)| (name.to_string(), bytes))
+        .collect();
+
+        let codeql = CodeQLLanguages::default();
+        assert_eq!(
+            codeql.from_github_languages(&languages),
+            vec![CodeQLLanguage::Cpp, CodeQLLanguage::Python]
+        );
+
+        let rust = CodeQLExtractor::load(
+            r#"
+            name: "rust"
+            display_name: "Rust"
+            version: 0.1.0
+            github_api_languages:
+              - Rust
+            file_types: []
+            "#,
+        )
+        .unwrap();
+        let codeql = CodeQLLanguages::new(vec![rust]);
+        assert_eq!(
+            codeql.from_github_languages(&languages),
+            vec![
+                CodeQLLanguage::Cpp,
+                CodeQLLanguage::Custom(String::from("rust")),
+                CodeQLLanguage::Python
+            ]
+        );
+    }
+
     #[test]
     fn test_incorrect() {
         // RIP Rust
@@ -226,4 +583,38 @@ mod tests {
         let lang = CodeQLLanguage::from(None);
         assert_eq!(lang, CodeQLLanguage::None);
     }
+
+    #[test]
+    fn test_count_file_extensions() {
+        let root = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src");
+        let counts = super::count_file_extensions(&root);
+
+        assert!(counts.get("rs").copied().unwrap_or_default() > 10);
+        assert!(!counts.contains_key("py"));
+    }
+
+    #[test]
+    fn test_detect() {
+        let extractor = crate::codeql::CodeQLExtractor::load(
+            r#"
+            name: "rust"
+            display_name: "Rust"
+            version: 0.1.0
+            file_types:
+              - name: rust
+                display_name: Rust
+                extensions:
+                  - .rs
+            "#,
+        )
+        .unwrap();
+        let languages = super::CodeQLLanguages::from(vec![extractor]);
+
+        let root = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src");
+        let detected = languages.detect(&root);
+
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].0, CodeQLLanguage::Custom(String::from("rust")));
+        assert!(detected[0].1 > 10);
+    }
 }
@@ -84,9 +84,11 @@ pub mod extractors;
 /// This module contains the codeql language struct and its methods
 pub mod languages;
 pub mod packs;
+#[cfg(test)]
+pub(crate) mod test_utils;
 
 pub use cli::CodeQL;
 pub use database::CodeQLDatabase;
 pub use databases::CodeQLDatabases;
 pub use extractors::CodeQLExtractor;
-pub use languages::CodeQLLanguage;
+pub use languages::{BuildMode, CodeQLLanguage, CodeQLLanguages};
@@ -1,7 +1,8 @@
 //! CodeQL Packs
 
 pub mod pack;
+#[allow(clippy::module_inception)]
 pub mod packs;
 
-pub use pack::{CodeQLPack, CodeQLPackType};
+pub use pack::{CodeQLPack, CodeQLPackInstallMode, CodeQLPackType};
 pub use packs::CodeQLPacks;
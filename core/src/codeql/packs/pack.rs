@@ -1,7 +1,11 @@
 //! CodeQL Pack
-use std::{collections::HashMap, fmt::Display, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
-use crate::GHASError;
+use crate::{codeql::CodeQLLanguage, GHASError};
 
 /// CodeQL Pack
 #[derive(Debug, Clone, Default)]
@@ -40,6 +44,15 @@ impl CodeQLPack {
     pub fn path(&self) -> PathBuf {
         self.path.clone()
     }
+    /// Get the pack path as a CodeQL CLI argument (the path must be valid UTF-8)
+    fn path_arg(&self) -> Result<String, GHASError> {
+        self.path.to_str().map(|p| p.to_string()).ok_or_else(|| {
+            GHASError::CodeQLPackError(format!(
+                "Invalid pack path (not UTF-8): {}",
+                self.path.display()
+            ))
+        })
+    }
     /// Get the pack version
     pub fn version(&self) -> Option<String> {
         self.pack.version.clone()
@@ -64,38 +77,64 @@ impl CodeQLPack {
         self.pack_type.clone()
     }
 
-    /// Download a CodeQL Pack using its name (namespace/name[@version])
+    /// Get the language of the pack.
+    ///
+    /// This uses the pack's extractor (library packs) or the standard CodeQL library /
+    /// query packs it depends on or extends (`codeql/<language>-all`). Returns
+    /// `CodeQLLanguage::None` if the language can't be determined.
+    pub fn language(&self) -> CodeQLLanguage {
+        if let Some(extractor) = &self.pack.extractor {
+            return CodeQLLanguage::from((extractor.as_str(), true));
+        }
+
+        let dependencies = self.dependencies();
+        let targets = self.pack.extension_targets.clone().unwrap_or_default();
+
+        std::iter::once(&self.pack.name)
+            .chain(dependencies.keys())
+            .chain(targets.keys())
+            .find_map(|name| Self::standard_pack_language(name))
+            .unwrap_or_default()
+    }
+
+    /// Get the language from a standard CodeQL pack name (`codeql/<language>-all` or
+    /// `codeql/<language>-queries`)
+    fn standard_pack_language(name: &str) -> Option<CodeQLLanguage> {
+        let name = name.strip_prefix("codeql/")?;
+        let language = name
+            .strip_suffix("-all")
+            .or_else(|| name.strip_suffix("-queries"))?;
+        Some(CodeQLLanguage::from((language, true)))
+    }
+
+    /// Download a CodeQL Pack using its name (namespace/name[@version]), the version
+    /// can be an exact version or a semver range (e.g. `codeql/python-queries@^1.0.0`)
+    ///
+    /// The pack is loaded from where the CodeQL CLI installed it (see
+    /// `CodeQLPacks::codeql_packages_path`), for a range this is the latest installed
+    /// version matching it.
     ///
     /// ```bash
     /// codeql pack download <name>
     /// ```
-    #[cfg(feature = "async")]
     pub async fn download(
         codeql: &crate::CodeQL,
         name: impl Into<String>,
     ) -> Result<Self, GHASError> {
         let name = name.into();
         if let Some((namespace, mut packname)) = &name.split_once('/') {
-            let version: Option<String> = if let Some((pname, version)) = packname.split_once('@') {
+            let version: Option<&str> = if let Some((pname, version)) = packname.split_once('@') {
                 packname = pname;
-                Some(version.to_string())
+                Some(version)
             } else {
                 None
             };
 
             codeql.run(vec!["pack", "download", name.as_str()]).await?;
 
-            // CodeQL installs the pack in `~/.codeql/packages/{namespace}/{name}/{version}`
-            let path = PathBuf::from("~/.codeql/packages")
-                .join(namespace)
-                .join(packname)
-                .join(version.unwrap_or_default());
-            Ok(Self::new(path))
+            crate::CodeQLPacks::load_package(namespace, packname, version)
         } else {
-            Err(GHASError::CodeQLPackError(format!(
-                "Invalid Pack Name: {}",
-                name
-            )))
+            Err(GHASError::Parse(format!("Invalid Pack Name: {}", name)))
         }
     }
 
@@ -104,40 +143,91 @@ impl CodeQLPack {
     /// ```bash
     /// codeql pack install <path>
     /// ```
-    #[cfg(feature = "async")]
     pub async fn install(&self, codeql: &crate::CodeQL) -> Result<(), GHASError> {
         codeql
-            .run(vec!["pack", "install", self.path().to_str().unwrap()])
+            .run(vec!["pack", "install", self.path_arg()?.as_str()])
             .await
             .map(|_| ())
     }
 
+    /// Install the CodeQL Pack Dependencies using an install mode, returning the
+    /// resolved lock file (`codeql-pack.lock.yml`).
+    ///
+    /// Using `CodeQLPackInstallMode::Verify` fails if the lock file would change,
+    /// which is useful for reproducible builds. Strict mode can be disabled to turn
+    /// some errors into warnings (`--no-strict-mode`).
+    ///
+    /// ```bash
+    /// codeql pack install --mode=<mode> [--no-strict-mode] <path>
+    /// ```
+    pub async fn install_with_mode(
+        &self,
+        codeql: &crate::CodeQL,
+        mode: CodeQLPackInstallMode,
+        strict_mode: bool,
+    ) -> Result<PackYamlLock, GHASError> {
+        let mode = format!("--mode={}", mode);
+        let path = self.path_arg()?;
+
+        let mut args = vec!["pack", "install", mode.as_str()];
+        if !strict_mode {
+            args.push("--no-strict-mode");
+        }
+        args.push(path.as_str());
+        codeql.run(args).await?;
+
+        Self::load_lock(&self.path.join("codeql-pack.lock.yml"))?.ok_or_else(|| {
+            GHASError::NotFound {
+                message: format!(
+                    "Pack lock file not found after install: {}",
+                    self.path.display()
+                ),
+                source: None,
+            }
+        })
+    }
+
     /// Upgrade CodeQL Pack Dependencies
-    #[cfg(feature = "async")]
     pub async fn upgrade(&self, codeql: &crate::CodeQL) -> Result<(), GHASError> {
         codeql
-            .run(vec!["pack", "upgrade", self.path().to_str().unwrap()])
+            .run(vec!["pack", "upgrade", self.path_arg()?.as_str()])
             .await
             .map(|_| ())
     }
 
     /// Publish the CodeQL Pack
     ///
+    /// The token is passed to the CodeQL CLI as the registry credentials
+    /// (`CODEQL_REGISTRIES_AUTH`). If publishing fails (e.g. invalid token or the
+    /// version already exists) a `GHASError::CodeQLError` with the CLI's error
+    /// output is returned.
+    ///
     /// ```bash
     /// codeql pack publish <path>
     /// ```
-    #[cfg(feature = "async")]
     pub async fn publish(
         &self,
         codeql: &crate::CodeQL,
         token: impl Into<String>,
     ) -> Result<(), GHASError> {
-        Ok(tokio::process::Command::new(codeql.path())
-            .env("CODEQL_REGISTRIES_AUTH", token.into())
-            .args(vec!["pack", "publish", self.path().to_str().unwrap()])
-            .output()
+        let token = token.into();
+        let path = self.path_arg()?;
+
+        codeql
+            .run_with_env(
+                vec!["pack", "publish", path.as_str()],
+                &[("CODEQL_REGISTRIES_AUTH", token.as_str())],
+            )
             .await
-            .map(|_| ())?)
+            .map_err(|e| match e {
+                GHASError::CodeQLError(stderr) => GHASError::CodeQLError(format!(
+                    "Failed to publish pack {} :: {}",
+                    self.name(),
+                    stderr.trim()
+                )),
+                e => e,
+            })
+            .map(|_| ())
     }
 
     /// Load a QLPack from a path (root directory or qlpack.yml file)
@@ -162,13 +252,7 @@ impl CodeQLPack {
         };
         let pack_type = Self::get_pack_type(&pack);
 
-        let pack_lock: Option<PackYamlLock> = match std::fs::File::open(qlpack_lock_path) {
-            Ok(f) => match serde_yaml::from_reader(f) {
-                Ok(p) => Some(p),
-                Err(e) => return Err(GHASError::YamlError(e)),
-            },
-            Err(_) => None,
-        };
+        let pack_lock = Self::load_lock(&qlpack_lock_path)?;
 
         Ok(Self {
             path,
@@ -178,6 +262,17 @@ impl CodeQLPack {
         })
     }
 
+    /// Load the pack lock file if it exists
+    fn load_lock(path: &Path) -> Result<Option<PackYamlLock>, GHASError> {
+        match std::fs::File::open(path) {
+            Ok(f) => match serde_yaml::from_reader(f) {
+                Ok(p) => Ok(Some(p)),
+                Err(e) => Err(GHASError::YamlError(e)),
+            },
+            Err(_) => Ok(None),
+        }
+    }
+
     /// Based on the loaded YAML, determine the pack type
     fn get_pack_type(pack_yaml: &PackYaml) -> CodeQLPackType {
         if let Some(library) = pack_yaml.library {
@@ -231,6 +326,28 @@ impl Display for CodeQLPackType {
     }
 }
 
+/// CodeQL Pack install mode (`codeql pack install --mode`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CodeQLPackInstallMode {
+    /// Use the versions in the lock file (creating it if missing)
+    #[default]
+    UseLock,
+    /// Update the lock file to the latest compatible versions
+    Update,
+    /// Fail if the lock file is missing or would change
+    Verify,
+}
+
+impl Display for CodeQLPackInstallMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodeQLPackInstallMode::UseLock => write!(f, "use-lock"),
+            CodeQLPackInstallMode::Update => write!(f, "update"),
+            CodeQLPackInstallMode::Verify => write!(f, "verify"),
+        }
+    }
+}
+
 /// CodeQL Pack Yaml Structure
 #[derive(Debug, Clone, Default, serde::Deserialize)]
 pub struct PackYaml {
@@ -283,3 +400,100 @@ pub struct PackYamlLockDependency {
     /// Version
     pub version: String,
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_load_lock() {
+        use super::{CodeQLPack, CodeQLPackInstallMode};
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+
+        let lock = root.join("codeql-pack.lock.yml");
+        assert!(CodeQLPack::load_lock(&lock).unwrap().is_none());
+
+        std::fs::write(
+            &lock,
+            "lockVersion: 1.0.0\ndependencies:\n  codeql/python-all:\n    version: 1.2.3\ncompiled: false\n",
+        )
+        .unwrap();
+        let lock = CodeQLPack::load_lock(&lock).unwrap().unwrap();
+        assert_eq!(lock.dependencies["codeql/python-all"].version, "1.2.3");
+
+        assert_eq!(CodeQLPackInstallMode::default().to_string(), "use-lock");
+        assert_eq!(CodeQLPackInstallMode::Verify.to_string(), "verify");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_publish_failure() {
+        use std::os::unix::fs::PermissionsExt;
+
+        use super::CodeQLPack;
+        use crate::{CodeQL, GHASError};
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+
+        // Fake CodeQL CLI which fails to publish
+        let cli = root.join("codeql");
+        std::fs::write(
+            &cli,
+            "#!/bin/sh\necho \"A fatal error occurred: version already exists\" >&2\nexit 2\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&cli, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::write(
+            root.join("qlpack.yml"),
+            "name: geekmasher/test\nversion: 0.1.0\n",
+        )
+        .unwrap();
+
+        let codeql = CodeQL::init()
+            .path(cli.display().to_string())
+            .build()
+            .await
+            .unwrap();
+        let pack = CodeQLPack::load(root).unwrap();
+
+        match pack.publish(&codeql, "token").await {
+            Err(GHASError::CodeQLError(e)) => {
+                assert!(e.contains("geekmasher/test"));
+                assert!(e.contains("version already exists"));
+            }
+            r => panic!("Expected publish to fail: {:?}", r),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_non_utf8_path() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        use super::{CodeQLPack, CodeQLPackInstallMode};
+        use crate::{CodeQL, GHASError};
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path().join(OsStr::from_bytes(b"pack-\xff"));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(
+            root.join("qlpack.yml"),
+            "name: geekmasher/test\nversion: 0.1.0\n",
+        )
+        .unwrap();
+
+        // The CLI is never run with a mangled path
+        let codeql = CodeQL::default();
+        let pack = CodeQLPack::load(&root).unwrap();
+        assert!(matches!(
+            pack.install_with_mode(&codeql, CodeQLPackInstallMode::default(), true)
+                .await,
+            Err(GHASError::CodeQLPackError(_))
+        ));
+        assert!(matches!(
+            pack.publish(&codeql, "token").await,
+            Err(GHASError::CodeQLPackError(_))
+        ));
+    }
+}
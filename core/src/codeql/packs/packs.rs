@@ -1,9 +1,10 @@
 //! CodeQL Packs module
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
+use log::warn;
 
-use crate::CodeQLPack;
+use crate::{codeql::CodeQLLanguage, CodeQLPack, CodeQLPackType, GHASError};
 
 /// CodeQL Packs
 #[derive(Debug, Clone, Default)]
@@ -16,40 +17,201 @@ impl CodeQLPacks {
     pub fn len(&self) -> usize {
         self.packs.len()
     }
+    /// Check if there are no packs
+    pub fn is_empty(&self) -> bool {
+        self.packs.is_empty()
+    }
     /// Sort the packs by type (Library, Queries, Models, Testing)
     pub fn sort(&mut self) {
-        self.packs.sort_by(|a, b| a.pack_type().cmp(&b.pack_type()));
+        self.packs.sort_by_key(|a| a.pack_type());
     }
     /// Get the packs
     pub fn packs(&self) -> &[CodeQLPack] {
         &self.packs
     }
+    /// Get the packs of a type (e.g. all the `Models` packs)
+    pub fn by_type(&self, pack_type: CodeQLPackType) -> Vec<&CodeQLPack> {
+        self.packs
+            .iter()
+            .filter(|p| p.pack_type() == pack_type)
+            .collect()
+    }
+    /// Get the packs for a language (see `CodeQLPack::language`)
+    pub fn by_language(&self, language: impl Into<CodeQLLanguage>) -> Vec<&CodeQLPack> {
+        let language: CodeQLLanguage = language.into();
+        if language.is_none() {
+            return Vec::new();
+        }
+        self.packs
+            .iter()
+            .filter(|p| p.language() == language)
+            .collect()
+    }
+    /// Find a pack by its full name (`namespace/name`, optionally with `@version`)
+    pub fn find(&self, full_name: &str) -> Option<&CodeQLPack> {
+        let (name, version) = match full_name.split_once('@') {
+            Some((name, version)) => (name, Some(version)),
+            None => (full_name, None),
+        };
+        self.packs.iter().find(|p| {
+            p.name() == name && version.map_or(true, |v| p.version().as_deref() == Some(v))
+        })
+    }
     /// Merge two CodeQL Packs
     pub fn merge(&mut self, other: &mut Self) {
         self.packs.append(&mut other.packs);
     }
 
+    /// Get the path where the CodeQL CLI installs downloaded packs
+    /// (`~/.codeql/packages`)
+    pub fn codeql_packages_path() -> PathBuf {
+        match std::env::var("HOME") {
+            Ok(p) => PathBuf::from(p).join(".codeql").join("packages"),
+            Err(_) => PathBuf::from("/tmp/codeql/packages"),
+        }
+    }
+
+    /// Load a downloaded pack from the CodeQL packages path
+    /// (`<packages>/<namespace>/<name>/<version>`).
+    ///
+    /// The version can be an exact version or a semver range (e.g. `^1.2.0` or
+    /// `>=0.9, <1.0`), the latest installed version matching the range is loaded.
+    /// If no version is provided the latest installed version is loaded.
+    pub fn load_package(
+        namespace: &str,
+        name: &str,
+        version: Option<&str>,
+    ) -> Result<CodeQLPack, GHASError> {
+        let path = Self::find_package(&Self::codeql_packages_path(), namespace, name, version)?;
+        CodeQLPack::load(path)
+    }
+
+    /// Find the directory of an installed pack version in a packages directory
+    fn find_package(
+        packages: &Path,
+        namespace: &str,
+        name: &str,
+        version: Option<&str>,
+    ) -> Result<PathBuf, GHASError> {
+        let root = packages.join(namespace).join(name);
+
+        let (path, spec) = match version {
+            // Exact version
+            Some(version)
+                if semver::Version::parse(version).is_ok() || root.join(version).is_dir() =>
+            {
+                return Ok(root.join(version));
+            }
+            Some(version) => {
+                let range = semver::VersionReq::parse(version).map_err(|e| {
+                    GHASError::Parse(format!("Invalid pack version `{}`: {}", version, e))
+                })?;
+                (
+                    Self::latest_version(&root, Some(&range)),
+                    format!("{}/{}@{}", namespace, name, version),
+                )
+            }
+            None => (
+                Self::latest_version(&root, None),
+                format!("{}/{}", namespace, name),
+            ),
+        };
+
+        path.ok_or_else(|| GHASError::NotFound {
+            message: format!("Pack {} is not installed in {}", spec, root.display()),
+            source: None,
+        })
+    }
+
+    /// Find the directory of the latest (semver) version of an installed pack,
+    /// optionally matching a version range
+    fn latest_version(root: &Path, range: Option<&semver::VersionReq>) -> Option<PathBuf> {
+        std::fs::read_dir(root)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| {
+                let version = semver::Version::parse(e.file_name().to_str()?).ok()?;
+                Some((version, e.path()))
+            })
+            .filter(|(version, _)| range.map_or(true, |r| r.matches(version)))
+            .max_by(|a, b| a.0.cmp(&b.0))
+            .map(|(_, path)| path)
+    }
+
     /// Load CodeQL Packs from a directory. It will recursively search for `qlpack.yml` files.
+    ///
+    /// Packs are loaded in parallel and returned in path order. Packs which fail to load
+    /// are skipped and logged, use `load_with_errors` to get the errors.
     pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let (packs, errors) = Self::load_with_errors(path)?;
+        for (path, error) in errors {
+            warn!(
+                "Failed to load CodeQL Pack {} :: {}",
+                path.display(),
+                error.display_chain()
+            );
+        }
+        Ok(packs)
+    }
+
+    /// Load CodeQL Packs from a directory (see `load`) returning the packs which
+    /// failed to load along with their errors.
+    pub fn load_with_errors(path: impl Into<PathBuf>) -> Result<(Self, Vec<(PathBuf, GHASError)>)> {
         let path: PathBuf = path.into();
-        let mut packs = Vec::new();
+        let mut paths = Vec::new();
 
-        for entry in walkdir::WalkDir::new(&path) {
+        for entry in walkdir::WalkDir::new(&path).sort_by_file_name() {
             let entry = entry?;
 
-            // Skip any subdirectories named `.codeql`
+            // Skip any subdirectories named `.codeql` (below the root so packs can be
+            // loaded from the CodeQL packages path `~/.codeql/packages`)
             // TODO: Is this the best way to handle this?
-            if entry.path().to_str().unwrap().contains(".codeql") {
+            let relative = entry.path().strip_prefix(&path).unwrap_or(entry.path());
+            if relative.to_string_lossy().contains(".codeql") {
                 continue;
             }
 
             if entry.file_name() == "qlpack.yml" {
-                let pack = CodeQLPack::new(entry.path());
-                packs.push(pack);
+                paths.push(entry.path().to_path_buf());
+            }
+        }
+
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        let chunk_size = ((paths.len() + threads - 1) / threads).max(1);
+
+        // Each thread loads a contiguous chunk so joining them in order keeps the path order
+        let results: Vec<Result<CodeQLPack, GHASError>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = paths
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(CodeQLPack::load)
+                            .collect::<Vec<Result<CodeQLPack, GHASError>>>()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("CodeQL Pack loading thread panicked"))
+                .collect()
+        });
+
+        let mut packs = Vec::new();
+        let mut errors = Vec::new();
+        for (path, result) in paths.into_iter().zip(results) {
+            match result {
+                Ok(pack) => packs.push(pack),
+                Err(e) => errors.push((path, e)),
             }
         }
 
-        Ok(Self { packs })
+        Ok((Self { packs }, errors))
     }
 }
 
@@ -67,3 +229,157 @@ impl Extend<CodeQLPack> for CodeQLPacks {
         self.packs.extend(iter);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::CodeQLPacks;
+    use crate::CodeQLPackType;
+
+    #[test]
+    fn test_filters() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+
+        let packs = [
+            ("lib", "name: geekmasher/python-lib\nversion: 1.0.0\nlibrary: true\ndependencies:\n  codeql/python-all: '*'\n"),
+            ("queries", "name: geekmasher/python-queries\nversion: 1.2.0\ndependencies:\n  codeql/python-all: '*'\n"),
+            ("models", "name: geekmasher/java-models\nlibrary: true\nextensionTargets:\n  codeql/java-all: '*'\ndataExtensions:\n  - models/*.yml\n"),
+        ];
+        for (dir, content) in packs {
+            let dir = root.join(dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("qlpack.yml"), content).unwrap();
+        }
+
+        let packs = CodeQLPacks::load(root).unwrap();
+        assert_eq!(packs.len(), 3);
+
+        assert_eq!(packs.by_type(CodeQLPackType::Library).len(), 1);
+        assert_eq!(packs.by_type(CodeQLPackType::Models).len(), 1);
+        assert!(packs.by_type(CodeQLPackType::Testing).is_empty());
+
+        assert_eq!(packs.by_language("python").len(), 2);
+        assert_eq!(packs.by_language("java").len(), 1);
+        assert!(packs.by_language("go").is_empty());
+
+        assert!(packs.find("geekmasher/python-queries").is_some());
+        assert!(packs.find("geekmasher/python-queries@1.2.0").is_some());
+        assert!(packs.find("geekmasher/python-queries@2.0.0").is_none());
+        assert!(packs.find("geekmasher/ruby-queries").is_none());
+    }
+
+    #[test]
+    fn test_load_codeql_directory() {
+        // Packs are loaded when the root is inside a `.codeql` directory
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path().join(".codeql").join("packages");
+
+        for version in ["1.0.0", "1.1.0"] {
+            let dir = root.join("codeql").join("suite-helpers").join(version);
+            std::fs::create_dir_all(dir.join(".codeql")).unwrap();
+            std::fs::write(
+                dir.join("qlpack.yml"),
+                format!("name: codeql/suite-helpers\nversion: {}\n", version),
+            )
+            .unwrap();
+            // Nested `.codeql` directories are still skipped
+            std::fs::write(dir.join(".codeql").join("qlpack.yml"), "name: skipped\n").unwrap();
+        }
+
+        let packs = CodeQLPacks::load(&root).unwrap();
+        assert_eq!(packs.len(), 2);
+        assert!(packs.find("codeql/suite-helpers@1.0.0").is_some());
+        assert!(packs.find("codeql/suite-helpers@1.1.0").is_some());
+        assert!(packs.find("skipped").is_none());
+    }
+
+    #[test]
+    fn test_latest_version() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        for version in ["0.9.0", "0.10.1", "0.10.0", "not-a-version"] {
+            std::fs::create_dir_all(root.join(version)).unwrap();
+        }
+
+        assert_eq!(
+            CodeQLPacks::latest_version(root, None),
+            Some(root.join("0.10.1"))
+        );
+        std::fs::remove_dir_all(root).unwrap();
+        assert_eq!(CodeQLPacks::latest_version(root, None), None);
+    }
+
+    #[test]
+    fn test_find_package_range() {
+        let packages = tempfile::tempdir().unwrap();
+        let root = packages.path().join("codeql").join("python-queries");
+        for version in ["0.9.0", "0.9.4", "1.0.0", "1.2.3"] {
+            std::fs::create_dir_all(root.join(version)).unwrap();
+        }
+        let find = |version| {
+            CodeQLPacks::find_package(packages.path(), "codeql", "python-queries", version)
+        };
+
+        assert_eq!(find(None).unwrap(), root.join("1.2.3"));
+        assert_eq!(find(Some("1.0.0")).unwrap(), root.join("1.0.0"));
+        assert_eq!(find(Some("^0.9.0")).unwrap(), root.join("0.9.4"));
+        assert_eq!(find(Some("~0.9.0")).unwrap(), root.join("0.9.4"));
+        assert_eq!(find(Some(">=0.9, <1.2")).unwrap(), root.join("1.0.0"));
+        assert_eq!(find(Some("*")).unwrap(), root.join("1.2.3"));
+
+        assert!(matches!(
+            find(Some("^2.0.0")),
+            Err(crate::GHASError::NotFound { .. })
+        ));
+        assert!(matches!(
+            find(Some("not a range")),
+            Err(crate::GHASError::Parse(_))
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires the CodeQL CLI and network access"]
+    async fn test_download_pack() {
+        let codeql = crate::CodeQL::new().await;
+        let pack = crate::CodeQLPack::download(&codeql, "codeql/suite-helpers")
+            .await
+            .unwrap();
+
+        assert_eq!(pack.name(), "codeql/suite-helpers");
+        assert!(pack.version().is_some());
+        assert!(pack.path().starts_with(CodeQLPacks::codeql_packages_path()));
+    }
+
+    #[test]
+    fn test_load_many_packs() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+
+        for i in 0..64 {
+            let dir = root.join(format!("pack-{:02}", i));
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("qlpack.yml"),
+                format!("name: geekmasher/pack-{:02}\nversion: 0.1.0\n", i),
+            )
+            .unwrap();
+        }
+        // Invalid pack
+        let broken = root.join("pack-broken");
+        std::fs::create_dir_all(&broken).unwrap();
+        std::fs::write(broken.join("qlpack.yml"), "name: [invalid").unwrap();
+
+        let (packs, errors) = CodeQLPacks::load_with_errors(root).unwrap();
+
+        assert_eq!(packs.len(), 64);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].0.starts_with(&broken));
+
+        let names: Vec<String> = packs.packs().iter().map(|p| p.name()).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+
+        assert_eq!(CodeQLPacks::load(root).unwrap().len(), 64);
+    }
+}
@@ -0,0 +1,28 @@
+//! Test utilities for CodeQL databases
+use std::{io::Write, path::Path};
+
+/// Minimal `codeql-database.yml` for a language
+pub(crate) fn database_config(language: &str) -> String {
+    format!(
+        "primaryLanguage: {}\nbaselineLinesOfCode: 1\nunicodeNewlines: false\ncolumnKind: utf32\n",
+        language
+    )
+}
+
+/// Write a fake database (only the `codeql-database.yml`) for a language to a directory
+pub(crate) fn write_database(path: &Path, language: &str) {
+    std::fs::create_dir_all(path).unwrap();
+    std::fs::write(path.join("codeql-database.yml"), database_config(language)).unwrap();
+}
+
+/// Create a database zip archive (as downloaded from GitHub) with the database configuration
+pub(crate) fn database_archive(config: &[u8]) -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    zip.start_file(
+        "codeql_db/codeql-database.yml",
+        zip::write::SimpleFileOptions::default(),
+    )
+    .unwrap();
+    zip.write_all(config).unwrap();
+    zip.finish().unwrap().into_inner()
+}
@@ -1,9 +1,27 @@
+use std::{collections::HashSet, io::Write, path::Path};
+
 use crate::{
-    codescanning::models::{CodeScanningAlert, CodeScanningAnalysis},
-    Repository,
+    codeql::CodeQLLanguages,
+    codescanning::models::{
+        CodeScanningAlert, CodeScanningAlertInstance, CodeScanningAlertState, CodeScanningAnalysis,
+        CodeScanningAnalysisDeletion, CodeScanningConfiguration, CodeScanningConfigurationState,
+        CodeScanningConfigurationUpdate, CodeScanningDismissedReason, CodeScanningQuerySuite,
+        CodeScanningRunnerType, CodeScanningThreatModel, ListCodeQLDatabase, SarifUpload,
+    },
+    octokit::{github::list_languages, models::GitHubLanguages},
+    utils::{
+        sarif::Sarif,
+        spans::{self, SpanTimer},
+    },
+    CodeQLDatabase, GHASError, Repository,
 };
-use log::debug;
+use base64::Engine;
+use flate2::{write::GzEncoder, Compression};
+use http_body_util::BodyExt;
+use log::{debug, warn};
 use octocrab::{Octocrab, Page, Result as OctoResult};
+use tokio::io::AsyncWriteExt;
+use walkdir::WalkDir;
 
 /// Code Scanning Handler
 #[derive(Debug, Clone)]
@@ -31,7 +49,7 @@ impl<'octo> CodeScanningHandler<'octo> {
     }
 
     /// Get a list of code scanning alerts for a repository
-    pub fn list(&self) -> ListCodeScanningAlerts {
+    pub fn list(&self) -> ListCodeScanningAlerts<'_, '_> {
         ListCodeScanningAlerts::new(self)
     }
 
@@ -47,10 +65,493 @@ impl<'octo> CodeScanningHandler<'octo> {
         self.crab.get(route, None::<&()>).await
     }
 
+    /// Update (dismiss / reopen) a single code scanning alert
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ghastoolkit::codescanning::models::CodeScanningDismissedReason;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let github = ghastoolkit::GitHub::default();
+    /// let repo = ghastoolkit::Repository::new("geekmasher", "ghastoolkit-rs");
+    ///
+    /// let alert = github
+    ///     .code_scanning(&repo)
+    ///     .update_alert(1)
+    ///     .dismissed_reason(CodeScanningDismissedReason::UsedInTests)
+    ///     .dismissed_comment("Test code")
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update_alert(&self, number: u64) -> UpdateCodeScanningAlert<'octo, '_> {
+        UpdateCodeScanningAlert::new(self, number)
+    }
+
+    /// Get the code scanning default setup configuration
+    pub async fn configuration(&self) -> Result<CodeScanningConfiguration, GHASError> {
+        let route = format!(
+            "/repos/{owner}/{repo}/code-scanning/default-setup",
+            owner = self.repository.owner(),
+            repo = self.repository.name()
+        );
+        Ok(self.crab.get(route, None::<&()>).await?)
+    }
+
+    /// Update the code scanning default setup configuration
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ghastoolkit::codescanning::models::{CodeScanningConfigurationState, CodeScanningQuerySuite};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let github = ghastoolkit::GitHub::default();
+    /// let repo = ghastoolkit::Repository::new("geekmasher", "ghastoolkit-rs");
+    ///
+    /// github
+    ///     .code_scanning(&repo)
+    ///     .update_configuration()
+    ///     .state(CodeScanningConfigurationState::Configured)
+    ///     .query_suite(CodeScanningQuerySuite::Extended)
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update_configuration(&self) -> UpdateCodeScanningConfiguration<'octo, '_> {
+        UpdateCodeScanningConfiguration::new(self)
+    }
+
+    /// Upload a SARIF file to GitHub Code Scanning for a commit and reference
+    /// (e.g. `refs/heads/main`), returning the SARIF upload ID.
+    ///
+    /// The SARIF is serialized, gzip compressed and base64 encoded as the API requires.
+    /// To upload a SARIF file exactly as it was produced (e.g. by a third-party tool),
+    /// use [`CodeScanningHandler::upload_sarif_file`] instead.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use std::path::PathBuf;
+    /// use ghastoolkit::utils::sarif::Sarif;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let github = ghastoolkit::GitHub::default();
+    /// let repo = ghastoolkit::Repository::new("geekmasher", "ghastoolkit-rs");
+    ///
+    /// let sarif = Sarif::try_from(PathBuf::from("results.sarif"))?;
+    /// let upload = github
+    ///     .code_scanning(&repo)
+    ///     .upload_sarif(&sarif, "4b6472266afd7b471e86085a6659e8c7f2b119da", "refs/heads/main")
+    ///     .await?;
+    /// println!("SARIF ID :: {}", upload.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upload_sarif(
+        &self,
+        sarif: &Sarif,
+        commit_sha: &str,
+        reference: &str,
+    ) -> Result<SarifUpload, GHASError> {
+        let data = serde_json::to_vec(sarif)?;
+        self.upload_sarif_bytes(&data, commit_sha, reference).await
+    }
+
+    /// Upload a SARIF file to GitHub Code Scanning without parsing it, the file is
+    /// uploaded byte for byte (gzip compressed and base64 encoded).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let github = ghastoolkit::GitHub::default();
+    /// let repo = ghastoolkit::Repository::new("geekmasher", "ghastoolkit-rs");
+    ///
+    /// let upload = github
+    ///     .code_scanning(&repo)
+    ///     .upload_sarif_file("results.sarif", "4b6472266afd7b471e86085a6659e8c7f2b119da", "refs/heads/main")
+    ///     .await?;
+    /// println!("SARIF ID :: {}", upload.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upload_sarif_file(
+        &self,
+        path: impl AsRef<Path>,
+        commit_sha: &str,
+        reference: &str,
+    ) -> Result<SarifUpload, GHASError> {
+        let data = tokio::fs::read(path.as_ref()).await?;
+        self.upload_sarif_bytes(&data, commit_sha, reference).await
+    }
+
+    /// Upload the raw bytes of a SARIF file to GitHub Code Scanning (see
+    /// [`CodeScanningHandler::upload_sarif_file`]).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "github.codescanning.upload_sarif",
+            skip_all,
+            fields(
+                repo = %self.repository,
+                reference = reference,
+                duration_ms = tracing::field::Empty
+            )
+        )
+    )]
+    pub async fn upload_sarif_bytes(
+        &self,
+        data: &[u8],
+        commit_sha: &str,
+        reference: &str,
+    ) -> Result<SarifUpload, GHASError> {
+        let _timer = SpanTimer::start();
+        let route = format!(
+            "/repos/{owner}/{repo}/code-scanning/sarifs",
+            owner = self.repository.owner(),
+            repo = self.repository.name()
+        );
+        let body = serde_json::json!({
+            "commit_sha": commit_sha,
+            "ref": reference,
+            "sarif": encode_sarif(data)?,
+        });
+
+        Ok(self.crab.post(route, Some(&body)).await?)
+    }
+
+    /// Get all the instances of a code scanning alert (across branches / refs)
+    pub async fn alert_instances(
+        &self,
+        number: u64,
+    ) -> Result<Vec<CodeScanningAlertInstance>, GHASError> {
+        let route = format!(
+            "/repos/{owner}/{repo}/code-scanning/alerts/{number}/instances",
+            owner = self.repository.owner(),
+            repo = self.repository.name(),
+            number = number
+        );
+
+        let page: Page<CodeScanningAlertInstance> = self
+            .crab
+            .get(route, Some(&serde_json::json!({ "per_page": 100 })))
+            .await?;
+        Ok(self.crab.all_pages(page).await?)
+    }
+
     /// Get a list of code scanning analyses for a repository
-    pub fn analyses(&self) -> ListCodeScanningAnalyses {
+    pub fn analyses(&self) -> ListCodeScanningAnalyses<'_, '_> {
         ListCodeScanningAnalyses::new(self)
     }
+
+    /// Delete a code scanning analysis.
+    ///
+    /// GitHub returns the next analysis in the set which can be deleted
+    /// (`next_analysis_url` / `confirm_delete_url`) for chained deletion.
+    /// `confirm_delete` is required to delete the last analysis in a set, GitHub only checks
+    /// if the `confirm_delete` parameter is present so it is only sent when `true`.
+    pub async fn delete_analysis(
+        &self,
+        analysis_id: i32,
+        confirm_delete: bool,
+    ) -> Result<CodeScanningAnalysisDeletion, GHASError> {
+        let mut route = format!(
+            "/repos/{owner}/{repo}/code-scanning/analyses/{id}",
+            owner = self.repository.owner(),
+            repo = self.repository.name(),
+            id = analysis_id,
+        );
+        if confirm_delete {
+            route.push_str("?confirm_delete");
+        }
+
+        Ok(self.crab.delete(route, None::<&()>).await?)
+    }
+
+    /// Delete all the deletable code scanning analyses for a tool (e.g. `CodeQL`).
+    ///
+    /// The analyses are listed once and deleted from the most recent, following the
+    /// chain of deletable analyses GitHub returns. Without `confirm_delete` the last
+    /// analysis of each set is kept (GitHub requires confirmation to delete it).
+    /// Returns the number of deleted analyses.
+    pub async fn delete_analyses_for_tool(
+        &self,
+        tool_name: impl Into<String>,
+        confirm_delete: bool,
+    ) -> Result<usize, GHASError> {
+        let tool_name = tool_name.into();
+        // GitHub returns the analyses with the most recent first
+        let page = self.analyses().tool_name(&tool_name).send().await?;
+        let analyses = self.crab.all_pages(page).await?;
+
+        // Only the listed analyses can be deleted, a longer chain means GitHub keeps
+        // returning analyses which were already deleted
+        let max_deletions = analyses.len();
+        let mut deleted: HashSet<i32> = HashSet::new();
+        let mut count = 0;
+
+        for analysis in analyses.iter().filter(|a| a.deletable) {
+            if deleted.contains(&analysis.id) {
+                continue;
+            }
+
+            debug!("Deleting code scanning analysis :: {}", analysis.id);
+            let mut deletion = match self.delete_analysis(analysis.id, confirm_delete).await {
+                Ok(deletion) => deletion,
+                // The last analysis of a set can only be deleted with confirmation
+                Err(GHASError::Http { status: 400, .. }) if !confirm_delete => {
+                    debug!("Skipping last analysis of a set :: {}", analysis.id);
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            deleted.insert(analysis.id);
+            count += 1;
+
+            loop {
+                let next = if confirm_delete {
+                    deletion
+                        .confirm_delete_url
+                        .take()
+                        .or(deletion.next_analysis_url.take())
+                } else {
+                    deletion.next_analysis_url.take()
+                };
+                let Some(next) = next else {
+                    break;
+                };
+
+                if count >= max_deletions {
+                    return Err(GHASError::CodeScanningError(format!(
+                        "Analysis deletion chain exceeded the {} listed analyses",
+                        max_deletions
+                    )));
+                }
+
+                debug!("Deleting code scanning analysis :: {}", next);
+                deletion = self.crab.delete(&next, None::<&()>).await?;
+                if let Some(id) = analysis_id_from_url(&next) {
+                    deleted.insert(id);
+                }
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// List the CodeQL databases available for the repository
+    pub async fn list_codeql_databases(&self) -> OctoResult<Vec<ListCodeQLDatabase>> {
+        let route = format!(
+            "/repos/{owner}/{repo}/code-scanning/codeql/databases",
+            owner = self.repository.owner(),
+            repo = self.repository.name(),
+        );
+
+        self.crab.get(route, None::<&()>).await
+    }
+
+    /// Download the CodeQL database for a language and extract it into the `output` directory.
+    ///
+    /// The archive is streamed to `<output>/<language>.zip` and extracted into
+    /// `<output>/<language>`. The archive is removed after extraction unless
+    /// `keep_archive` is set. If extraction fails the partially extracted directory
+    /// is removed so a retry starts fresh.
+    ///
+    /// GitHub only keeps the latest database for each language and the API does not
+    /// support requesting a database for a specific commit / ref. Use
+    /// [`CodeQLDatabase::commit_sha`] on the returned database to check which commit
+    /// it was created from.
+    ///
+    /// This works for both GitHub Cloud and Enterprise Server as the request is made
+    /// against the REST API base URI the client was configured with.
+    pub async fn download_codeql_database(
+        &self,
+        language: impl Into<String>,
+        output: impl AsRef<Path>,
+        keep_archive: bool,
+    ) -> Result<CodeQLDatabase, GHASError> {
+        self.download_codeql_database_with_progress(language, output, keep_archive, |_, _| {})
+            .await
+    }
+
+    /// Download the CodeQL database for a language, reporting the download progress.
+    ///
+    /// The `progress` callback is called with the number of bytes downloaded so far
+    /// and the total size of the archive (from the `Content-Length` header, if present).
+    /// See [`CodeScanningHandler::download_codeql_database`] for details.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "github.codeql.database.download",
+            skip_all,
+            fields(
+                repo = %self.repository,
+                language = tracing::field::Empty,
+                duration_ms = tracing::field::Empty
+            )
+        )
+    )]
+    pub async fn download_codeql_database_with_progress<F>(
+        &self,
+        language: impl Into<String>,
+        output: impl AsRef<Path>,
+        keep_archive: bool,
+        mut progress: F,
+    ) -> Result<CodeQLDatabase, GHASError>
+    where
+        F: FnMut(u64, Option<u64>),
+    {
+        let _timer = SpanTimer::start();
+        let language = language.into();
+        spans::record("language", &language);
+        let output = output.as_ref();
+        let route = format!(
+            "/repos/{owner}/{repo}/code-scanning/codeql/databases/{language}",
+            owner = self.repository.owner(),
+            repo = self.repository.name(),
+            language = language
+        );
+
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            http::header::ACCEPT,
+            http::HeaderValue::from_static("application/zip"),
+        );
+
+        debug!("Downloading CodeQL database :: {}", route);
+        let response = self.crab._get_with_headers(route, Some(headers)).await?;
+        let headers = response.headers().clone();
+        let response = match octocrab::map_github_error(response).await {
+            Ok(response) => response,
+            Err(e) => {
+                let error = GHASError::from(e).with_retry_after(&headers);
+                if let GHASError::NotFound { source, .. } = error {
+                    return Err(GHASError::NotFound {
+                        message: format!(
+                            "No CodeQL database found for `{}` in {} (the endpoint might not be available on this GitHub instance)",
+                            language, self.repository
+                        ),
+                        source,
+                    });
+                }
+                return Err(error);
+            }
+        };
+
+        tokio::fs::create_dir_all(output).await?;
+        let archive = output.join(format!("{}.zip", language));
+        debug!("Writing CodeQL database archive :: {}", archive.display());
+
+        let total = response
+            .headers()
+            .get(http::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+
+        // Stream the body to disk so the archive is never fully held in memory
+        let written: Result<(), GHASError> = async {
+            let mut file = tokio::fs::File::create(&archive).await?;
+            let mut body = response.into_body();
+            let mut downloaded: u64 = 0;
+            progress(downloaded, total);
+            while let Some(frame) = body.frame().await {
+                if let Ok(data) = frame?.into_data() {
+                    file.write_all(&data).await?;
+                    downloaded += data.len() as u64;
+                    progress(downloaded, total);
+                }
+            }
+            file.flush().await?;
+
+            match total {
+                Some(total) if downloaded != total => Err(GHASError::CodeQLDatabaseError(format!(
+                    "Incomplete CodeQL database download ({} of {} bytes)",
+                    downloaded, total
+                ))),
+                _ => Ok(()),
+            }
+        }
+        .await;
+        if let Err(error) = written {
+            // Never leave a partial archive behind
+            let _ = tokio::fs::remove_file(&archive).await;
+            return Err(error);
+        }
+
+        // Extracting the archive is blocking (zip)
+        let repository = self.repository.clone();
+        let output = output.join(&language);
+        let database = tokio::task::spawn_blocking(move || {
+            let extracted = extract_database(&archive, &output, |path| {
+                CodeQLDatabase::init()
+                    .repository(&repository)
+                    .path(path.display().to_string())
+                    .build()
+            });
+            if !keep_archive {
+                debug!("Removing CodeQL database archive :: {}", archive.display());
+                std::fs::remove_file(&archive)?;
+            }
+            extracted
+        })
+        .await??;
+        debug!(
+            "Downloaded CodeQL database :: {} (commit: {:?})",
+            database,
+            database.commit_sha()
+        );
+        Ok(database)
+    }
+}
+
+/// Extract a downloaded CodeQL database archive into `output`.
+///
+/// A database previously downloaded to `output` is replaced, any other existing
+/// content is left untouched and an error is returned.
+fn extract_database<F>(archive: &Path, output: &Path, load: F) -> Result<CodeQLDatabase, GHASError>
+where
+    F: FnOnce(&Path) -> Result<CodeQLDatabase, GHASError>,
+{
+    let previous = WalkDir::new(output)
+        .min_depth(1)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .any(|e| e.file_name() == "codeql-database.yml");
+    if previous {
+        debug!("Replacing CodeQL database :: {}", output.display());
+        std::fs::remove_dir_all(output)?;
+    }
+
+    let file = std::fs::File::open(archive)?;
+    CodeQLDatabase::extract_archive(file, output, load)
+}
+
+/// Encode a SARIF file for uploading (gzip compressed and base64 encoded)
+fn encode_sarif(data: &[u8]) -> Result<String, GHASError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    let compressed = encoder.finish()?;
+
+    Ok(base64::engine::general_purpose::STANDARD.encode(compressed))
+}
+
+/// Get the analysis ID from an analysis URL (`.../code-scanning/analyses/{id}?confirm_delete`)
+fn analysis_id_from_url(url: &str) -> Option<i32> {
+    url.split('?').next()?.rsplit('/').next()?.parse().ok()
 }
 
 /// List Code Scanning Analyses
@@ -60,9 +561,11 @@ pub struct ListCodeScanningAlerts<'octo, 'b> {
     handler: &'b CodeScanningHandler<'octo>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    state: Option<String>,
+    state: Option<CodeScanningAlertState>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    severity: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     per_page: Option<u8>,
@@ -74,8 +577,9 @@ impl<'octo, 'b> ListCodeScanningAlerts<'octo, 'b> {
     pub(crate) fn new(handler: &'b CodeScanningHandler<'octo>) -> Self {
         Self {
             handler,
-            state: Some(String::from("open")),
+            state: Some(CodeScanningAlertState::Open),
             tool_name: None,
+            severity: None,
             // Default to 100 per page
             per_page: Some(100),
             // Default to page 1
@@ -84,8 +588,8 @@ impl<'octo, 'b> ListCodeScanningAlerts<'octo, 'b> {
     }
 
     /// Set the state of the code scanning alert
-    pub fn state(mut self, state: &str) -> Self {
-        self.state = Some(state.to_string());
+    pub fn state(mut self, state: impl Into<CodeScanningAlertState>) -> Self {
+        self.state = Some(state.into());
         self
     }
 
@@ -95,6 +599,13 @@ impl<'octo, 'b> ListCodeScanningAlerts<'octo, 'b> {
         self
     }
 
+    /// Set the severity of the code scanning alerts (`critical`, `high`, `medium`,
+    /// `low`, `warning`, `note` or `error`)
+    pub fn severity(mut self, severity: &str) -> Self {
+        self.severity = Some(severity.to_string());
+        self
+    }
+
     /// Set the number of items per page
     pub fn per_page(mut self, per_page: impl Into<u8>) -> Self {
         self.per_page = Some(per_page.into());
@@ -117,6 +628,304 @@ impl<'octo, 'b> ListCodeScanningAlerts<'octo, 'b> {
 
         self.handler.crab.get(route, Some(&self)).await
     }
+
+    /// Send the request and follow the pagination to return every alert
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "github.codescanning.alerts",
+            skip_all,
+            fields(
+                repo = %self.handler.repository,
+                duration_ms = tracing::field::Empty
+            )
+        )
+    )]
+    pub async fn all(self) -> Result<Vec<CodeScanningAlert>, GHASError> {
+        let _timer = SpanTimer::start();
+        let crab = self.handler.crab;
+        let page = self.send().await?;
+        Ok(crab.all_pages(page).await?)
+    }
+}
+
+/// Update a Code Scanning Alert
+#[derive(Debug, serde::Serialize)]
+pub struct UpdateCodeScanningAlert<'octo, 'b> {
+    #[serde(skip)]
+    handler: &'b CodeScanningHandler<'octo>,
+    #[serde(skip)]
+    number: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<CodeScanningAlertState>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dismissed_reason: Option<CodeScanningDismissedReason>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dismissed_comment: Option<String>,
+}
+
+impl<'octo, 'b> UpdateCodeScanningAlert<'octo, 'b> {
+    pub(crate) fn new(handler: &'b CodeScanningHandler<'octo>, number: u64) -> Self {
+        Self {
+            handler,
+            number,
+            state: None,
+            dismissed_reason: None,
+            dismissed_comment: None,
+        }
+    }
+
+    /// Set the state of the alert (`open` or `dismissed`)
+    pub fn state(mut self, state: impl Into<CodeScanningAlertState>) -> Self {
+        self.state = Some(state.into());
+        self
+    }
+
+    /// Set the dismissed reason of the alert (`false positive`, `won't fix`, or
+    /// `used in tests`). If no state is set, the state will be set to `dismissed`.
+    pub fn dismissed_reason(mut self, reason: impl Into<CodeScanningDismissedReason>) -> Self {
+        self.dismissed_reason = Some(reason.into());
+        if self.state.is_none() {
+            self.state = Some(CodeScanningAlertState::Dismissed);
+        }
+        self
+    }
+
+    /// Set the dismissed comment
+    pub fn dismissed_comment(mut self, comment: impl Into<String>) -> Self {
+        self.dismissed_comment = Some(comment.into());
+        self
+    }
+
+    /// Validate the update request before sending it
+    fn validate(&self) -> Result<(), GHASError> {
+        match &self.state {
+            Some(CodeScanningAlertState::Open) => Ok(()),
+            Some(CodeScanningAlertState::Dismissed) if self.dismissed_reason.is_none() => {
+                Err(GHASError::CodeScanningError(String::from(
+                    "Dismissed reason is required when dismissing an alert",
+                )))
+            }
+            Some(CodeScanningAlertState::Dismissed) => Ok(()),
+            Some(state) => Err(GHASError::CodeScanningError(format!(
+                "Alerts can only be updated to `open` or `dismissed`, not `{}`",
+                state
+            ))),
+            None => Err(GHASError::CodeScanningError(String::from(
+                "State is required to update an alert",
+            ))),
+        }
+    }
+
+    /// Send the request and return the updated alert
+    pub async fn send(self) -> Result<CodeScanningAlert, GHASError> {
+        self.validate()?;
+
+        let route = format!(
+            "/repos/{owner}/{repo}/code-scanning/alerts/{number}",
+            owner = self.handler.repository.owner(),
+            repo = self.handler.repository.name(),
+            number = self.number
+        );
+
+        Ok(self.handler.crab.patch(route, Some(&self)).await?)
+    }
+}
+
+/// Update the Code Scanning default setup configuration
+/// https://docs.github.com/en/rest/code-scanning/code-scanning?apiVersion=2022-11-28#update-a-code-scanning-default-setup-configuration
+#[derive(Debug, serde::Serialize)]
+pub struct UpdateCodeScanningConfiguration<'octo, 'b> {
+    #[serde(skip)]
+    handler: &'b CodeScanningHandler<'octo>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<CodeScanningConfigurationState>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    runner_type: Option<CodeScanningRunnerType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    runner_label: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query_suite: Option<CodeScanningQuerySuite>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    threat_model: Option<CodeScanningThreatModel>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    languages: Option<Vec<String>>,
+}
+
+impl<'octo, 'b> UpdateCodeScanningConfiguration<'octo, 'b> {
+    pub(crate) fn new(handler: &'b CodeScanningHandler<'octo>) -> Self {
+        Self {
+            handler,
+            state: None,
+            runner_type: None,
+            runner_label: None,
+            query_suite: None,
+            threat_model: None,
+            languages: None,
+        }
+    }
+
+    /// Set the state of default setup (`configured` or `not-configured`)
+    pub fn state(mut self, state: impl Into<CodeScanningConfigurationState>) -> Self {
+        self.state = Some(state.into());
+        self
+    }
+
+    /// Set the runner type (`standard` or `labeled`)
+    pub fn runner_type(mut self, runner_type: impl Into<CodeScanningRunnerType>) -> Self {
+        self.runner_type = Some(runner_type.into());
+        self
+    }
+
+    /// Set the runner label, the runner type will be set to `labeled`
+    pub fn runner_label(mut self, label: impl Into<String>) -> Self {
+        self.runner_label = Some(label.into());
+        self.runner_type = Some(CodeScanningRunnerType::Labeled);
+        self
+    }
+
+    /// Set the query suite (`default` or `extended`)
+    pub fn query_suite(mut self, query_suite: impl Into<CodeScanningQuerySuite>) -> Self {
+        self.query_suite = Some(query_suite.into());
+        self
+    }
+
+    /// Set the threat model (`remote` or `remote_and_local`)
+    pub fn threat_model(mut self, threat_model: impl Into<CodeScanningThreatModel>) -> Self {
+        self.threat_model = Some(threat_model.into());
+        self
+    }
+
+    /// Add a language to analyze
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.languages
+            .get_or_insert_with(Vec::new)
+            .push(language.into());
+        self
+    }
+
+    /// Add multiple languages to analyze
+    pub fn languages<I, S>(mut self, languages: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.languages
+            .get_or_insert_with(Vec::new)
+            .extend(languages.into_iter().map(Into::into));
+        self
+    }
+
+    /// Analyze all the languages of the repository which default setup supports.
+    ///
+    /// The repository languages are fetched from GitHub and mapped to the default setup
+    /// languages, unsupported languages (e.g. Markdown) are skipped with a warning.
+    pub async fn all_supported_languages(self) -> Result<Self, GHASError> {
+        let languages = list_languages(self.handler.crab, self.handler.repository).await?;
+
+        let supported = default_setup_languages(&languages);
+        if supported.is_empty() {
+            return Err(GHASError::Unsupported(format!(
+                "No languages supported by default setup found in {}",
+                self.handler.repository
+            )));
+        }
+        Ok(self.languages(supported))
+    }
+
+    /// Validate the update request before sending it (unknown values are rejected)
+    fn validate(&self) -> Result<(), GHASError> {
+        for language in self.languages.iter().flatten() {
+            if !DEFAULT_SETUP_LANGUAGES.contains(&language.as_str()) {
+                return Err(GHASError::Unsupported(format!(
+                    "Language `{}` is not supported by default setup (expected one of: {})",
+                    language,
+                    DEFAULT_SETUP_LANGUAGES.join(", ")
+                )));
+            }
+        }
+        if let Some(CodeScanningConfigurationState::Unknown(state)) = &self.state {
+            return Err(GHASError::CodeScanningError(format!(
+                "Unknown default setup state `{}` (expected `configured` or `not-configured`)",
+                state
+            )));
+        }
+        if let Some(CodeScanningRunnerType::Unknown(runner_type)) = &self.runner_type {
+            return Err(GHASError::CodeScanningError(format!(
+                "Unknown runner type `{}` (expected `standard` or `labeled`)",
+                runner_type
+            )));
+        }
+        if let Some(CodeScanningQuerySuite::Unknown(query_suite)) = &self.query_suite {
+            return Err(GHASError::CodeScanningError(format!(
+                "Unknown query suite `{}` (expected `default` or `extended`)",
+                query_suite
+            )));
+        }
+        if let Some(CodeScanningThreatModel::Unknown(threat_model)) = &self.threat_model {
+            return Err(GHASError::CodeScanningError(format!(
+                "Unknown threat model `{}` (expected `remote` or `remote_and_local`)",
+                threat_model
+            )));
+        }
+        if self.runner_type == Some(CodeScanningRunnerType::Standard) && self.runner_label.is_some()
+        {
+            return Err(GHASError::CodeScanningError(String::from(
+                "A runner label can only be used with the `labeled` runner type",
+            )));
+        }
+        Ok(())
+    }
+
+    /// Send the request, returning the workflow run triggered by the update (if any)
+    pub async fn send(self) -> Result<CodeScanningConfigurationUpdate, GHASError> {
+        self.validate()?;
+
+        let route = format!(
+            "/repos/{owner}/{repo}/code-scanning/default-setup",
+            owner = self.handler.repository.owner(),
+            repo = self.handler.repository.name()
+        );
+
+        Ok(self.handler.crab.patch(route, Some(&self)).await?)
+    }
+}
+
+/// Languages supported by Code Scanning default setup
+const DEFAULT_SETUP_LANGUAGES: &[&str] = &[
+    "actions",
+    "c-cpp",
+    "csharp",
+    "go",
+    "java-kotlin",
+    "javascript",
+    "javascript-typescript",
+    "python",
+    "ruby",
+    "swift",
+    "typescript",
+];
+
+/// Map the repository (linguist) languages to default setup languages (most bytes first)
+fn default_setup_languages(languages: &GitHubLanguages) -> Vec<String> {
+    let mut supported: Vec<String> = Vec::new();
+    for language in CodeQLLanguages::default().from_github_languages(languages) {
+        match language.default_setup_language() {
+            Some(name) if !supported.iter().any(|l| l == name) => supported.push(name.to_string()),
+            Some(_) => {}
+            None => warn!("Language `{}` is not supported by default setup", language),
+        }
+    }
+    supported
 }
 
 /// List code scanning analyses
@@ -199,3 +1008,431 @@ impl<'octo, 'b> ListCodeScanningAnalyses<'octo, 'b> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use octocrab::Octocrab;
+
+    use super::{
+        analysis_id_from_url, default_setup_languages, extract_database, CodeScanningHandler,
+    };
+    use crate::{
+        codeql::test_utils::{database_archive, database_config},
+        codescanning::models::{CodeScanningAlertState, CodeScanningDismissedReason},
+        octokit::test_utils::mock_server,
+        CodeQLDatabase, GHASError, GitHub, Repository,
+    };
+
+    fn analysis(server: &str, id: i32, deletable: bool) -> serde_json::Value {
+        serde_json::json!({
+            "ref": "refs/heads/main",
+            "commit_sha": "d6e3b7a4e1c4f6a8c2b1e0f9a7d5c3b2a1e0f9d8",
+            "analysis_key": ".github/workflows/codeql.yml:analyze",
+            "environment": "{}",
+            "error": "",
+            "category": ".github/workflows/codeql.yml:analyze/language:rust",
+            "created_at": "2024-01-01T00:00:00Z",
+            "results_count": 0,
+            "rules_count": 10,
+            "id": id,
+            "url": analysis_url(server, id),
+            "sarif_id": "6c81cd8e-b078-4ac3-a3be-1dad7dbd0b53",
+            "tool": { "name": "CodeQL", "guid": null, "version": "2.17.0" },
+            "deletable": deletable,
+            "warning": ""
+        })
+    }
+
+    fn analysis_url(server: &str, id: i32) -> String {
+        format!(
+            "{}/repos/geekmasher/ghastoolkit-rs/code-scanning/analyses/{}",
+            server, id
+        )
+    }
+
+    fn deletion(next: Option<String>, confirm: Option<String>) -> String {
+        serde_json::json!({ "next_analysis_url": next, "confirm_delete_url": confirm }).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_update_configuration() {
+        let crab = Octocrab::default();
+        let repo = Repository::new("geekmasher", "ghastoolkit-rs");
+        let handler = CodeScanningHandler::new(&crab, &repo);
+
+        let update = handler
+            .update_configuration()
+            .state("configured")
+            .query_suite("extended")
+            .threat_model("remote_and_local")
+            .runner_label("code-scanning")
+            .language("python")
+            .language("javascript-typescript");
+        assert!(update.validate().is_ok());
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"state":"configured","runner_type":"labeled","runner_label":"code-scanning","query_suite":"extended","threat_model":"remote_and_local","languages":["python","javascript-typescript"]}"#
+        );
+
+        let update = handler.update_configuration().state("not-configured");
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"state":"not-configured"}"#
+        );
+
+        assert!(handler
+            .update_configuration()
+            .state("configgured")
+            .validate()
+            .is_err());
+        assert!(handler
+            .update_configuration()
+            .query_suite("security-and-quality")
+            .validate()
+            .is_err());
+        assert!(handler
+            .update_configuration()
+            .language("rust")
+            .validate()
+            .is_err());
+        assert!(handler
+            .update_configuration()
+            .runner_label("label")
+            .runner_type("standard")
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_default_setup_languages() {
+        let languages = [
+            ("Markdown", 100_000),
+            ("TypeScript", 5_000),
+            ("Kotlin", 1_000),
+            ("JavaScript", 20_000),
+            ("Java", 30_000),
+            ("Shell", 200),
+        ]
+        .into_iter()
+        .map(|(name, bytes)| (name.to_string(), bytes))
+        .collect();
+
+        assert_eq!(
+            default_setup_languages(&languages),
+            vec!["java-kotlin", "javascript-typescript"]
+        );
+        assert!(default_setup_languages(&Default::default()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_all_supported_languages() {
+        let repo = Repository::new("geekmasher", "ghastoolkit-rs");
+        let (server, requests) = mock_server(|_| {
+            vec![(
+                "200 OK",
+                r#"{"Markdown": 5000, "Python": 1000, "Kotlin": 2000, "Java": 500}"#.to_string(),
+            )]
+        });
+        let github = GitHub::init().base_uri(&server).unwrap().build().unwrap();
+        let handler = github.code_scanning(&repo);
+        let update = handler
+            .update_configuration()
+            .all_supported_languages()
+            .await
+            .unwrap();
+        assert_eq!(
+            update.languages,
+            Some(vec![String::from("java-kotlin"), String::from("python")])
+        );
+        assert!(requests.lock().unwrap()[0]
+            .starts_with("GET /repos/geekmasher/ghastoolkit-rs/languages"));
+    }
+
+    #[tokio::test]
+    async fn test_update_alert_validation() {
+        let crab = Octocrab::default();
+        let repo = Repository::new("geekmasher", "ghastoolkit-rs");
+        let handler = CodeScanningHandler::new(&crab, &repo);
+
+        assert!(handler.update_alert(1).validate().is_err());
+        assert!(handler
+            .update_alert(1)
+            .state("dismissed")
+            .validate()
+            .is_err());
+        assert!(handler.update_alert(1).state("fixed").validate().is_err());
+        assert!(handler.update_alert(1).state("open").validate().is_ok());
+
+        let update = handler
+            .update_alert(1)
+            .dismissed_reason("wont_fix")
+            .dismissed_comment("Accepted risk");
+        assert!(update.validate().is_ok());
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            serde_json::json!({
+                "state": "dismissed",
+                "dismissed_reason": "won't fix",
+                "dismissed_comment": "Accepted risk"
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode_sarif() {
+        use base64::Engine;
+        use std::io::Read;
+
+        // The file is uploaded byte for byte (nothing the model doesn't know is dropped)
+        let data = crate::utils::sarif::tests::SARIF.as_bytes();
+        let encoded = super::encode_sarif(data).unwrap();
+
+        let compressed = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .unwrap();
+        let mut json = String::new();
+        flate2::read::GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut json)
+            .unwrap();
+        assert_eq!(json.as_bytes(), data);
+    }
+
+    #[test]
+    fn test_alert_enums() {
+        let state: CodeScanningAlertState = serde_json::from_str(r#""fixed""#).unwrap();
+        assert_eq!(state, CodeScanningAlertState::Fixed);
+        let state: CodeScanningAlertState = serde_json::from_str(r#""archived""#).unwrap();
+        assert_eq!(
+            state,
+            CodeScanningAlertState::Unknown(String::from("archived"))
+        );
+
+        let reason: CodeScanningDismissedReason =
+            serde_json::from_str(r#""used in tests""#).unwrap();
+        assert_eq!(reason, CodeScanningDismissedReason::UsedInTests);
+        assert_eq!(
+            CodeScanningDismissedReason::from("Won't Fix"),
+            CodeScanningDismissedReason::WontFix
+        );
+        assert_eq!(
+            CodeScanningDismissedReason::from("false-positive"),
+            CodeScanningDismissedReason::FalsePositive
+        );
+    }
+
+    #[test]
+    fn test_extract_database() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+
+        let archive = root.join("python.zip");
+        std::fs::write(
+            &archive,
+            database_archive(database_config("python").as_bytes()),
+        )
+        .unwrap();
+
+        let load = |path: &Path| {
+            CodeQLDatabase::init()
+                .name("python")
+                .path(path.display().to_string())
+                .build()
+        };
+
+        let output = root.join("python");
+        let database = extract_database(&archive, &output, load).unwrap();
+        assert_eq!(database.path(), &output.join("codeql_db"));
+
+        // A previous download is replaced
+        let database = extract_database(&archive, &output, load).unwrap();
+        assert_eq!(database.language(), "python");
+    }
+
+    #[test]
+    fn test_extract_database_cleanup() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+
+        // Truncated / corrupt archive
+        let archive = root.join("python.zip");
+        std::fs::write(&archive, b"PK\x03\x04 not a zip").unwrap();
+
+        let load = |path: &Path| {
+            CodeQLDatabase::init()
+                .name("python")
+                .path(path.display().to_string())
+                .build()
+        };
+
+        let output = root.join("python");
+        assert!(extract_database(&archive, &output, load).is_err());
+        assert!(!output.exists());
+
+        // Other content in the output directory is never removed
+        std::fs::create_dir_all(output.join("other")).unwrap();
+        assert!(extract_database(&archive, &output, load).is_err());
+        assert!(output.join("other").exists());
+    }
+
+    #[tokio::test]
+    async fn test_delete_analyses_for_tool() {
+        let repo = Repository::new("geekmasher", "ghastoolkit-rs");
+
+        // Without confirmation the chain stops at the last analysis of the set
+        let (server, requests) = mock_server(|server| {
+            vec![
+                (
+                    "200 OK",
+                    serde_json::json!([
+                        analysis(server, 3, true),
+                        analysis(server, 2, false),
+                        analysis(server, 1, false)
+                    ])
+                    .to_string(),
+                ),
+                (
+                    "200 OK",
+                    deletion(
+                        Some(analysis_url(server, 2)),
+                        Some(format!("{}?confirm_delete", analysis_url(server, 2))),
+                    ),
+                ),
+                (
+                    "200 OK",
+                    deletion(
+                        None,
+                        Some(format!("{}?confirm_delete", analysis_url(server, 1))),
+                    ),
+                ),
+            ]
+        });
+        let github = GitHub::init().base_uri(&server).unwrap().build().unwrap();
+        let deleted = github
+            .code_scanning(&repo)
+            .delete_analyses_for_tool("CodeQL", false)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 2);
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 3);
+        assert!(
+            requests[0].starts_with("GET /repos/geekmasher/ghastoolkit-rs/code-scanning/analyses")
+        );
+        assert!(requests[1]
+            .starts_with("DELETE /repos/geekmasher/ghastoolkit-rs/code-scanning/analyses/3 "));
+        assert!(requests[2]
+            .starts_with("DELETE /repos/geekmasher/ghastoolkit-rs/code-scanning/analyses/2 "));
+
+        // With confirmation the whole set is deleted (and analyses are only listed once)
+        let (server, requests) = mock_server(|server| {
+            vec![
+                (
+                    "200 OK",
+                    serde_json::json!([
+                        analysis(server, 3, true),
+                        analysis(server, 2, true),
+                        analysis(server, 1, true)
+                    ])
+                    .to_string(),
+                ),
+                ("200 OK", deletion(Some(analysis_url(server, 2)), None)),
+                (
+                    "200 OK",
+                    deletion(
+                        None,
+                        Some(format!("{}?confirm_delete", analysis_url(server, 1))),
+                    ),
+                ),
+                ("200 OK", deletion(None, None)),
+            ]
+        });
+        let github = GitHub::init().base_uri(&server).unwrap().build().unwrap();
+        let deleted = github
+            .code_scanning(&repo)
+            .delete_analyses_for_tool("CodeQL", true)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 3);
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 4);
+        assert!(requests[1].contains("/analyses/3?confirm_delete "));
+        assert!(requests[3].contains("/analyses/1?confirm_delete"));
+    }
+
+    #[tokio::test]
+    async fn test_delete_analyses_for_tool_bounded() {
+        let repo = Repository::new("geekmasher", "ghastoolkit-rs");
+
+        // A chain which keeps returning the same analysis is not followed forever
+        let (server, requests) = mock_server(|server| {
+            vec![
+                (
+                    "200 OK",
+                    serde_json::json!([analysis(server, 1, true)]).to_string(),
+                ),
+                ("200 OK", deletion(Some(analysis_url(server, 1)), None)),
+            ]
+        });
+        let github = GitHub::init().base_uri(&server).unwrap().build().unwrap();
+        let error = github
+            .code_scanning(&repo)
+            .delete_analyses_for_tool("CodeQL", true)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, GHASError::CodeScanningError(_)));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        assert_eq!(
+            analysis_id_from_url(
+                "https://api.github.com/repos/o/r/code-scanning/analyses/41?confirm_delete"
+            ),
+            Some(41)
+        );
+        assert_eq!(
+            analysis_id_from_url("https://api.github.com/repos/o/r"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_alert_instances() {
+        let repo = Repository::new("geekmasher", "ghastoolkit-rs");
+        let (server, requests) = mock_server(|_| {
+            vec![(
+                "200 OK",
+                serde_json::json!([{
+                    "ref": "refs/heads/main",
+                    "analysis_key": ".github/workflows/codeql.yml:analyze",
+                    "category": ".github/workflows/codeql.yml:analyze/language:rust",
+                    "environment": "{}",
+                    "state": "open",
+                    "commit_sha": "d6e3b7a4e1c4f6a8c2b1e0f9a7d5c3b2a1e0f9d8",
+                    "message": { "text": "Hard-coded credentials" },
+                    "location": {
+                        "path": "src/main.rs",
+                        "start_line": 10,
+                        "end_line": 10,
+                        "start_column": 5,
+                        "end_column": 20
+                    },
+                    "classifications": []
+                }])
+                .to_string(),
+            )]
+        });
+        let github = GitHub::init().base_uri(&server).unwrap().build().unwrap();
+        let instances = github
+            .code_scanning(&repo)
+            .alert_instances(42)
+            .await
+            .unwrap();
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].state, CodeScanningAlertState::Open);
+        assert_eq!(instances[0].location.path, "src/main.rs");
+        let requests = requests.lock().unwrap().clone();
+        assert!(requests[0]
+            .starts_with("GET /repos/geekmasher/ghastoolkit-rs/code-scanning/alerts/42/instances"));
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use serde::{Deserialize, Serialize};
 
 use crate::octokit::models::{Location, Message};
@@ -14,8 +16,8 @@ pub struct CodeScanningAlert {
     pub url: String,
     /// The HTML URL of the alert.
     pub html_url: String,
-    /// The state of the alert. Can be "open", "fixed", etc.
-    pub state: String,
+    /// The state of the alert.
+    pub state: CodeScanningAlertState,
     /// Fixed at time.
     pub fixed_at: Option<String>,
     /// Dismissed by user.
@@ -23,19 +25,120 @@ pub struct CodeScanningAlert {
     /// Dismissed at time.
     pub dismissed_at: Option<chrono::DateTime<chrono::Utc>>,
     /// Dismissed reason.
-    pub dismissed_reason: Option<String>,
+    pub dismissed_reason: Option<CodeScanningDismissedReason>,
     /// Dismissed comment.
     pub dismissed_comment: Option<String>,
     /// The rule that triggered the alert.
     pub rule: CodeScanningAlertRule,
     /// The tool that generated the alert.
     pub tool: CodeScanningAlertTool,
-    /// The most recent instance of the alert.
-    pub most_recent_instance: CodeScanningAlertInstance,
+    /// The most recent instance of the alert (not always returned by GitHub).
+    #[serde(default)]
+    pub most_recent_instance: Option<CodeScanningAlertInstance>,
     /// URL to the instances of the alert.
+    #[serde(default)]
     pub instances_url: String,
 }
 
+/// Code Scanning Alert State
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum CodeScanningAlertState {
+    /// Open Alert
+    #[serde(rename = "open")]
+    Open,
+    /// Closed Alert (dismissed or fixed, only used to filter alerts)
+    #[serde(rename = "closed")]
+    Closed,
+    /// Dismissed Alert
+    #[serde(rename = "dismissed")]
+    Dismissed,
+    /// Fixed Alert
+    #[serde(rename = "fixed")]
+    Fixed,
+    /// Unknown state (new values GitHub might add)
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl Display for CodeScanningAlertState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodeScanningAlertState::Open => write!(f, "Open"),
+            CodeScanningAlertState::Closed => write!(f, "Closed"),
+            CodeScanningAlertState::Dismissed => write!(f, "Dismissed"),
+            CodeScanningAlertState::Fixed => write!(f, "Fixed"),
+            CodeScanningAlertState::Unknown(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<&str> for CodeScanningAlertState {
+    fn from(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "open" => CodeScanningAlertState::Open,
+            "closed" => CodeScanningAlertState::Closed,
+            "dismissed" => CodeScanningAlertState::Dismissed,
+            "fixed" => CodeScanningAlertState::Fixed,
+            _ => CodeScanningAlertState::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for CodeScanningAlertState {
+    fn from(value: String) -> Self {
+        CodeScanningAlertState::from(value.as_str())
+    }
+}
+
+/// Code Scanning Alert Dismissed Reason
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum CodeScanningDismissedReason {
+    /// False Positive
+    #[serde(rename = "false positive")]
+    FalsePositive,
+    /// Won't Fix
+    #[serde(rename = "won't fix")]
+    WontFix,
+    /// Used in Tests
+    #[serde(rename = "used in tests")]
+    UsedInTests,
+    /// Unknown reason (new values GitHub might add)
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl Display for CodeScanningDismissedReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodeScanningDismissedReason::FalsePositive => write!(f, "False Positive"),
+            CodeScanningDismissedReason::WontFix => write!(f, "Won't Fix"),
+            CodeScanningDismissedReason::UsedInTests => write!(f, "Used in Tests"),
+            CodeScanningDismissedReason::Unknown(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<&str> for CodeScanningDismissedReason {
+    fn from(value: &str) -> Self {
+        // Accept the API values along with common variations (`wont_fix`, `won't fix`)
+        let normalized = value.trim().to_lowercase().replace(['_', '-'], " ");
+        match normalized.as_str() {
+            "false positive" => CodeScanningDismissedReason::FalsePositive,
+            "won't fix" | "wont fix" => CodeScanningDismissedReason::WontFix,
+            "used in tests" => CodeScanningDismissedReason::UsedInTests,
+            _ => CodeScanningDismissedReason::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for CodeScanningDismissedReason {
+    fn from(value: String) -> Self {
+        CodeScanningDismissedReason::from(value.as_str())
+    }
+}
+
 /// A code scanning alert rule.
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
 pub struct CodeScanningAlertRule {
@@ -43,11 +146,17 @@ pub struct CodeScanningAlertRule {
     pub id: String,
     /// The severity of the rule.
     pub severity: String,
+    /// The security severity of the rule (`critical`, `high`, `medium` or `low`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_severity_level: Option<String>,
     /// The tags of the rule.
+    #[serde(default)]
     pub tags: Vec<String>,
     /// The description of the rule.
+    #[serde(default)]
     pub description: String,
     /// The name of the rule.
+    #[serde(default)]
     pub name: String,
 }
 
@@ -57,13 +166,16 @@ pub struct CodeScanningAlertInstance {
     /// The reference to the branch or tag the analysis was performed on.
     pub r#ref: String,
     /// Analysis key.
+    #[serde(default)]
     pub analysis_key: String,
     /// Category.
+    #[serde(default)]
     pub category: String,
     /// Environment.
+    #[serde(default)]
     pub environment: String,
     /// The state of the alert instance.
-    pub state: String,
+    pub state: CodeScanningAlertState,
     /// Commit SHA.
     pub commit_sha: String,
     /// Message.
@@ -71,6 +183,7 @@ pub struct CodeScanningAlertInstance {
     /// Location.
     pub location: Location,
     /// Classifications.
+    #[serde(default)]
     pub classifications: Vec<String>,
 }
 
@@ -81,13 +194,30 @@ pub struct CodeScanningAlertTool {
     pub name: String,
     /// The guid of the tool.
     pub guid: Option<String>,
-    /// The version of the tool.
-    pub version: String,
+    /// The version of the tool (not set for some third-party tools).
+    pub version: Option<String>,
 }
 
-/// A code scanning alert dismissed by.
+/// The user who dismissed a code scanning alert.
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
-pub struct CodeScanningAlertDismissedBy {}
+pub struct CodeScanningAlertDismissedBy {
+    /// The login of the user.
+    pub login: String,
+    /// The ID of the user.
+    pub id: u64,
+    /// The HTML URL of the user.
+    #[serde(default)]
+    pub html_url: String,
+    /// The type of the user (`User`, `Bot`, ...).
+    #[serde(default)]
+    pub r#type: String,
+}
+
+impl Display for CodeScanningAlertDismissedBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.login)
+    }
+}
 
 /// A code scanning analysis.
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
@@ -123,3 +253,393 @@ pub struct CodeScanningAnalysis {
     /// Warning message.
     pub warning: Option<String>,
 }
+
+/// Response of deleting a code scanning analysis.
+/// https://docs.github.com/en/rest/code-scanning/code-scanning?apiVersion=2022-11-28#delete-a-code-scanning-analysis-from-a-repository
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct CodeScanningAnalysisDeletion {
+    /// Next deletable analysis in the chain (without last analysis deletion confirmation).
+    pub next_analysis_url: Option<String>,
+    /// Next deletable analysis in the chain (with last analysis deletion confirmation).
+    pub confirm_delete_url: Option<String>,
+}
+
+/// Response of uploading a SARIF file.
+/// https://docs.github.com/en/rest/code-scanning/code-scanning?apiVersion=2022-11-28#upload-an-analysis-as-sarif-data
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct SarifUpload {
+    /// The SARIF upload ID
+    pub id: String,
+    /// The URL to get the processing status of the upload
+    pub url: String,
+}
+
+/// Code scanning default setup configuration of a repository.
+/// https://docs.github.com/en/rest/code-scanning/code-scanning?apiVersion=2022-11-28#get-a-code-scanning-default-setup-configuration
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct CodeScanningConfiguration {
+    /// The state of default setup.
+    pub state: CodeScanningConfigurationState,
+    /// The languages to analyze.
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// The type of runner to use.
+    #[serde(default)]
+    pub runner_type: Option<CodeScanningRunnerType>,
+    /// The label of the runner (only for `labeled` runners).
+    #[serde(default)]
+    pub runner_label: Option<String>,
+    /// The query suite to use.
+    #[serde(default)]
+    pub query_suite: Option<CodeScanningQuerySuite>,
+    /// The threat model to use.
+    #[serde(default)]
+    pub threat_model: Option<CodeScanningThreatModel>,
+    /// Updated at time.
+    #[serde(default)]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// The frequency of the periodic analysis.
+    #[serde(default)]
+    pub schedule: Option<String>,
+}
+
+/// Response of updating the code scanning default setup configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct CodeScanningConfigurationUpdate {
+    /// ID of the workflow run triggered by the update (if any).
+    #[serde(default)]
+    pub run_id: Option<u64>,
+    /// URL of the workflow run triggered by the update (if any).
+    #[serde(default)]
+    pub run_url: Option<String>,
+}
+
+/// Code Scanning default setup state
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum CodeScanningConfigurationState {
+    /// Default setup is enabled
+    #[serde(rename = "configured")]
+    Configured,
+    /// Default setup is disabled
+    #[serde(rename = "not-configured")]
+    NotConfigured,
+    /// Unknown state (new values GitHub might add)
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl Display for CodeScanningConfigurationState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodeScanningConfigurationState::Configured => write!(f, "configured"),
+            CodeScanningConfigurationState::NotConfigured => write!(f, "not-configured"),
+            CodeScanningConfigurationState::Unknown(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<&str> for CodeScanningConfigurationState {
+    fn from(value: &str) -> Self {
+        match value.trim().to_lowercase().replace('_', "-").as_str() {
+            "configured" | "enabled" => CodeScanningConfigurationState::Configured,
+            "not-configured" | "disabled" => CodeScanningConfigurationState::NotConfigured,
+            _ => CodeScanningConfigurationState::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for CodeScanningConfigurationState {
+    fn from(value: String) -> Self {
+        CodeScanningConfigurationState::from(value.as_str())
+    }
+}
+
+/// Code Scanning default setup query suite
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum CodeScanningQuerySuite {
+    /// Default query suite
+    #[serde(rename = "default")]
+    Default,
+    /// Extended query suite (`security-extended`)
+    #[serde(rename = "extended")]
+    Extended,
+    /// Unknown query suite (new values GitHub might add)
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl Display for CodeScanningQuerySuite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodeScanningQuerySuite::Default => write!(f, "default"),
+            CodeScanningQuerySuite::Extended => write!(f, "extended"),
+            CodeScanningQuerySuite::Unknown(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<&str> for CodeScanningQuerySuite {
+    fn from(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "default" => CodeScanningQuerySuite::Default,
+            "extended" | "security-extended" => CodeScanningQuerySuite::Extended,
+            _ => CodeScanningQuerySuite::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for CodeScanningQuerySuite {
+    fn from(value: String) -> Self {
+        CodeScanningQuerySuite::from(value.as_str())
+    }
+}
+
+/// Code Scanning default setup threat model
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum CodeScanningThreatModel {
+    /// Remote sources only
+    #[serde(rename = "remote")]
+    Remote,
+    /// Remote and local sources
+    #[serde(rename = "remote_and_local")]
+    RemoteAndLocal,
+    /// Unknown threat model (new values GitHub might add)
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl Display for CodeScanningThreatModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodeScanningThreatModel::Remote => write!(f, "remote"),
+            CodeScanningThreatModel::RemoteAndLocal => write!(f, "remote_and_local"),
+            CodeScanningThreatModel::Unknown(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<&str> for CodeScanningThreatModel {
+    fn from(value: &str) -> Self {
+        match value.trim().to_lowercase().replace('-', "_").as_str() {
+            "remote" => CodeScanningThreatModel::Remote,
+            "remote_and_local" | "local" => CodeScanningThreatModel::RemoteAndLocal,
+            _ => CodeScanningThreatModel::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for CodeScanningThreatModel {
+    fn from(value: String) -> Self {
+        CodeScanningThreatModel::from(value.as_str())
+    }
+}
+
+/// Code Scanning default setup runner type
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum CodeScanningRunnerType {
+    /// GitHub hosted runners
+    #[serde(rename = "standard")]
+    Standard,
+    /// Self-hosted runners with a label
+    #[serde(rename = "labeled")]
+    Labeled,
+    /// Unknown runner type (new values GitHub might add)
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl Display for CodeScanningRunnerType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodeScanningRunnerType::Standard => write!(f, "standard"),
+            CodeScanningRunnerType::Labeled => write!(f, "labeled"),
+            CodeScanningRunnerType::Unknown(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<&str> for CodeScanningRunnerType {
+    fn from(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "standard" => CodeScanningRunnerType::Standard,
+            "labeled" | "labelled" => CodeScanningRunnerType::Labeled,
+            _ => CodeScanningRunnerType::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for CodeScanningRunnerType {
+    fn from(value: String) -> Self {
+        CodeScanningRunnerType::from(value.as_str())
+    }
+}
+
+/// A CodeQL database stored on GitHub for a repository.
+/// https://docs.github.com/en/rest/code-scanning/code-scanning?apiVersion=2022-11-28#list-codeql-databases-for-a-repository
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct ListCodeQLDatabase {
+    /// The ID of the database.
+    pub id: u64,
+    /// The name of the database.
+    pub name: String,
+    /// The language of the database.
+    pub language: String,
+    /// The user who uploaded the database.
+    pub uploader: CodeQLDatabaseUploader,
+    /// The MIME type of the database file.
+    pub content_type: String,
+    /// The size of the database file in bytes.
+    pub size: u64,
+    /// Created at time.
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Updated at time.
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    /// The URL to download the database.
+    pub url: String,
+    /// The commit SHA of the repository at the time the database was created.
+    pub commit_oid: Option<String>,
+}
+
+/// The user who uploaded a CodeQL database.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct CodeQLDatabaseUploader {
+    /// The login of the user.
+    pub login: String,
+    /// The ID of the user.
+    pub id: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CodeScanningAlert, CodeScanningAlertState, CodeScanningConfiguration,
+        CodeScanningConfigurationState, CodeScanningDismissedReason, CodeScanningQuerySuite,
+        CodeScanningRunnerType,
+    };
+
+    #[test]
+    fn test_configuration() {
+        let config: CodeScanningConfiguration = serde_json::from_str(
+            r#"{
+                "state": "configured",
+                "languages": ["ruby", "python"],
+                "query_suite": "default",
+                "threat_model": "remote",
+                "updated_at": "2023-01-19T11:21:34Z",
+                "schedule": "weekly"
+            }"#,
+        )
+        .expect("Failed to parse configuration");
+
+        assert_eq!(config.state, CodeScanningConfigurationState::Configured);
+        assert_eq!(config.languages, vec!["ruby", "python"]);
+        assert_eq!(config.query_suite, Some(CodeScanningQuerySuite::Default));
+        assert!(config.runner_type.is_none());
+
+        assert_eq!(
+            CodeScanningRunnerType::from("labelled"),
+            CodeScanningRunnerType::Labeled
+        );
+        assert_eq!(
+            CodeScanningConfigurationState::from("configgured"),
+            CodeScanningConfigurationState::Unknown(String::from("configgured"))
+        );
+    }
+
+    #[test]
+    fn test_alert_missing_fields() {
+        let alert: CodeScanningAlert = serde_json::from_str(
+            r#"{
+                "number": 42,
+                "created_at": "2024-01-01T00:00:00Z",
+                "url": "https://api.github.com/repos/octocat/hello-world/code-scanning/alerts/42",
+                "html_url": "https://github.com/octocat/hello-world/code-scanning/42",
+                "state": "fixed",
+                "fixed_at": "2024-01-02T00:00:00Z",
+                "dismissed_by": null,
+                "dismissed_at": null,
+                "dismissed_reason": null,
+                "dismissed_comment": null,
+                "rule": { "id": "js/zipslip", "severity": "error", "description": "Arbitrary file write" },
+                "tool": { "name": "Third Party", "guid": null, "version": null }
+            }"#,
+        )
+        .expect("Failed to parse alert");
+
+        assert_eq!(alert.number, 42);
+        assert!(alert.most_recent_instance.is_none());
+        assert!(alert.rule.tags.is_empty());
+        assert!(alert.tool.version.is_none());
+    }
+
+    #[test]
+    fn test_alert_dismissed_by() {
+        let alert: CodeScanningAlert = serde_json::from_str(
+            r#"{
+                "number": 4,
+                "created_at": "2020-02-13T12:29:18Z",
+                "url": "https://api.github.com/repos/octocat/hello-world/code-scanning/alerts/4",
+                "html_url": "https://github.com/octocat/hello-world/code-scanning/4",
+                "state": "dismissed",
+                "fixed_at": null,
+                "dismissed_by": {
+                    "login": "octocat",
+                    "id": 1,
+                    "node_id": "MDQ6VXNlcjE=",
+                    "avatar_url": "https://github.com/images/error/octocat_happy.gif",
+                    "gravatar_id": "",
+                    "url": "https://api.github.com/users/octocat",
+                    "html_url": "https://github.com/octocat",
+                    "type": "User",
+                    "site_admin": false
+                },
+                "dismissed_at": "2020-02-14T12:29:18Z",
+                "dismissed_reason": "false positive",
+                "dismissed_comment": "This alert is not actually correct.",
+                "rule": {
+                    "id": "js/zipslip",
+                    "severity": "error",
+                    "tags": ["security", "external/cwe/cwe-022"],
+                    "description": "Arbitrary file write during zip extraction",
+                    "name": "js/zipslip"
+                },
+                "tool": { "name": "CodeQL", "guid": null, "version": "2.4.0" },
+                "most_recent_instance": {
+                    "ref": "refs/heads/main",
+                    "analysis_key": ".github/workflows/codeql-analysis.yml:CodeQL-Build",
+                    "category": ".github/workflows/codeql-analysis.yml:CodeQL-Build",
+                    "environment": "{}",
+                    "state": "dismissed",
+                    "commit_sha": "39406e42cb832f683daa691dd652a8dc36ee8930",
+                    "message": { "text": "This path depends on a user-provided value." },
+                    "location": {
+                        "path": "spec-main/api-session-spec.ts",
+                        "start_line": 917,
+                        "end_line": 917,
+                        "start_column": 7,
+                        "end_column": 18
+                    },
+                    "classifications": ["test"]
+                },
+                "instances_url": "https://api.github.com/repos/octocat/hello-world/code-scanning/alerts/4/instances"
+            }"#,
+        )
+        .expect("Failed to parse alert");
+
+        assert_eq!(alert.state, CodeScanningAlertState::Dismissed);
+        assert_eq!(
+            alert.dismissed_reason,
+            Some(CodeScanningDismissedReason::FalsePositive)
+        );
+        let user = alert.dismissed_by.expect("Missing dismissed_by");
+        assert_eq!(user.login, "octocat");
+        assert_eq!(user.id, 1);
+        assert_eq!(user.html_url, "https://github.com/octocat");
+        assert_eq!(user.r#type, "User");
+        assert_eq!(user.to_string(), "octocat");
+    }
+}
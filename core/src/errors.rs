@@ -1,16 +1,30 @@
 //! # GHASToolkit Errors
 //!
 //! This module contains all the errors that can be thrown by the library
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use octocrab::Error as OctocrabError;
 use regex::Error as RegexError;
 
+/// Boxed underlying error of a `GHASError`
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
 /// GitHub Advanced Security Toolkit Error
+///
+/// The enum is `#[non_exhaustive]`, new variants can be added without a breaking change
+/// so matches need a wildcard arm. Prefer the semantic variants (`Config`, `Parse`,
+/// `NotFound`, `Unsupported` and `Timeout`) over the module specific string errors.
 #[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
 pub enum GHASError {
     /// Repository Reference Error
     #[error("RepositoryReferenceError: {0}")]
     RepositoryReferenceError(String),
 
+    /// Invalid Repository (the repository reference could not be parsed)
+    #[error("InvalidRepository: {0}")]
+    InvalidRepository(String),
+
     /// CodeQL Error
     #[error("CodeQLError: {0}")]
     CodeQLError(String),
@@ -23,40 +37,321 @@ pub enum GHASError {
     #[error("CodeQLPackError: {0}")]
     CodeQLPackError(String),
 
+    /// Code Scanning Error
+    #[error("CodeScanningError: {0}")]
+    CodeScanningError(String),
+
+    /// Secret Scanning Error
+    #[error("SecretScanningError: {0}")]
+    SecretScanningError(String),
+
+    /// Supply Chain Error
+    #[error("SupplyChainError: {0}")]
+    SupplyChainError(String),
+
+    /// GitHub GraphQL Error (errors returned by a GraphQL query)
+    #[error("GraphQLError: {0}")]
+    GraphQLError(String),
+
+    /// HTTP Error returned by the GitHub API (non-success status code)
+    #[error("HttpError: {status} - {message}")]
+    Http {
+        /// HTTP status code
+        status: u16,
+        /// Error message returned by GitHub
+        message: String,
+        /// Underlying error (if any)
+        #[source]
+        source: Option<BoxError>,
+    },
+
+    /// Resource Not Found (HTTP 404, missing files, packs, databases, etc.)
+    #[error("NotFound: {message}")]
+    NotFound {
+        /// What was not found
+        message: String,
+        /// Underlying error (if any, e.g. the GitHub API error)
+        #[source]
+        source: Option<BoxError>,
+    },
+
+    /// Invalid or missing configuration (builder options, arguments, etc.)
+    #[error("ConfigError: {0}")]
+    Config(String),
+
+    /// Invalid content that could not be parsed (names, versions, files, etc.)
+    #[error("ParseError: {0}")]
+    Parse(String),
+
+    /// Operation or value not supported (language, build mode, format, etc.)
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+
+    /// Operation timed out
+    #[error("Timeout: {0}")]
+    Timeout(String),
+
+    /// GitHub API rate limit exceeded
+    #[error("RateLimited: retry after {retry_after:?}")]
+    RateLimited {
+        /// How long to wait before retrying, only known when the response headers
+        /// (`Retry-After` or `x-ratelimit-reset`) are available
+        retry_after: Option<Duration>,
+        /// Underlying error (if any, e.g. the GitHub API error)
+        #[source]
+        source: Option<BoxError>,
+    },
+
     /// Octocrab Error (octocrab::Error)
-    #[error("OctocrabError: {0}")]
-    OctocrabError(#[from] OctocrabError),
+    #[error("Octocrab error")]
+    OctocrabError(#[source] OctocrabError),
 
     /// GHActions Error
     #[cfg(feature = "toolcache")]
-    #[error("GHActionsError: {0}")]
+    #[error("GitHub Actions error")]
     GHActionsError(#[from] ghactions::ActionsError),
 
     /// Regex Error (regex::Error)
-    #[error("RegexError: {0}")]
+    #[error("Regex error")]
     RegexError(#[from] RegexError),
 
     /// Io Error (std::io::Error)
-    #[error("IoError: {0}")]
+    #[error("I/O error")]
     IoError(#[from] std::io::Error),
 
     /// Serde Error (serde_json::Error)
-    #[error("SerdeError: {0}")]
+    #[error("JSON error")]
     SerdeError(#[from] serde_json::Error),
 
     /// Yaml Error (serde_yaml::Error)
-    #[error("YamlError: {0}")]
+    #[error("YAML error")]
     YamlError(#[from] serde_yaml::Error),
 
+    /// Toml Error (toml::de::Error)
+    #[error("TOML error")]
+    TomlError(#[from] toml::de::Error),
+
     /// Url Error (url::ParseError)
-    #[error("UrlError: {0}")]
+    #[error("URL error")]
     UrlError(#[from] url::ParseError),
 
+    /// Zip Error (zip::result::ZipError)
+    #[error("Zip error")]
+    ZipError(#[from] zip::result::ZipError),
+
+    /// Task Error (a spawned task panicked or was cancelled)
+    #[error("Task error")]
+    TaskError(#[from] tokio::task::JoinError),
+
     /// Git Errors (git2::Error)
-    #[error("GitErrors: {0}")]
+    #[error("Git error")]
     GitErrors(#[from] git2::Error),
 
     /// Unknown Error
     #[error("UnknownError: {0}")]
     UnknownError(String),
+
+    /// Any other error (anyhow::Error)
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl GHASError {
+    /// Get the error message followed by the messages of its sources (e.g.
+    /// `I/O error: disk full`). The wrapper variants only describe the kind of error, so
+    /// use this when logging an error instead of its `Display`.
+    pub fn display_chain(&self) -> String {
+        let mut message = self.to_string();
+        let mut source = std::error::Error::source(self);
+        while let Some(error) = source {
+            message.push_str(": ");
+            message.push_str(&error.to_string());
+            source = error.source();
+        }
+        message
+    }
+
+    /// Create an error from a HTTP status code and message
+    pub(crate) fn from_status(status: u16, message: String) -> Self {
+        GHASError::from_status_source(status, message, None)
+    }
+
+    /// Create an error from a HTTP status code and message, keeping the underlying error
+    pub(crate) fn from_status_source(
+        status: u16,
+        message: String,
+        source: Option<BoxError>,
+    ) -> Self {
+        match status {
+            404 => GHASError::NotFound { message, source },
+            429 => GHASError::RateLimited {
+                retry_after: None,
+                source,
+            },
+            403 if message.to_lowercase().contains("rate limit") => GHASError::RateLimited {
+                retry_after: None,
+                source,
+            },
+            _ => GHASError::Http {
+                status,
+                message,
+                source,
+            },
+        }
+    }
+
+    /// Set how long to wait before retrying a rate limited request from the response
+    /// headers, other errors are returned as is
+    pub(crate) fn with_retry_after(self, headers: &http::HeaderMap) -> Self {
+        match self {
+            GHASError::RateLimited {
+                retry_after: None,
+                source,
+            } => GHASError::RateLimited {
+                retry_after: retry_after(headers, SystemTime::now()),
+                source,
+            },
+            error => error,
+        }
+    }
+}
+
+/// Get how long to wait before retrying from the `Retry-After` (seconds) or the
+/// `x-ratelimit-reset` (UTC epoch seconds) headers
+fn retry_after(headers: &http::HeaderMap, now: SystemTime) -> Option<Duration> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+
+    if let Some(seconds) = header("retry-after") {
+        return Some(Duration::from_secs(seconds));
+    }
+    let reset = UNIX_EPOCH + Duration::from_secs(header("x-ratelimit-reset")?);
+    Some(reset.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+impl From<OctocrabError> for GHASError {
+    /// Map GitHub API errors to the typed HTTP variants (keeping the original error
+    /// as their source), other errors are kept as `GHASError::OctocrabError`.
+    fn from(error: OctocrabError) -> Self {
+        match &error {
+            OctocrabError::GitHub { source, .. } => {
+                let status = source.status_code.as_u16();
+                let message = source.message.clone();
+                GHASError::from_status_source(status, message, Some(Box::new(error)))
+            }
+            _ => GHASError::OctocrabError(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::GHASError;
+
+    #[test]
+    fn test_source_chain() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing file");
+        let error = GHASError::from(io);
+        let source = error.source().expect("IoError should have a source");
+        assert_eq!(source.to_string(), "missing file");
+
+        let error = GHASError::from(serde_json::from_str::<u32>("nope").unwrap_err());
+        assert!(error.source().is_some());
+
+        // The cause is part of the anyhow chain
+        let error = anyhow::Error::from(GHASError::from(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "disk full",
+        )));
+        let chain: Vec<String> = error.chain().map(|e| e.to_string()).collect();
+        // The cause is only printed once
+        assert_eq!(chain, vec!["I/O error", "disk full"]);
+
+        // Logged errors include the cause
+        let error = GHASError::from(std::io::Error::new(std::io::ErrorKind::Other, "disk full"));
+        assert_eq!(error.display_chain(), "I/O error: disk full");
+        assert_eq!(
+            GHASError::from_status(500, String::from("Server Error")).display_chain(),
+            "HttpError: 500 - Server Error"
+        );
+
+        let error = GHASError::from_status(500, String::from("Server Error"));
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn test_other() {
+        let error = GHASError::from(anyhow::anyhow!("inner").context("outer"));
+        assert!(matches!(error, GHASError::Other(_)));
+        // Transparent, the display and source come from the anyhow error
+        assert_eq!(error.to_string(), "outer");
+        assert_eq!(error.source().unwrap().to_string(), "inner");
+    }
+
+    #[test]
+    fn test_retry_after() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut headers = http::HeaderMap::new();
+        assert_eq!(super::retry_after(&headers, now), None);
+
+        headers.insert("x-ratelimit-reset", "1700000060".parse().unwrap());
+        assert_eq!(
+            super::retry_after(&headers, now),
+            Some(Duration::from_secs(60))
+        );
+        // Already reset
+        let later = now + Duration::from_secs(120);
+        assert_eq!(super::retry_after(&headers, later), Some(Duration::ZERO));
+
+        // `Retry-After` takes precedence
+        headers.insert("retry-after", "30".parse().unwrap());
+        assert_eq!(
+            super::retry_after(&headers, now),
+            Some(Duration::from_secs(30))
+        );
+
+        let error = GHASError::from_status(429, String::from("Too Many Requests"))
+            .with_retry_after(&headers);
+        assert!(matches!(
+            error,
+            GHASError::RateLimited {
+                retry_after: Some(d),
+                ..
+            } if d == Duration::from_secs(30)
+        ));
+        let error =
+            GHASError::from_status(404, String::from("Not Found")).with_retry_after(&headers);
+        assert!(matches!(error, GHASError::NotFound { .. }));
+    }
+
+    #[test]
+    fn test_status_mapping() {
+        assert!(matches!(
+            GHASError::from_status(404, String::from("Not Found")),
+            GHASError::NotFound { message, source: None } if message == "Not Found"
+        ));
+        assert!(matches!(
+            GHASError::from_status(403, String::from("API rate limit exceeded")),
+            GHASError::RateLimited {
+                retry_after: None,
+                ..
+            }
+        ));
+        assert!(matches!(
+            GHASError::from_status(429, String::from("Too Many Requests")),
+            GHASError::RateLimited { .. }
+        ));
+        assert!(matches!(
+            GHASError::from_status(403, String::from("Resource not accessible")),
+            GHASError::Http { status: 403, .. }
+        ));
+    }
 }
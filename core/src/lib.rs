@@ -12,6 +12,16 @@
 //!   - [x] Code Scanning
 //!   - [x] Secret Scanning
 //!
+//! ## Feature Flags
+//!
+//! - `async` (default): Optional async helpers. The CodeQL CLI wrapper and the GitHub APIs
+//!   are always async and require a [tokio](https://tokio.rs) runtime.
+//! - `toolcache`: Find and install CodeQL using the GitHub Actions tool cache.
+//! - `gitignore`: Respect `.gitignore` files when walking source trees.
+//! - `tracing`: Emit [tracing](https://docs.rs/tracing) spans (with the repository, language
+//!   and `duration_ms`) around CodeQL commands, database create / analyze, downloads and
+//!   API calls. The `log` output is unchanged.
+//!
 //! ## Usage
 //!
 //! ```rust
@@ -46,7 +56,7 @@ pub use octokit::github::GitHub;
 pub use octokit::repository::Repository;
 
 // CodeQL
-pub use codeql::packs::{CodeQLPack, CodeQLPackType, CodeQLPacks};
+pub use codeql::packs::{CodeQLPack, CodeQLPackInstallMode, CodeQLPackType, CodeQLPacks};
 pub use codeql::CodeQL;
 pub use codeql::CodeQLDatabase;
 pub use codeql::CodeQLDatabases;
@@ -1,13 +1,26 @@
-use std::{fmt::Display, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
 use git2::Repository as GitRepository;
 use log::debug;
 use octocrab::{Octocrab, Result as OctoResult};
+use serde::{de::DeserializeOwned, Serialize};
 use url::Url;
 
+use futures::Stream;
+
 use crate::{
-    codescanning::api::CodeScanningHandler, octokit::models::GitHubLanguages,
-    secretscanning::api::SecretScanningHandler, GHASError, Repository,
+    codeql::{CodeQLLanguage, CodeQLLanguages},
+    codescanning::api::CodeScanningHandler,
+    octokit::models::{GitHubLanguages, RepositoryInfo, RepositoryLicense, SecurityFeatures},
+    octokit::repositories::ListRepositories,
+    secretscanning::api::{OrgSecretScanningHandler, SecretScanningHandler},
+    supplychain::{sbom::DependencyGraphSbom, Licenses},
+    utils::spans::SpanTimer,
+    Dependencies, GHASError, Repository,
 };
 
 /// GitHub instance
@@ -28,12 +41,17 @@ pub struct GitHub {
     instance: Url,
     /// REST API endpoint
     api_rest: Url,
+    /// GraphQL API endpoint
+    api_graphql: Url,
 
     /// If an enterprise server instance is being used
     enterprise_server: bool,
 
     /// If the token is for a GitHub App
     github_app: bool,
+
+    /// If a pre-built Octocrab instance is used for all the API requests
+    custom_client: bool,
 }
 
 impl GitHub {
@@ -72,6 +90,42 @@ impl GitHub {
         self.instance.to_string()
     }
 
+    /// Get the GitHub instance URL (e.g. `https://github.com`)
+    pub fn instance_url(&self) -> &Url {
+        &self.instance
+    }
+
+    /// Get the REST API base URL.
+    ///
+    /// For GitHub Cloud this is `https://api.github.com` and for Enterprise Server
+    /// this is `<instance>/api/v3`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ghastoolkit::GitHub;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let github = GitHub::init()
+    ///     .instance("https://github.geekmasher.dev")
+    ///     .build()
+    ///     .expect("Failed to initialise GitHub instance");
+    ///
+    /// assert_eq!(github.rest_base().as_str(), "https://github.geekmasher.dev/api/v3");
+    /// # }
+    /// ```
+    pub fn rest_base(&self) -> &Url {
+        &self.api_rest
+    }
+
+    /// Get the GraphQL API base URL.
+    ///
+    /// For GitHub Cloud this is `https://api.github.com/graphql` and for
+    /// Enterprise Server this is `<instance>/api/graphql`.
+    pub fn graphql_base(&self) -> &Url {
+        &self.api_graphql
+    }
+
     /// Get the GitHub Token
     pub fn token(&self) -> Option<&String> {
         self.token.as_ref()
@@ -79,22 +133,27 @@ impl GitHub {
 
     /// Get the URL used for clong a repository.
     fn clone_repository_url(&self, repo: &Repository) -> Result<String, GHASError> {
+        let scheme = self.instance_url().scheme();
+        let host = self.instance_url().host_str().ok_or_else(|| {
+            GHASError::UnknownError(format!("Invalid GitHub instance: {}", self.instance))
+        })?;
+
         if self.github_app {
             // GitHub Apps require a different URL
             Ok(format!(
                 "{}://x-access-token:{}@{}/{}/{}.git",
-                self.instance.scheme(),
+                scheme,
                 self.token.clone().expect("Failed to get token"),
-                self.instance.host().expect("Failed to get host"),
+                host,
                 repo.owner(),
                 repo.name()
             ))
         } else if let Some(token) = &self.token {
             Ok(format!(
                 "{}://{}@{}/{}/{}.git",
-                self.instance.scheme(),
+                scheme,
                 token,
-                self.instance.host().expect("Failed to get host"),
+                host,
                 repo.owner(),
                 repo.name()
             ))
@@ -102,8 +161,8 @@ impl GitHub {
             // No token
             Ok(format!(
                 "{}://{}/{}/{}.git",
-                self.instance.scheme(),
-                self.instance.host().expect("Failed to get host"),
+                scheme,
+                host,
                 repo.owner(),
                 repo.name()
             ))
@@ -141,20 +200,293 @@ impl GitHub {
         &self.octocrab
     }
 
+    /// Run a GraphQL query against the GitHub GraphQL API and return the `data` of
+    /// the response. This uses the configured instance (Cloud or Enterprise Server)
+    /// and token.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ghastoolkit::GitHub;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Viewer {
+    ///     viewer: Login,
+    /// }
+    /// #[derive(serde::Deserialize)]
+    /// struct Login {
+    ///     login: String,
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let github = GitHub::default();
+    ///
+    /// let viewer: Viewer = github
+    ///     .graphql("query { viewer { login } }", serde_json::json!({}))
+    ///     .await?;
+    /// println!("Logged in as :: {}", viewer.viewer.login);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "github.graphql",
+            skip_all,
+            fields(duration_ms = tracing::field::Empty)
+        )
+    )]
+    pub async fn graphql<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: impl Serialize,
+    ) -> Result<T, GHASError> {
+        let _timer = SpanTimer::start();
+        let payload = serde_json::json!({
+            "query": query,
+            "variables": variables,
+        });
+
+        let response: GraphQLResponse<T> = if self.enterprise_server && !self.custom_client {
+            // Enterprise Server's GraphQL API isn't under the REST API base (`/api/v3`)
+            self.graphql_client()?.graphql(&payload).await?
+        } else {
+            self.octocrab.graphql(&payload).await?
+        };
+
+        if let Some(errors) = response.errors {
+            if !errors.is_empty() {
+                let messages: Vec<String> = errors.into_iter().map(|e| e.message).collect();
+                return Err(GHASError::GraphQLError(messages.join(", ")));
+            }
+        }
+        response.data.ok_or_else(|| {
+            GHASError::GraphQLError(String::from("No data returned from GraphQL query"))
+        })
+    }
+
+    /// Octocrab client rooted at the GraphQL API base (without `/graphql`)
+    fn graphql_client(&self) -> Result<Octocrab, GHASError> {
+        let base = self.api_graphql.as_str().trim_end_matches("/graphql");
+        let mut builder = Octocrab::builder().base_uri(base)?;
+        if let Some(token) = &self.token {
+            builder = builder.personal_token(token.clone());
+        }
+        Ok(builder.build()?)
+    }
+
     /// Get Secret Scanning Handler based on the Repository
-    pub fn secret_scanning<'a>(&'a self, repo: &'a Repository) -> SecretScanningHandler {
+    pub fn secret_scanning<'a>(&'a self, repo: &'a Repository) -> SecretScanningHandler<'a> {
         SecretScanningHandler::new(self.octocrab(), repo)
     }
 
+    /// Get Secret Scanning Handler for an Organization
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let github = ghastoolkit::GitHub::default();
+    ///
+    /// let alerts = github
+    ///     .org_secret_scanning("geekmasher")
+    ///     .list()
+    ///     .state("open")
+    ///     .all()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn org_secret_scanning(&self, org: impl Into<String>) -> OrgSecretScanningHandler<'_> {
+        OrgSecretScanningHandler::new(self.octocrab(), org)
+    }
+
     /// Get Code Scanning Handler based on the Repository provided.
-    pub fn code_scanning<'a>(&'a self, repo: &'a Repository) -> CodeScanningHandler {
+    pub fn code_scanning<'a>(&'a self, repo: &'a Repository) -> CodeScanningHandler<'a> {
         CodeScanningHandler::new(self.octocrab(), repo)
     }
 
     /// Get Repository languages from GitHub
     pub async fn list_languages(&self, repo: &Repository) -> OctoResult<GitHubLanguages> {
-        let route = format!("/repos/{}/{}/languages", repo.owner(), repo.name());
-        self.octocrab.get(route, None::<&()>).await
+        list_languages(&self.octocrab, repo).await
+    }
+
+    /// Get the languages of a repository which CodeQL supports, sorted by the number of
+    /// bytes (most first). The GitHub (linguist) languages are mapped using
+    /// [`CODEQL_LANGUAGES`](crate::codeql::languages::CODEQL_LANGUAGES).
+    ///
+    /// Use [`CodeQLLanguages::from_github_languages`] with the CodeQL extractors to
+    /// include custom extractors.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ghastoolkit::{GitHub, Repository};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let github = GitHub::default();
+    /// let repository = Repository::parse("geekmasher/ghastoolkit-rs")?;
+    ///
+    /// for language in github.codeql_languages(&repository).await? {
+    ///     println!("{}", language);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn codeql_languages(
+        &self,
+        repo: &Repository,
+    ) -> Result<Vec<CodeQLLanguage>, GHASError> {
+        let languages = self.list_languages(repo).await?;
+        Ok(CodeQLLanguages::default().from_github_languages(&languages))
+    }
+
+    /// Get the repository information from GitHub (default branch, visibility,
+    /// archived, and security and analysis settings)
+    pub async fn get_repository(
+        &self,
+        owner: &str,
+        name: &str,
+    ) -> Result<RepositoryInfo, GHASError> {
+        let route = format!("/repos/{}/{}", owner, name);
+        Ok(self.octocrab.get(route, None::<&()>).await?)
+    }
+
+    /// List the repositories of an organization or user.
+    ///
+    /// The repositories are streamed, following the pagination as needed. Use
+    /// [`GitHub::repositories`] to filter by type or visibility.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use futures::TryStreamExt;
+    /// use ghastoolkit::GitHub;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let github = GitHub::default();
+    ///
+    /// let mut repositories = github.list_repositories("geekmasher");
+    /// while let Some(repository) = repositories.try_next().await? {
+    ///     println!("{}", repository.full_name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_repositories(
+        &self,
+        owner: &str,
+    ) -> impl Stream<Item = Result<RepositoryInfo, GHASError>> + '_ {
+        self.repositories(owner).stream()
+    }
+
+    /// List the repositories of an organization or user (with filters)
+    pub fn repositories(&self, owner: &str) -> ListRepositories<'_> {
+        ListRepositories::new(self.octocrab(), owner)
+    }
+
+    /// Get the enablement of the security features of a repository (from the
+    /// repository's `security_and_analysis` settings).
+    ///
+    /// This is more reliable than `CodeScanningHandler::is_enabled` which checks
+    /// for analyses. Features GitHub doesn't return a status for (the token needs
+    /// admin access) are `SecurityFeatureStatus::NotAvailable`.
+    pub async fn security_features(
+        &self,
+        repo: &Repository,
+    ) -> Result<SecurityFeatures, GHASError> {
+        Ok(self
+            .get_repository(repo.owner(), repo.name())
+            .await?
+            .security_features())
+    }
+
+    /// Get the license GitHub detected for a repository
+    pub async fn repository_license(
+        &self,
+        repo: &Repository,
+    ) -> Result<RepositoryLicense, GHASError> {
+        let route = format!("/repos/{}/{}/license", repo.owner(), repo.name());
+        Ok(self.octocrab.get(route, None::<&()>).await?)
+    }
+
+    /// Export the SBOM (SPDX) of a repository from the GitHub Dependency Graph
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "github.dependency_graph.sbom",
+            skip_all,
+            fields(repo = %repo, duration_ms = tracing::field::Empty)
+        )
+    )]
+    pub async fn dependency_graph_sbom(
+        &self,
+        repo: &Repository,
+    ) -> Result<DependencyGraphSbom, GHASError> {
+        let _timer = SpanTimer::start();
+        let route = format!(
+            "/repos/{}/{}/dependency-graph/sbom",
+            repo.owner(),
+            repo.name()
+        );
+        Ok(self.octocrab.get(route, None::<&()>).await?)
+    }
+
+    /// Fill in the licenses of dependencies hosted on GitHub using the license
+    /// GitHub detected for the repository (see `Dependency::github_repository`).
+    ///
+    /// Dependencies which already have licenses, are not hosted on GitHub, or have
+    /// no detected license are skipped. Each repository is only requested once.
+    /// If the API rate limit is hit the enrichment stops and a
+    /// `GHASError::RateLimited` error is returned (dependencies enriched before
+    /// that point keep their licenses).
+    ///
+    /// Returns the number of dependencies that were updated.
+    pub async fn enrich_licenses(
+        &self,
+        dependencies: &mut Dependencies,
+    ) -> Result<usize, GHASError> {
+        let mut cache: HashMap<String, Option<Licenses>> = HashMap::new();
+        let mut updated = 0;
+
+        for dependency in dependencies.iter_mut() {
+            if !dependency.licenses.is_empty() {
+                continue;
+            }
+            let Some(repository) = dependency.github_repository() else {
+                continue;
+            };
+
+            let key = repository.to_string();
+            if !cache.contains_key(&key) {
+                let licenses = match self.repository_license(&repository).await {
+                    Ok(license) => license
+                        .license
+                        .and_then(|l| l.spdx_id)
+                        .filter(|id| id != "NOASSERTION")
+                        .map(Licenses::from),
+                    Err(GHASError::NotFound { .. }) => None,
+                    Err(e @ GHASError::RateLimited { .. }) => return Err(e),
+                    Err(e) => {
+                        debug!("Failed to get license for {} :: {}", key, e.display_chain());
+                        None
+                    }
+                };
+                cache.insert(key.clone(), licenses);
+            }
+
+            if let Some(Some(licenses)) = cache.get(&key) {
+                dependency.licenses = licenses.clone();
+                updated += 1;
+            }
+        }
+
+        Ok(updated)
     }
 
     /// Clone a GitHub Repository to a local path
@@ -162,15 +494,116 @@ impl GitHub {
         &self,
         repo: &mut Repository,
         path: &String,
+    ) -> Result<GitRepository, GHASError> {
+        self.clone_repository_with_options(repo, path, &CloneOptions::default())
+    }
+
+    /// Clone a GitHub Repository to a local path with options (e.g. submodules).
+    ///
+    /// Git LFS files are never downloaded (libgit2 doesn't support LFS), the LFS
+    /// pointer files are checked out instead.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ghastoolkit::{octokit::github::CloneOptions, GitHub, Repository};
+    ///
+    /// let github = GitHub::default();
+    /// let mut repository = Repository::new("geekmasher", "ghastoolkit-rs");
+    ///
+    /// let options = CloneOptions::default().submodules(true);
+    /// github
+    ///     .clone_repository_with_options(&mut repository, &"/tmp/ghastoolkit-rs".to_string(), &options)
+    ///     .expect("Failed to clone repository");
+    /// ```
+    pub fn clone_repository_with_options(
+        &self,
+        repo: &mut Repository,
+        path: &String,
+        options: &CloneOptions,
     ) -> Result<GitRepository, GHASError> {
         let url = self.clone_repository_url(repo)?;
-        match GitRepository::clone(url.as_str(), path.as_str()) {
-            Ok(gitrepo) => {
-                repo.set_root(PathBuf::from(path));
-                Ok(gitrepo)
-            }
-            Err(e) => Err(GHASError::from(e)),
+        let gitrepo = self.clone_url(&url, Path::new(path), options)?;
+
+        repo.set_root(PathBuf::from(path));
+        Ok(gitrepo)
+    }
+
+    /// Clone a Git URL to a local path with options
+    fn clone_url(
+        &self,
+        url: &str,
+        path: &Path,
+        options: &CloneOptions,
+    ) -> Result<GitRepository, GHASError> {
+        let gitrepo = GitRepository::clone(url, path)?;
+
+        if options.submodules {
+            self.update_submodules(&gitrepo)?;
+        }
+        Ok(gitrepo)
+    }
+
+    /// Initialize and update all the submodules of a repository (recursively).
+    ///
+    /// Submodules on the same GitHub instance are fetched with the token, so private
+    /// submodules can be cloned.
+    fn update_submodules(&self, gitrepo: &GitRepository) -> Result<(), GHASError> {
+        for mut submodule in gitrepo.submodules()? {
+            debug!("Updating submodule :: {}", submodule.path().display());
+
+            let mut attempts = 0;
+            let mut callbacks = git2::RemoteCallbacks::new();
+            callbacks.credentials(|url, _, _| {
+                // libgit2 keeps asking for credentials if they are rejected
+                attempts += 1;
+                match self.submodule_token(url) {
+                    Some(token) if attempts == 1 => {
+                        git2::Cred::userpass_plaintext("x-access-token", token)
+                    }
+                    _ => Err(git2::Error::from_str("No credentials for submodule")),
+                }
+            });
+            let mut fetch = git2::FetchOptions::new();
+            fetch.remote_callbacks(callbacks);
+
+            let mut update = git2::SubmoduleUpdateOptions::new();
+            update.fetch(fetch);
+            submodule.update(true, Some(&mut update))?;
+
+            let subrepo = submodule.open()?;
+            self.update_submodules(&subrepo)?;
         }
+        Ok(())
+    }
+
+    /// Get the token for a submodule URL, the token is only sent to the GitHub instance
+    fn submodule_token(&self, url: &str) -> Option<&str> {
+        let url = Url::parse(url).ok()?;
+        if url.scheme() == self.instance.scheme() && url.host_str() == self.instance.host_str() {
+            self.token.as_deref()
+        } else {
+            None
+        }
+    }
+}
+
+/// Options for cloning a repository (see [`GitHub::clone_repository_with_options`])
+///
+/// There is no option to skip the Git LFS smudge filter as it is always skipped:
+/// libgit2 doesn't run external filter drivers (like `git lfs smudge`), so LFS files are
+/// checked out as their pointer files (the same as `GIT_LFS_SKIP_SMUDGE=1`).
+#[derive(Debug, Clone, Default)]
+pub struct CloneOptions {
+    /// Recursively clone the submodules
+    submodules: bool,
+}
+
+impl CloneOptions {
+    /// Recursively initialize and clone the submodules of the repository
+    pub fn submodules(mut self, submodules: bool) -> Self {
+        self.submodules = submodules;
+        self
     }
 }
 
@@ -195,11 +628,8 @@ impl Default for GitHub {
                 Url::parse("https://github.com").expect("Failed to parse GitHub instance URL")
             }
         };
-        // TODO(geekmasher): REST API
-        let token = match std::env::var("GITHUB_TOKEN") {
-            Ok(val) => Some(val),
-            Err(_) => None,
-        };
+        let (api_rest, api_graphql, enterprise_server) = api_urls(&instance);
+        let token = std::env::var("GITHUB_TOKEN").ok();
 
         Self {
             octocrab: Octocrab::default(),
@@ -207,14 +637,57 @@ impl Default for GitHub {
             enterprise: None,
             token,
             instance,
-            api_rest: Url::parse("https://api.github.com")
-                .expect("Failed to parse GitHub REST API URL"),
-            enterprise_server: false,
+            api_rest,
+            api_graphql,
+            enterprise_server,
             github_app: false,
+            custom_client: false,
         }
     }
 }
 
+/// Compute the REST and GraphQL API URLs for a GitHub instance, and if the
+/// instance is an Enterprise Server.
+fn api_urls(instance: &Url) -> (Url, Url, bool) {
+    if instance.host_str() == Some("github.com") {
+        (
+            Url::parse("https://api.github.com").expect("Failed to parse REST API URL"),
+            Url::parse("https://api.github.com/graphql").expect("Failed to parse GraphQL URL"),
+            false,
+        )
+    } else {
+        let base = instance.as_str().trim_end_matches('/');
+        (
+            Url::parse(format!("{}/api/v3", base).as_str()).expect("Failed to parse REST API URL"),
+            Url::parse(format!("{}/api/graphql", base).as_str())
+                .expect("Failed to parse GraphQL URL"),
+            true,
+        )
+    }
+}
+
+/// Get the repository (linguist) languages and their number of bytes from GitHub
+pub(crate) async fn list_languages(
+    octocrab: &Octocrab,
+    repo: &Repository,
+) -> OctoResult<GitHubLanguages> {
+    let route = format!("/repos/{}/{}/languages", repo.owner(), repo.name());
+    octocrab.get(route, None::<&()>).await
+}
+
+/// GraphQL response body
+#[derive(Debug, serde::Deserialize)]
+struct GraphQLResponse<T> {
+    data: Option<T>,
+    errors: Option<Vec<GraphQLResponseError>>,
+}
+
+/// GraphQL error returned in the response body
+#[derive(Debug, serde::Deserialize)]
+struct GraphQLResponseError {
+    message: String,
+}
+
 /// GitHub Builder
 #[derive(Debug, Clone)]
 pub struct GitHubBuilder {
@@ -223,8 +696,10 @@ pub struct GitHubBuilder {
     token: Option<String>,
     instance: Url,
     rest_api: Url,
+    graphql_api: Url,
     enterprise_server: bool,
     github_app: bool,
+    octocrab: Option<Octocrab>,
 }
 
 impl GitHubBuilder {
@@ -247,17 +722,11 @@ impl GitHubBuilder {
     pub fn instance(&mut self, instance: &str) -> &mut Self {
         self.instance = Url::parse(instance).expect("Failed to parse instance URL");
 
-        // GitHub Cloud
-        if self.instance.host_str() == Some("github.com") {
-            self.rest_api =
-                Url::parse("https://api.github.com").expect("Failed to parse REST API URL");
-            self.enterprise_server = false;
-        } else {
-            // GitHub Enterprise Server endpoint
-            self.rest_api = Url::parse(format!("{}/api/v3", instance).as_str())
-                .expect("Failed to parse REST API URL");
-            self.enterprise_server = true;
-        }
+        // GitHub Cloud or Enterprise Server endpoints
+        let (rest_api, graphql_api, enterprise_server) = api_urls(&self.instance);
+        self.rest_api = rest_api;
+        self.graphql_api = graphql_api;
+        self.enterprise_server = enterprise_server;
 
         self
     }
@@ -303,6 +772,46 @@ impl GitHubBuilder {
         self
     }
 
+    /// Override the REST API base URL (the GraphQL API is `<base_uri>/graphql`).
+    ///
+    /// This is mainly used to point the client at a mock server for testing.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ghastoolkit::GitHub;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let github = GitHub::init()
+    ///     .base_uri("http://127.0.0.1:8080")
+    ///     .expect("Invalid base URI")
+    ///     .build()
+    ///     .expect("Failed to initialise GitHub instance");
+    ///
+    /// assert_eq!(github.rest_base().as_str(), "http://127.0.0.1:8080/");
+    /// # }
+    /// ```
+    pub fn base_uri(&mut self, base_uri: &str) -> Result<&mut Self, GHASError> {
+        let rest_api = Url::parse(base_uri)
+            .map_err(|e| GHASError::Config(format!("Invalid base URI {}: {}", base_uri, e)))?;
+        self.graphql_api =
+            Url::parse(format!("{}/graphql", rest_api.as_str().trim_end_matches('/')).as_str())
+                .map_err(|e| GHASError::Config(format!("Invalid base URI {}: {}", base_uri, e)))?;
+        self.rest_api = rest_api;
+        Ok(self)
+    }
+
+    /// Use a pre-built Octocrab instance for all the API requests (REST and GraphQL,
+    /// GraphQL requests are sent to `/graphql` of the instance's base URI).
+    ///
+    /// The token and base URI settings are not applied to the provided instance and
+    /// the URLs can't be read back from it, so `rest_base()` / `graphql_base()` only
+    /// report the instance's endpoints if the same base URI is set with `base_uri()`.
+    pub fn octocrab(&mut self, octocrab: Octocrab) -> &mut Self {
+        self.octocrab = Some(octocrab);
+        self
+    }
+
     /// Build the GitHub instance with the provided settings.
     ///
     /// # Example
@@ -325,27 +834,33 @@ impl GitHubBuilder {
             None => std::env::var("GITHUB_TOKEN").ok(),
         };
 
-        let mut builder = octocrab::Octocrab::builder();
+        let octocrab = match &self.octocrab {
+            Some(octocrab) => octocrab.clone(),
+            None => {
+                let mut builder = octocrab::Octocrab::builder();
 
-        if let Some(token) = &self.token {
-            debug!("Setting personal token");
-            builder = builder.personal_token(token.clone());
-        }
+                if let Some(token) = &self.token {
+                    debug!("Setting personal token");
+                    builder = builder.personal_token(token.clone());
+                }
 
-        debug!("Setting base URI to: {}", self.rest_api);
-        builder = builder
-            .base_uri(self.rest_api.to_string().as_str())
-            .expect("Failed to set base URI");
+                debug!("Setting base URI to: {}", self.rest_api);
+                builder = builder.base_uri(self.rest_api.to_string().as_str())?;
+                builder.build()?
+            }
+        };
 
         Ok(GitHub {
-            octocrab: builder.build().expect("Failed to build Octocrab instance"),
+            octocrab,
             owner: self.owner.clone(),
             enterprise: self.enterprise.clone(),
             token,
             instance: self.instance.clone(),
             api_rest: self.rest_api.clone(),
+            api_graphql: self.graphql_api.clone(),
             enterprise_server: self.enterprise_server,
             github_app: self.github_app,
+            custom_client: self.octocrab.is_some(),
         })
     }
 }
@@ -360,14 +875,227 @@ impl Default for GitHubBuilder {
                 .expect("Failed to parse GitHub instance URL"),
             rest_api: Url::parse("https://api.github.com")
                 .expect("Failed to parse GitHub REST API URL"),
+            graphql_api: Url::parse("https://api.github.com/graphql")
+                .expect("Failed to parse GitHub GraphQL API URL"),
             enterprise_server: false,
             github_app: false,
+            octocrab: None,
         }
     }
 }
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::octokit::test_utils::mock_server;
+
+    #[tokio::test]
+    async fn test_base_uri() {
+        let (server, _) = mock_server(|_| {
+            vec![(
+                "200 OK",
+                String::from(
+                    r#"{"name": "ghastoolkit-rs", "full_name": "geekmasher/ghastoolkit-rs", "default_branch": "main", "visibility": "public", "archived": false}"#,
+                ),
+            )]
+        });
+        let github = GitHub::init()
+            .base_uri(&server)
+            .unwrap()
+            .build()
+            .expect("Failed to build GitHub instance");
+        assert_eq!(
+            github.graphql_base().as_str(),
+            format!("{}/graphql", server)
+        );
+
+        let repository = github
+            .get_repository("geekmasher", "ghastoolkit-rs")
+            .await
+            .expect("Failed to get repository");
+        assert_eq!(repository.full_name, "geekmasher/ghastoolkit-rs");
+
+        let (server, _) =
+            mock_server(|_| vec![("404 Not Found", String::from(r#"{"message": "Not Found"}"#))]);
+        let octocrab = Octocrab::builder()
+            .base_uri(server)
+            .unwrap()
+            .build()
+            .unwrap();
+        let github = GitHub::init().octocrab(octocrab).build().unwrap();
+        let error = github
+            .get_repository("geekmasher", "missing")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, GHASError::NotFound { .. }));
+        // The GitHub API error is kept as the source
+        let source = std::error::Error::source(&error).expect("NotFound should have a source");
+        assert!(source.downcast_ref::<octocrab::Error>().is_some());
+
+        assert!(matches!(
+            GitHub::init().base_uri("not a url"),
+            Err(GHASError::Config(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_graphql_custom_client() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Viewer {
+            viewer: Login,
+        }
+        #[derive(Debug, serde::Deserialize)]
+        struct Login {
+            login: String,
+        }
+
+        // GraphQL requests use the injected client, even for Enterprise Server
+        let (server, requests) = mock_server(|_| {
+            vec![(
+                "200 OK",
+                String::from(r#"{"data": {"viewer": {"login": "geekmasher"}}}"#),
+            )]
+        });
+        let octocrab = Octocrab::builder()
+            .base_uri(server.as_str())
+            .unwrap()
+            .build()
+            .unwrap();
+        let github = GitHub::init()
+            .instance("https://github.geekmasher.dev")
+            .octocrab(octocrab)
+            .base_uri(&server)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(github.rest_base().as_str(), format!("{}/", server));
+        assert_eq!(
+            github.graphql_base().as_str(),
+            format!("{}/graphql", server)
+        );
+
+        let viewer: Viewer = github
+            .graphql("query { viewer { login } }", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(viewer.viewer.login, "geekmasher");
+        assert!(requests.lock().unwrap()[0].starts_with("POST /graphql"));
+    }
+
+    /// Commit all the files in a repository
+    fn commit_all(repo: &GitRepository) {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("ghastoolkit", "ghastoolkit@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "commit",
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_clone_submodules() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+
+        let sub = GitRepository::init(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub").join("lib.rs"), "// lib").unwrap();
+        commit_all(&sub);
+
+        let main = GitRepository::init(root.join("main")).unwrap();
+        std::fs::write(root.join("main").join("README.md"), "# main").unwrap();
+        let sub_url = Url::from_file_path(root.join("sub")).unwrap();
+        let mut submodule = main
+            .submodule(sub_url.as_str(), Path::new("sub"), true)
+            .unwrap();
+        submodule.clone(None).unwrap();
+        submodule.add_finalize().unwrap();
+        commit_all(&main);
+
+        let github = GitHub::default();
+        let main_url = Url::from_file_path(root.join("main")).unwrap();
+
+        github
+            .clone_url(
+                main_url.as_str(),
+                &root.join("clone"),
+                &CloneOptions::default(),
+            )
+            .unwrap();
+        assert!(!root.join("clone").join("sub").join("lib.rs").exists());
+
+        let options = CloneOptions::default().submodules(true);
+        github
+            .clone_url(main_url.as_str(), &root.join("clone-submodules"), &options)
+            .unwrap();
+        assert!(root
+            .join("clone-submodules")
+            .join("sub")
+            .join("lib.rs")
+            .exists());
+    }
+
+    #[tokio::test]
+    async fn test_clone_lfs_pointer() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+
+        let pointer = "version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 12345\n";
+        let source = GitRepository::init(root.join("source")).unwrap();
+        std::fs::write(
+            root.join("source").join(".gitattributes"),
+            "*.bin filter=lfs diff=lfs merge=lfs -text\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("source").join("model.bin"), pointer).unwrap();
+        commit_all(&source);
+
+        // LFS files are never smudged, the pointer file is checked out
+        let url = Url::from_file_path(root.join("source")).unwrap();
+        GitHub::default()
+            .clone_url(url.as_str(), &root.join("clone"), &CloneOptions::default())
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join("clone").join("model.bin")).unwrap(),
+            pointer
+        );
+    }
+
+    #[tokio::test]
+    async fn test_submodule_token() {
+        let github = GitHub::init()
+            .instance("https://github.geekmasher.dev")
+            .token("token")
+            .build()
+            .unwrap();
+        assert_eq!(
+            github.submodule_token("https://github.geekmasher.dev/geekmasher/private.git"),
+            Some("token")
+        );
+        // The token is never sent to other hosts
+        assert_eq!(
+            github.submodule_token("https://github.com/geekmasher/ghastoolkit-rs.git"),
+            None
+        );
+        assert_eq!(
+            github.submodule_token("http://github.geekmasher.dev/geekmasher/private.git"),
+            None
+        );
+        assert_eq!(
+            github.submodule_token("git@github.com:geekmasher/ghastoolkit-rs.git"),
+            None
+        );
+    }
 
     #[tokio::test]
     async fn test_github_builder() {
@@ -383,6 +1111,59 @@ mod test {
         assert_eq!(gh.owner, Some("geekmasher".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_github_base_urls() {
+        let gh = GitHub::init()
+            .instance("https://github.com")
+            .build()
+            .expect("Failed to build GitHub instance");
+        assert_eq!(gh.instance_url().as_str(), "https://github.com/");
+        assert_eq!(gh.rest_base().as_str(), "https://api.github.com/");
+        assert_eq!(gh.graphql_base().as_str(), "https://api.github.com/graphql");
+
+        let ghes = GitHub::init()
+            .instance("https://github.geekmasher.dev/")
+            .build()
+            .expect("Failed to build GitHub instance");
+        assert!(ghes.is_enterprise_server());
+        assert_eq!(
+            ghes.instance_url().as_str(),
+            "https://github.geekmasher.dev/"
+        );
+        assert_eq!(
+            ghes.rest_base().as_str(),
+            "https://github.geekmasher.dev/api/v3"
+        );
+        assert_eq!(
+            ghes.graphql_base().as_str(),
+            "https://github.geekmasher.dev/api/graphql"
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a GITHUB_TOKEN"]
+    async fn test_graphql_viewer() {
+        #[derive(serde::Deserialize)]
+        struct Viewer {
+            viewer: Login,
+        }
+        #[derive(serde::Deserialize)]
+        struct Login {
+            login: String,
+        }
+
+        let gh = GitHub::init()
+            .token(&std::env::var("GITHUB_TOKEN").expect("GITHUB_TOKEN not set"))
+            .build()
+            .expect("Failed to build GitHub instance");
+
+        let viewer: Viewer = gh
+            .graphql("query { viewer { login } }", serde_json::json!({}))
+            .await
+            .expect("Failed to run GraphQL query");
+        assert!(!viewer.viewer.login.is_empty());
+    }
+
     #[tokio::test]
     async fn test_repo_clone_url() {
         let gh = GitHub::init()
@@ -4,5 +4,10 @@
 pub mod github;
 /// GitHub Models
 pub mod models;
+/// GitHub Repositories (listing)
+pub mod repositories;
 /// GitHub Repository
 pub mod repository;
+
+#[cfg(test)]
+pub(crate) mod test_utils;
@@ -26,3 +26,220 @@ pub struct Location {
 
 /// GitHub Languages
 pub type GitHubLanguages = HashMap<String, u32>;
+
+/// GitHub Repository License (`/repos/{owner}/{repo}/license`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RepositoryLicense {
+    /// Path to the license file
+    pub path: Option<String>,
+    /// Detected license
+    pub license: Option<RepositoryLicenseInfo>,
+}
+
+/// GitHub detected license
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RepositoryLicenseInfo {
+    /// License key (e.g. `mit`)
+    pub key: String,
+    /// License name
+    pub name: String,
+    /// SPDX identifier (`NOASSERTION` if GitHub could not determine the license)
+    pub spdx_id: Option<String>,
+}
+
+/// GitHub Repository information (`/repos/{owner}/{repo}`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RepositoryInfo {
+    /// Repository name
+    pub name: String,
+    /// Full name of the repository (`owner/name`)
+    pub full_name: String,
+    /// Default branch (e.g. `main`)
+    pub default_branch: Option<String>,
+    /// Visibility (`public`, `private` or `internal`)
+    pub visibility: Option<String>,
+    /// If the repository is archived
+    #[serde(default)]
+    pub archived: bool,
+    /// Security and analysis settings (only returned to users with admin access
+    /// or when GitHub Advanced Security is available)
+    pub security_and_analysis: Option<SecurityAndAnalysis>,
+}
+
+impl RepositoryInfo {
+    /// Check if GitHub Advanced Security is enabled (public repositories always have it)
+    pub fn advanced_security_enabled(&self) -> bool {
+        self.security_features().advanced_security.is_enabled()
+    }
+
+    /// Check if Secret Scanning is enabled
+    pub fn secret_scanning_enabled(&self) -> bool {
+        self.security_features().secret_scanning.is_enabled()
+    }
+
+    /// Get the enablement of the security features of the repository
+    pub fn security_features(&self) -> SecurityFeatures {
+        let mut features = self
+            .security_and_analysis
+            .as_ref()
+            .map(SecurityFeatures::from)
+            .unwrap_or_default();
+        // GitHub Advanced Security is always available for public repositories
+        if features.advanced_security == SecurityFeatureStatus::NotAvailable
+            && self.visibility.as_deref() == Some("public")
+        {
+            features.advanced_security = SecurityFeatureStatus::Enabled;
+        }
+        features
+    }
+}
+
+/// Repository security and analysis settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SecurityAndAnalysis {
+    /// GitHub Advanced Security
+    #[serde(default)]
+    pub advanced_security: SecurityAndAnalysisStatus,
+    /// Secret Scanning
+    #[serde(default)]
+    pub secret_scanning: SecurityAndAnalysisStatus,
+    /// Secret Scanning Push Protection
+    #[serde(default)]
+    pub secret_scanning_push_protection: SecurityAndAnalysisStatus,
+    /// Dependabot Security Updates
+    #[serde(default)]
+    pub dependabot_security_updates: SecurityAndAnalysisStatus,
+}
+
+/// Status of a security and analysis feature
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SecurityAndAnalysisStatus {
+    /// Status (`enabled` or `disabled`)
+    pub status: Option<String>,
+}
+
+impl SecurityAndAnalysisStatus {
+    /// Check if the feature is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.status().is_enabled()
+    }
+
+    /// Get the typed status of the feature
+    pub fn status(&self) -> SecurityFeatureStatus {
+        match self.status.as_deref() {
+            Some("enabled") => SecurityFeatureStatus::Enabled,
+            Some("disabled") => SecurityFeatureStatus::Disabled,
+            _ => SecurityFeatureStatus::NotAvailable,
+        }
+    }
+}
+
+/// Enablement of the security features of a repository
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SecurityFeatures {
+    /// GitHub Advanced Security (Code Scanning)
+    pub advanced_security: SecurityFeatureStatus,
+    /// Secret Scanning
+    pub secret_scanning: SecurityFeatureStatus,
+    /// Secret Scanning Push Protection
+    pub secret_scanning_push_protection: SecurityFeatureStatus,
+    /// Dependabot Security Updates
+    pub dependabot_security_updates: SecurityFeatureStatus,
+}
+
+impl From<&SecurityAndAnalysis> for SecurityFeatures {
+    fn from(value: &SecurityAndAnalysis) -> Self {
+        Self {
+            advanced_security: value.advanced_security.status(),
+            secret_scanning: value.secret_scanning.status(),
+            secret_scanning_push_protection: value.secret_scanning_push_protection.status(),
+            dependabot_security_updates: value.dependabot_security_updates.status(),
+        }
+    }
+}
+
+/// Status of a security feature
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityFeatureStatus {
+    /// The feature is enabled
+    Enabled,
+    /// The feature is disabled
+    Disabled,
+    /// The status is not returned by GitHub (not available for the repository
+    /// or the token can't read the settings)
+    #[default]
+    NotAvailable,
+}
+
+impl SecurityFeatureStatus {
+    /// Check if the feature is enabled
+    pub fn is_enabled(&self) -> bool {
+        matches!(self, SecurityFeatureStatus::Enabled)
+    }
+}
+
+impl std::fmt::Display for SecurityFeatureStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecurityFeatureStatus::Enabled => write!(f, "enabled"),
+            SecurityFeatureStatus::Disabled => write!(f, "disabled"),
+            SecurityFeatureStatus::NotAvailable => write!(f, "not available"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RepositoryInfo, SecurityFeatureStatus};
+
+    #[test]
+    fn test_repository_info() {
+        let info: RepositoryInfo = serde_json::from_str(
+            r#"{
+  "id": 1,
+  "name": "ghastoolkit-rs",
+  "full_name": "geekmasher/ghastoolkit-rs",
+  "default_branch": "main",
+  "visibility": "private",
+  "archived": false,
+  "security_and_analysis": {
+    "advanced_security": { "status": "enabled" },
+    "secret_scanning": { "status": "disabled" },
+    "secret_scanning_push_protection": { "status": "disabled" }
+  }
+}"#,
+        )
+        .unwrap();
+
+        assert_eq!(info.default_branch.as_deref(), Some("main"));
+        assert!(!info.archived);
+        assert!(info.advanced_security_enabled());
+        assert!(!info.secret_scanning_enabled());
+        assert!(!info
+            .security_and_analysis
+            .as_ref()
+            .unwrap()
+            .dependabot_security_updates
+            .is_enabled());
+
+        let features = info.security_features();
+        assert_eq!(features.advanced_security, SecurityFeatureStatus::Enabled);
+        assert_eq!(features.secret_scanning, SecurityFeatureStatus::Disabled);
+        assert_eq!(
+            features.dependabot_security_updates,
+            SecurityFeatureStatus::NotAvailable
+        );
+    }
+
+    #[test]
+    fn test_security_features_public() {
+        let info: RepositoryInfo = serde_json::from_str(
+            r#"{"name": "repo", "full_name": "owner/repo", "visibility": "public"}"#,
+        )
+        .unwrap();
+        let features = info.security_features();
+        assert!(features.advanced_security.is_enabled());
+        assert!(!features.secret_scanning.is_enabled());
+    }
+}
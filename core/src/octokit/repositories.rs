@@ -0,0 +1,144 @@
+//! # List Repositories
+//!
+//! List the repositories of an organization or user.
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use octocrab::{Octocrab, Page};
+
+use crate::{octokit::models::RepositoryInfo, GHASError};
+
+/// GitHub's maximum number of items per page
+const MAX_PER_PAGE: u8 = 100;
+
+/// List the repositories of an organization (`/orgs/{org}/repos`) or, if the
+/// owner is not an organization, a user (`/users/{user}/repos`)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ListRepositories<'octo> {
+    #[serde(skip)]
+    crab: &'octo Octocrab,
+    #[serde(skip)]
+    owner: String,
+    #[serde(skip)]
+    visibility: Option<String>,
+
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    repo_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    per_page: Option<u8>,
+}
+
+/// Pagination state of the repositories stream
+enum PageState<'octo> {
+    First(ListRepositories<'octo>),
+    Next(&'octo Octocrab, http::Uri),
+}
+
+impl<'octo> PageState<'octo> {
+    /// Request the page, returning its repositories and the state of the next page
+    async fn next(self) -> PageResult<'octo> {
+        let (crab, page) = match self {
+            PageState::First(list) => (list.crab, list.first_page().await?),
+            PageState::Next(crab, uri) => match crab.get_page(&Some(uri)).await? {
+                Some(page) => (crab, page),
+                None => return Ok(None),
+            },
+        };
+        let next = page.next.clone().map(|uri| PageState::Next(crab, uri));
+        Ok(Some((page.items, next)))
+    }
+}
+
+type PageResult<'octo> = Result<Option<(Vec<RepositoryInfo>, Option<PageState<'octo>>)>, GHASError>;
+
+impl<'octo> ListRepositories<'octo> {
+    pub(crate) fn new(crab: &'octo Octocrab, owner: impl Into<String>) -> Self {
+        Self {
+            crab,
+            owner: owner.into(),
+            visibility: None,
+            repo_type: None,
+            per_page: Some(MAX_PER_PAGE),
+        }
+    }
+
+    /// Set the type of repositories to list (e.g. `all`, `public`, `private`,
+    /// `forks`, `sources`, `member`)
+    pub fn r#type(mut self, repo_type: impl Into<String>) -> Self {
+        self.repo_type = Some(repo_type.into());
+        self
+    }
+
+    /// Only return repositories with a visibility (`public`, `private` or `internal`)
+    pub fn visibility(mut self, visibility: impl Into<String>) -> Self {
+        self.visibility = Some(visibility.into());
+        self
+    }
+
+    /// Set the number of items per page (clamped to 100)
+    pub fn per_page(mut self, per_page: impl Into<u8>) -> Self {
+        self.per_page = Some(per_page.into().min(MAX_PER_PAGE));
+        self
+    }
+
+    /// Request the first page, falling back to the user's repositories if the
+    /// owner is not an organization
+    async fn first_page(&self) -> Result<Page<RepositoryInfo>, GHASError> {
+        let route = format!("/orgs/{}/repos", self.owner);
+        match self.crab.get(route, Some(self)).await {
+            Ok(page) => Ok(page),
+            Err(error) => match GHASError::from(error) {
+                GHASError::NotFound { .. } => {
+                    let route = format!("/users/{}/repos", self.owner);
+                    Ok(self.crab.get(route, Some(self)).await?)
+                }
+                error => Err(error),
+            },
+        }
+    }
+
+    /// Stream the repositories, following the pagination as needed
+    pub fn stream(self) -> impl Stream<Item = Result<RepositoryInfo, GHASError>> + 'octo {
+        let visibility = self.visibility.clone();
+
+        stream::try_unfold(Some(PageState::First(self)), |state| async move {
+            match state {
+                Some(state) => state.next().await,
+                None => Ok(None),
+            }
+        })
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+        .try_filter(move |repo| {
+            let keep = match &visibility {
+                Some(visibility) => repo.visibility.as_deref() == Some(visibility.as_str()),
+                None => true,
+            };
+            futures::future::ready(keep)
+        })
+        .boxed()
+    }
+
+    /// Return every repository (following the pagination)
+    pub async fn all(self) -> Result<Vec<RepositoryInfo>, GHASError> {
+        self.stream().try_collect().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_repositories_params() {
+        let crab = Octocrab::default();
+
+        let list = ListRepositories::new(&crab, "geekmasher")
+            .r#type("sources")
+            .visibility("private")
+            .per_page(250u8);
+        assert_eq!(list.visibility.as_deref(), Some("private"));
+        assert_eq!(
+            serde_json::to_value(&list).unwrap(),
+            serde_json::json!({ "type": "sources", "per_page": 100 })
+        );
+    }
+}
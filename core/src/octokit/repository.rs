@@ -86,6 +86,14 @@ impl Repository {
         self.reference.as_deref()
     }
 
+    /// Set the Repository reference (the branch is updated for `refs/heads/` references)
+    pub fn set_reference(&mut self, reference: &str) {
+        self.reference = Some(reference.to_string());
+        self.branch = reference
+            .split_once("heads/")
+            .map(|(_, branch)| branch.to_string());
+    }
+
     /// Get the Repository branch
     pub fn branch(&self) -> Option<&str> {
         self.branch.as_deref()
@@ -158,36 +166,43 @@ impl Repository {
     pub fn parse(reporef: &str) -> Result<Repository, GHASError> {
         let mut repository = Repository::default();
 
+        // Ignore surrounding whitespace and a trailing slash (copied from a URL)
+        let reporef = reporef.trim();
+        let reporef = reporef.strip_suffix('/').unwrap_or(reporef);
+
         // regex match check
         let re = Regex::new(
             r"^[a-zA-Z0-9-_\.]+/[a-zA-Z0-9-_\.]+((:|/)[a-zA-Z0-9-_/\.]+)?(@[a-zA-Z0-9-_/]+)?$",
         )?;
 
-        re.is_match(reporef).then(|| {
-            let mut current = reporef.to_string();
-            // parse the repository reference
-            match current.split_once('@') {
-                Some((repo, branch)) => {
-                    repository.branch = Some(branch.to_string());
-                    repository.reference = Some(format!("refs/heads/{}", branch));
+        if !re.is_match(reporef) {
+            return Err(GHASError::InvalidRepository(reporef.to_string()));
+        }
 
-                    current = repo.to_string();
-                }
-                _ => {
-                    debug!("No reference found in repository reference");
-                }
+        let mut current = reporef.to_string();
+        // parse the repository reference
+        match current.split_once('@') {
+            Some((repo, branch)) => {
+                repository.branch = Some(branch.to_string());
+                repository.reference = Some(format!("refs/heads/{}", branch));
+
+                current = repo.to_string();
             }
-            // TODO(geekmasher): Support for `:` in the repository reference
-
-            let blocks = current.split('/').collect::<Vec<&str>>();
-            for (i, block) in blocks.iter().enumerate() {
-                match i {
-                    0 => repository.owner = block.to_string(),
-                    1 => repository.name = block.to_string(),
-                    _ => repository.path.push(block),
-                }
+            _ => {
+                debug!("No reference found in repository reference");
             }
-        });
+        }
+        // TODO(geekmasher): Support for `:` in the repository reference
+
+        let blocks = current.split('/').collect::<Vec<&str>>();
+        for (i, block) in blocks.iter().enumerate() {
+            match i {
+                0 => repository.owner = block.to_string(),
+                1 => repository.name = block.to_string(),
+                _ if block.is_empty() => {}
+                _ => repository.path.push(block),
+            }
+        }
 
         Ok(repository)
     }
@@ -211,6 +226,14 @@ impl TryFrom<&str> for Repository {
     }
 }
 
+impl TryFrom<String> for Repository {
+    type Error = GHASError;
+
+    fn try_from(reporef: String) -> Result<Self, Self::Error> {
+        Repository::parse(&reporef)
+    }
+}
+
 /// Repository Builder pattern
 #[derive(Debug, Default, Clone)]
 pub struct RepositoryBuilder {
@@ -302,4 +325,25 @@ mod test {
         assert_eq!(repository.path, PathBuf::from("path/to/file"));
         assert_eq!(repository.branch, Some("main".to_string()));
     }
+
+    #[test]
+    fn test_parse_trailing_slash() {
+        let repository = Repository::parse(" geekmasher/ghastoolkit-rs/ ").unwrap();
+        assert_eq!(repository.owner(), "geekmasher");
+        assert_eq!(repository.name(), "ghastoolkit-rs");
+        assert_eq!(repository.path(), &PathBuf::new());
+
+        let repository = Repository::parse("owner/repo/src//main.rs/\n").unwrap();
+        assert_eq!(repository.path(), &PathBuf::from("src/main.rs"));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let error = Repository::parse("not a repo").unwrap_err();
+        assert!(matches!(error, GHASError::InvalidRepository(ref r) if r == "not a repo"));
+
+        assert!(Repository::parse("").is_err());
+        assert!(Repository::parse("owner").is_err());
+        assert!(Repository::try_from(String::from("owner/")).is_err());
+    }
 }
@@ -0,0 +1,44 @@
+//! Test utilities for the GitHub API clients
+use std::{
+    io::{BufRead, BufReader, Write},
+    sync::{Arc, Mutex},
+};
+
+/// Requests received by a mock server (request lines, e.g. `GET /repos/owner/repo HTTP/1.1`)
+pub(crate) type MockRequests = Arc<Mutex<Vec<String>>>;
+
+/// Start a mock HTTP server responding to requests in order with `(status, body)`,
+/// the bodies are built with the server URL. Returns the URL and the request lines.
+pub(crate) fn mock_server<F>(responses: F) -> (String, MockRequests)
+where
+    F: FnOnce(&str) -> Vec<(&'static str, String)>,
+{
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let server = format!("http://{}", listener.local_addr().unwrap());
+    let responses = responses(&server);
+    let requests = MockRequests::default();
+
+    let recorded = requests.clone();
+    std::thread::spawn(move || {
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            recorded.lock().unwrap().push(line.trim().to_string());
+            // Skip the headers
+            line.clear();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (server, requests)
+}
@@ -2,9 +2,15 @@
 
 use octocrab::{Octocrab, Page, Result as OctoResult};
 
-use crate::Repository;
+use crate::{GHASError, Repository};
 
-use super::secretalerts::{SecretScanningAlert, SecretScanningSort};
+use super::secretalerts::{
+    SecretScanningAlert, SecretScanningAlertResolution, SecretScanningAlertStatus,
+    SecretScanningAlertValidity, SecretScanningLocation, SecretScanningSort,
+};
+
+/// Maximum number of items per page GitHub allows
+const MAX_PER_PAGE: u8 = 100;
 
 /// Secret Scanning Handler
 #[derive(Debug, Clone)]
@@ -19,9 +25,20 @@ impl<'octo> SecretScanningHandler<'octo> {
         Self { crab, repository }
     }
 
-    /// Get a list of code scanning alerts for a repository
-    pub fn list(&self) -> ListSecretScanningAlerts {
-        ListSecretScanningAlerts::new(self)
+    /// Get a list of secret scanning alerts for a repository
+    pub fn list(&self) -> ListSecretScanningAlerts<'octo> {
+        let route = format!(
+            "/repos/{owner}/{repo}/secret-scanning/alerts",
+            owner = self.repository.owner(),
+            repo = self.repository.name()
+        );
+        ListSecretScanningAlerts::new(self.crab, route)
+    }
+
+    /// Get every secret scanning alert for a repository (all pages), both
+    /// open and resolved. Use `list().state(..).all()` to filter the alerts.
+    pub async fn list_all(&self) -> Result<Vec<SecretScanningAlert>, GHASError> {
+        self.list().all_states().all().await
     }
 
     /// Get a single code scanning alert
@@ -35,16 +52,209 @@ impl<'octo> SecretScanningHandler<'octo> {
 
         self.crab.get(route, None::<&()>).await
     }
+
+    /// Get a list of locations for a secret scanning alert
+    pub fn locations(&self, number: u64) -> ListSecretScanningLocations<'octo, '_> {
+        ListSecretScanningLocations::new(self, number)
+    }
+
+    /// Update (resolve / reopen) a single secret scanning alert
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let github = ghastoolkit::GitHub::default();
+    /// let repo = ghastoolkit::Repository::new("geekmasher", "ghastoolkit-rs");
+    ///
+    /// let alert = github
+    ///     .secret_scanning(&repo)
+    ///     .update_alert(1)
+    ///     .resolution("false_positive")
+    ///     .resolution_comment("Test data")
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update_alert(&self, number: u64) -> UpdateSecretScanningAlert<'octo, '_> {
+        UpdateSecretScanningAlert::new(self, number)
+    }
 }
 
-/// List Secret Scanning Alerts
+/// List Secret Scanning Alert Locations
 #[derive(Debug, serde::Serialize)]
-pub struct ListSecretScanningAlerts<'octo, 'b> {
+pub struct ListSecretScanningLocations<'octo, 'b> {
     #[serde(skip)]
     handler: &'b SecretScanningHandler<'octo>,
+    #[serde(skip)]
+    number: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    per_page: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<u8>,
+}
+
+impl<'octo, 'b> ListSecretScanningLocations<'octo, 'b> {
+    pub(crate) fn new(handler: &'b SecretScanningHandler<'octo>, number: u64) -> Self {
+        Self {
+            handler,
+            number,
+            // Default to 100 per page
+            per_page: Some(100),
+            // Default to page 1
+            page: Some(1),
+        }
+    }
+
+    /// Set the number of items per page
+    pub fn per_page(mut self, per_page: impl Into<u8>) -> Self {
+        self.per_page = Some(per_page.into());
+        self
+    }
+
+    /// Set the page number
+    pub fn page(mut self, page: impl Into<u8>) -> Self {
+        self.page = Some(page.into());
+        self
+    }
+
+    /// Send the request
+    pub async fn send(self) -> OctoResult<Page<SecretScanningLocation>> {
+        let route = format!(
+            "/repos/{owner}/{repo}/secret-scanning/alerts/{number}/locations",
+            owner = self.handler.repository.owner(),
+            repo = self.handler.repository.name(),
+            number = self.number
+        );
+
+        self.handler.crab.get(route, Some(&self)).await
+    }
+}
+
+/// Update a Secret Scanning Alert
+#[derive(Debug, serde::Serialize)]
+pub struct UpdateSecretScanningAlert<'octo, 'b> {
+    #[serde(skip)]
+    handler: &'b SecretScanningHandler<'octo>,
+    #[serde(skip)]
+    number: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<SecretScanningAlertStatus>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolution: Option<SecretScanningAlertResolution>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolution_comment: Option<String>,
+}
+
+impl<'octo, 'b> UpdateSecretScanningAlert<'octo, 'b> {
+    pub(crate) fn new(handler: &'b SecretScanningHandler<'octo>, number: u64) -> Self {
+        Self {
+            handler,
+            number,
+            state: None,
+            resolution: None,
+            resolution_comment: None,
+        }
+    }
+
+    /// Set the state of the alert (`open` or `resolved`)
+    pub fn state(mut self, state: impl Into<SecretScanningAlertStatus>) -> Self {
+        self.state = Some(state.into());
+        self
+    }
+
+    /// Set the resolution of the alert (`false_positive`, `wont_fix`, `revoked`, or
+    /// `used_in_tests`). If no state is set, the state will be set to `resolved`.
+    pub fn resolution(mut self, resolution: impl Into<SecretScanningAlertResolution>) -> Self {
+        self.resolution = Some(resolution.into());
+        if self.state.is_none() {
+            self.state = Some(SecretScanningAlertStatus::Resolved);
+        }
+        self
+    }
+
+    /// Set the resolution comment
+    pub fn resolution_comment(mut self, comment: impl Into<String>) -> Self {
+        self.resolution_comment = Some(comment.into());
+        self
+    }
+
+    /// Validate the update request before sending it
+    fn validate(&self) -> Result<(), GHASError> {
+        match self.state {
+            None => Err(GHASError::SecretScanningError(String::from(
+                "State is required to update an alert",
+            ))),
+            Some(SecretScanningAlertStatus::Resolved) if self.resolution.is_none() => {
+                Err(GHASError::SecretScanningError(String::from(
+                    "Resolution is required when resolving an alert",
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Send the request and return the updated alert
+    pub async fn send(self) -> Result<SecretScanningAlert, GHASError> {
+        self.validate()?;
+
+        let route = format!(
+            "/repos/{owner}/{repo}/secret-scanning/alerts/{number}",
+            owner = self.handler.repository.owner(),
+            repo = self.handler.repository.name(),
+            number = self.number
+        );
+
+        Ok(self.handler.crab.patch(route, Some(&self)).await?)
+    }
+}
+
+/// Organization Secret Scanning Handler
+#[derive(Debug, Clone)]
+pub struct OrgSecretScanningHandler<'octo> {
+    crab: &'octo Octocrab,
+    org: String,
+}
+
+impl<'octo> OrgSecretScanningHandler<'octo> {
+    /// Create a new Organization Secret Scanning Handler instance
+    pub(crate) fn new(crab: &'octo Octocrab, org: impl Into<String>) -> Self {
+        Self {
+            crab,
+            org: org.into(),
+        }
+    }
+
+    /// Get a list of secret scanning alerts for an organization
+    pub fn list(&self) -> ListSecretScanningAlerts<'octo> {
+        let route = format!("/orgs/{org}/secret-scanning/alerts", org = self.org);
+        ListSecretScanningAlerts::new(self.crab, route)
+    }
+
+    /// Get every secret scanning alert for an organization (all pages), both
+    /// open and resolved. Use `list().state(..).all()` to filter the alerts.
+    pub async fn list_all(&self) -> Result<Vec<SecretScanningAlert>, GHASError> {
+        self.list().all_states().all().await
+    }
+}
+
+/// List Secret Scanning Alerts
+#[derive(Debug, serde::Serialize)]
+pub struct ListSecretScanningAlerts<'octo> {
+    #[serde(skip)]
+    crab: &'octo Octocrab,
+    #[serde(skip)]
+    route: String,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    state: Option<String>,
+    state: Option<SecretScanningAlertStatus>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     secret_type: Option<String>,
@@ -53,7 +263,7 @@ pub struct ListSecretScanningAlerts<'octo, 'b> {
     sort: Option<SecretScanningSort>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    validity: Option<String>,
+    validity: Option<SecretScanningAlertValidity>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     per_page: Option<u8>,
@@ -61,30 +271,37 @@ pub struct ListSecretScanningAlerts<'octo, 'b> {
     page: Option<u8>,
 }
 
-impl<'octo, 'b> ListSecretScanningAlerts<'octo, 'b> {
-    pub(crate) fn new(handler: &'b SecretScanningHandler<'octo>) -> Self {
+impl<'octo> ListSecretScanningAlerts<'octo> {
+    pub(crate) fn new(crab: &'octo Octocrab, route: String) -> Self {
         Self {
-            handler,
-            state: Some(String::from("open")),
+            crab,
+            route,
+            state: Some(SecretScanningAlertStatus::Open),
             secret_type: None,
             sort: None,
             validity: None,
-            // Default to 100 per page
-            per_page: Some(25),
+            // Default to 100 per page (GitHub's maximum)
+            per_page: Some(MAX_PER_PAGE),
             // Default to page 1
             page: Some(1),
         }
     }
 
-    /// Set the state of the code scanning alert
-    pub fn state(mut self, state: impl Into<String>) -> Self {
-        let state = state.into();
-        if !state.is_empty() {
-            self.state = Some(state);
+    /// Set the state of the secret scanning alert (empty values are ignored)
+    pub fn state(mut self, state: impl Into<SecretScanningAlertStatus>) -> Self {
+        match state.into() {
+            SecretScanningAlertStatus::Unknown(s) if s.is_empty() => {}
+            state => self.state = Some(state),
         }
         self
     }
 
+    /// Clear the state filter so both open and resolved alerts are returned
+    pub fn all_states(mut self) -> Self {
+        self.state = None;
+        self
+    }
+
     /// Set the Secret Type
     pub fn secret_type(mut self, stype: impl Into<String>) -> Self {
         self.secret_type = Some(stype.into());
@@ -97,15 +314,20 @@ impl<'octo, 'b> ListSecretScanningAlerts<'octo, 'b> {
         self
     }
 
-    /// Validity
-    pub fn validity(mut self, validity: impl Into<String>) -> Self {
-        self.validity = Some(validity.into());
+    /// Validity (empty values are ignored)
+    pub fn validity(mut self, validity: impl Into<SecretScanningAlertValidity>) -> Self {
+        match validity.into() {
+            SecretScanningAlertValidity::Other(s) if s.is_empty() => {}
+            validity => self.validity = Some(validity),
+        }
         self
     }
 
-    /// Set the number of items per page
+    /// Set the number of items per page.
+    ///
+    /// GitHub caps `per_page` at 100 so any larger value is clamped to 100.
     pub fn per_page(mut self, per_page: impl Into<u8>) -> Self {
-        self.per_page = Some(per_page.into());
+        self.per_page = Some(per_page.into().min(MAX_PER_PAGE));
         self
     }
 
@@ -117,12 +339,108 @@ impl<'octo, 'b> ListSecretScanningAlerts<'octo, 'b> {
 
     /// Send the request
     pub async fn send(self) -> OctoResult<Page<SecretScanningAlert>> {
-        let route = format!(
-            "/repos/{owner}/{repo}/secret-scanning/alerts",
-            owner = self.handler.repository.owner(),
-            repo = self.handler.repository.name()
+        self.crab.get(&self.route, Some(&self)).await
+    }
+
+    /// Send the request and follow the pagination to return every alert
+    pub async fn all(self) -> Result<Vec<SecretScanningAlert>, GHASError> {
+        let crab = self.crab;
+        let page = self.send().await?;
+        Ok(crab.all_pages(page).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_alerts_params() {
+        let crab = Octocrab::default();
+        let repo = Repository::new("geekmasher", "ghastoolkit-rs");
+        let handler = SecretScanningHandler::new(&crab, &repo);
+
+        let list = handler.list();
+        assert_eq!(list.per_page, Some(100));
+        assert_eq!(list.state, Some(SecretScanningAlertStatus::Open));
+
+        let list = handler.list().all_states().per_page(250u8);
+        assert_eq!(list.state, None);
+        assert_eq!(list.per_page, Some(100));
+
+        let list = handler
+            .list()
+            .state("resolved")
+            .validity(SecretScanningAlertValidity::Active);
+        assert_eq!(
+            serde_json::to_value(&list).unwrap(),
+            serde_json::json!({
+                "state": "resolved",
+                "validity": "active",
+                "per_page": 100,
+                "page": 1
+            })
         );
+        // Empty values (e.g. unset CLI arguments) are ignored
+        let list = handler.list().state("").validity("");
+        assert_eq!(list.state, Some(SecretScanningAlertStatus::Open));
+        assert_eq!(list.validity, None);
+    }
 
-        self.handler.crab.get(route, Some(&self)).await
+    #[tokio::test]
+    async fn test_update_alert_validation() {
+        let crab = Octocrab::default();
+        let repo = Repository::new("geekmasher", "ghastoolkit-rs");
+        let handler = SecretScanningHandler::new(&crab, &repo);
+
+        assert!(handler.update_alert(1).validate().is_err());
+        assert!(handler
+            .update_alert(1)
+            .state("resolved")
+            .validate()
+            .is_err());
+        assert!(handler.update_alert(1).state("open").validate().is_ok());
+
+        let update = handler
+            .update_alert(1)
+            .resolution("false_positive")
+            .resolution_comment("Test data");
+        assert!(update.validate().is_ok());
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            serde_json::json!({
+                "state": "resolved",
+                "resolution": "false_positive",
+                "resolution_comment": "Test data"
+            })
+        );
+    }
+
+    #[test]
+    fn test_alert_enums() {
+        let status: SecretScanningAlertStatus = serde_json::from_str(r#""resolved""#).unwrap();
+        assert_eq!(status, SecretScanningAlertStatus::Resolved);
+        let status: SecretScanningAlertStatus = serde_json::from_str(r#""archived""#).unwrap();
+        assert_eq!(
+            status,
+            SecretScanningAlertStatus::Unknown(String::from("archived"))
+        );
+        assert_eq!(serde_json::to_string(&status).unwrap(), r#""archived""#);
+
+        let resolution: SecretScanningAlertResolution =
+            serde_json::from_str(r#""wont_fix""#).unwrap();
+        assert_eq!(resolution, SecretScanningAlertResolution::WontFix);
+        assert_eq!(
+            SecretScanningAlertResolution::from("used_in_tests"),
+            SecretScanningAlertResolution::UsedInTests
+        );
+
+        let validity: SecretScanningAlertValidity = serde_json::from_str(r#""unknown""#).unwrap();
+        assert_eq!(validity, SecretScanningAlertValidity::Unknown);
+        let validity: SecretScanningAlertValidity = serde_json::from_str(r#""expired""#).unwrap();
+        assert_eq!(
+            validity,
+            SecretScanningAlertValidity::Other(String::from("expired"))
+        );
     }
 }
@@ -16,6 +16,9 @@ pub enum SecretScanningAlertStatus {
     /// Resolved Alert
     #[serde(rename = "resolved")]
     Resolved,
+    /// Unknown status (new values GitHub might add)
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 impl Display for SecretScanningAlertStatus {
@@ -23,10 +26,27 @@ impl Display for SecretScanningAlertStatus {
         match self {
             SecretScanningAlertStatus::Open => write!(f, "Open"),
             SecretScanningAlertStatus::Resolved => write!(f, "Resolved"),
+            SecretScanningAlertStatus::Unknown(s) => write!(f, "{}", s),
         }
     }
 }
 
+impl From<&str> for SecretScanningAlertStatus {
+    fn from(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "open" => SecretScanningAlertStatus::Open,
+            "resolved" => SecretScanningAlertStatus::Resolved,
+            _ => SecretScanningAlertStatus::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for SecretScanningAlertStatus {
+    fn from(value: String) -> Self {
+        SecretScanningAlertStatus::from(value.as_str())
+    }
+}
+
 /// Secret Scanning Alert Resolution
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -50,6 +70,43 @@ pub enum SecretScanningAlertResolution {
     /// Used in Tests
     #[serde(rename = "used_in_tests")]
     UsedInTests,
+    /// Unknown resolution (new values GitHub might add)
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl Display for SecretScanningAlertResolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretScanningAlertResolution::FalsePositive => write!(f, "False Positive"),
+            SecretScanningAlertResolution::WontFix => write!(f, "Won't Fix"),
+            SecretScanningAlertResolution::Revoked => write!(f, "Revoked"),
+            SecretScanningAlertResolution::PatternEdited => write!(f, "Pattern Edited"),
+            SecretScanningAlertResolution::PatternDeleted => write!(f, "Pattern Deleted"),
+            SecretScanningAlertResolution::UsedInTests => write!(f, "Used in Tests"),
+            SecretScanningAlertResolution::Unknown(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<&str> for SecretScanningAlertResolution {
+    fn from(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "false_positive" => SecretScanningAlertResolution::FalsePositive,
+            "wont_fix" => SecretScanningAlertResolution::WontFix,
+            "revoked" => SecretScanningAlertResolution::Revoked,
+            "pattern_edited" => SecretScanningAlertResolution::PatternEdited,
+            "pattern_deleted" => SecretScanningAlertResolution::PatternDeleted,
+            "used_in_tests" => SecretScanningAlertResolution::UsedInTests,
+            _ => SecretScanningAlertResolution::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for SecretScanningAlertResolution {
+    fn from(value: String) -> Self {
+        SecretScanningAlertResolution::from(value.as_str())
+    }
 }
 
 /// Secret Scanning Validity
@@ -66,6 +123,37 @@ pub enum SecretScanningAlertValidity {
     /// Unknown
     #[serde(rename = "unknown")]
     Unknown,
+    /// Other validity (new values GitHub might add)
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl Display for SecretScanningAlertValidity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretScanningAlertValidity::Active => write!(f, "Active"),
+            SecretScanningAlertValidity::Inactive => write!(f, "Inactive"),
+            SecretScanningAlertValidity::Unknown => write!(f, "Unknown"),
+            SecretScanningAlertValidity::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<&str> for SecretScanningAlertValidity {
+    fn from(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "active" => SecretScanningAlertValidity::Active,
+            "inactive" => SecretScanningAlertValidity::Inactive,
+            "unknown" => SecretScanningAlertValidity::Unknown,
+            _ => SecretScanningAlertValidity::Other(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for SecretScanningAlertValidity {
+    fn from(value: String) -> Self {
+        SecretScanningAlertValidity::from(value.as_str())
+    }
 }
 
 /// Secret Scanning Validity
@@ -103,7 +191,7 @@ pub struct SecretScanningAlert {
     pub secret: String,
 
     /// Alert Resolution
-    pub resolved: Option<SecretScanningAlertResolution>,
+    pub resolution: Option<SecretScanningAlertResolution>,
     /// When the alert was resolved
     pub resolved_at: Option<chrono::DateTime<chrono::Utc>>,
     /// Who resolved the alert
@@ -121,6 +209,10 @@ pub struct SecretScanningAlert {
     /// Validity check
     pub validity: Option<SecretScanningAlertValidity>,
 
+    /// Repository of the alert (only present for organization alerts)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<SecretScanningAlertRepository>,
+
     /// URL
     pub url: Url,
     /// HTML
@@ -128,3 +220,209 @@ pub struct SecretScanningAlert {
     /// Locations
     pub locations_url: Url,
 }
+
+/// Minimal Repository returned with organization secret scanning alerts
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct SecretScanningAlertRepository {
+    /// Repository ID
+    pub id: u64,
+    /// Repository name
+    pub name: String,
+    /// Full name (owner/name)
+    pub full_name: String,
+    /// HTML URL
+    pub html_url: Url,
+}
+
+/// Secret Scanning Alert Location
+///
+/// https://docs.github.com/en/rest/secret-scanning/secret-scanning?apiVersion=2022-11-28#list-locations-for-a-secret-scanning-alert
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[non_exhaustive]
+#[serde(
+    try_from = "RawSecretScanningLocation",
+    into = "RawSecretScanningLocation"
+)]
+pub enum SecretScanningLocation {
+    /// Secret found in a commit (file / blob)
+    Commit(SecretScanningCommitLocation),
+    /// Secret found in a wiki commit
+    WikiCommit(SecretScanningCommitLocation),
+    /// Secret found in an issue title
+    IssueTitle {
+        /// API URL of the issue
+        issue_title_url: Url,
+    },
+    /// Secret found in an issue body
+    IssueBody {
+        /// API URL of the issue
+        issue_body_url: Url,
+    },
+    /// Secret found in an issue comment
+    IssueComment {
+        /// API URL of the issue comment
+        issue_comment_url: Url,
+    },
+    /// Any other location type (discussions, pull requests, etc.) kept as returned by GitHub
+    Other {
+        /// Location type name (e.g. `discussion_title`)
+        r#type: String,
+        /// Raw location details
+        details: serde_json::Value,
+    },
+}
+
+/// Raw location used to (de)serialize known and unknown location types
+#[derive(Serialize, Deserialize)]
+struct RawSecretScanningLocation {
+    r#type: String,
+    #[serde(default)]
+    details: serde_json::Value,
+}
+
+impl TryFrom<RawSecretScanningLocation> for SecretScanningLocation {
+    type Error = serde_json::Error;
+
+    fn try_from(value: RawSecretScanningLocation) -> Result<Self, Self::Error> {
+        let url = |key: &str| serde_json::from_value::<Url>(value.details[key].clone());
+
+        Ok(match value.r#type.as_str() {
+            "commit" => SecretScanningLocation::Commit(serde_json::from_value(value.details)?),
+            "wiki_commit" => {
+                SecretScanningLocation::WikiCommit(serde_json::from_value(value.details)?)
+            }
+            "issue_title" => SecretScanningLocation::IssueTitle {
+                issue_title_url: url("issue_title_url")?,
+            },
+            "issue_body" => SecretScanningLocation::IssueBody {
+                issue_body_url: url("issue_body_url")?,
+            },
+            "issue_comment" => SecretScanningLocation::IssueComment {
+                issue_comment_url: url("issue_comment_url")?,
+            },
+            _ => SecretScanningLocation::Other {
+                r#type: value.r#type,
+                details: value.details,
+            },
+        })
+    }
+}
+
+impl From<SecretScanningLocation> for RawSecretScanningLocation {
+    fn from(value: SecretScanningLocation) -> Self {
+        let (r#type, details) = match value {
+            SecretScanningLocation::Commit(commit) => ("commit", serde_json::json!(commit)),
+            SecretScanningLocation::WikiCommit(commit) => {
+                ("wiki_commit", serde_json::json!(commit))
+            }
+            SecretScanningLocation::IssueTitle { issue_title_url } => (
+                "issue_title",
+                serde_json::json!({ "issue_title_url": issue_title_url }),
+            ),
+            SecretScanningLocation::IssueBody { issue_body_url } => (
+                "issue_body",
+                serde_json::json!({ "issue_body_url": issue_body_url }),
+            ),
+            SecretScanningLocation::IssueComment { issue_comment_url } => (
+                "issue_comment",
+                serde_json::json!({ "issue_comment_url": issue_comment_url }),
+            ),
+            SecretScanningLocation::Other { r#type, details } => {
+                return RawSecretScanningLocation { r#type, details }
+            }
+        };
+        RawSecretScanningLocation {
+            r#type: r#type.to_string(),
+            details,
+        }
+    }
+}
+
+/// Secret Scanning Commit / Blob Location
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct SecretScanningCommitLocation {
+    /// File path in the repository
+    pub path: String,
+    /// Start line
+    pub start_line: u32,
+    /// End line
+    pub end_line: u32,
+    /// Start column
+    pub start_column: u32,
+    /// End column
+    pub end_column: u32,
+    /// Blob SHA
+    pub blob_sha: String,
+    /// Blob API URL
+    pub blob_url: Url,
+    /// Commit SHA
+    pub commit_sha: String,
+    /// Commit API URL
+    pub commit_url: Url,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locations() {
+        let data = r#"[
+          {
+            "type": "commit",
+            "details": {
+              "path": "/example/secrets.txt",
+              "start_line": 1,
+              "end_line": 1,
+              "start_column": 1,
+              "end_column": 64,
+              "blob_sha": "af5626b4a114abcb82d63db7c8082c3c4756e51b",
+              "blob_url": "https://api.github.com/repos/octocat/hello-world/git/blobs/af5626b4a114abcb82d63db7c8082c3c4756e51b",
+              "commit_sha": "f14d7debf9775f957cf4f1e8176da0786431f72b",
+              "commit_url": "https://api.github.com/repos/octocat/hello-world/git/commits/f14d7debf9775f957cf4f1e8176da0786431f72b"
+            }
+          },
+          {
+            "type": "issue_title",
+            "details": {
+              "issue_title_url": "https://api.github.com/repos/octocat/Hello-World/issues/1347"
+            }
+          },
+          {
+            "type": "discussion_title",
+            "details": {
+              "discussion_title_url": "https://github.com/community/hello-world/discussions/79"
+            }
+          }
+        ]"#;
+
+        let locations: Vec<SecretScanningLocation> = serde_json::from_str(data).unwrap();
+        assert_eq!(locations.len(), 3);
+
+        match &locations[0] {
+            SecretScanningLocation::Commit(commit) => {
+                assert_eq!(commit.path, "/example/secrets.txt");
+                assert_eq!(commit.end_column, 64);
+            }
+            _ => panic!("Expected a commit location"),
+        }
+        assert!(matches!(
+            locations[1],
+            SecretScanningLocation::IssueTitle { .. }
+        ));
+        match &locations[2] {
+            SecretScanningLocation::Other { r#type, details } => {
+                assert_eq!(r#type, "discussion_title");
+                assert_eq!(
+                    details["discussion_title_url"],
+                    "https://github.com/community/hello-world/discussions/79"
+                );
+            }
+            _ => panic!("Expected an other location"),
+        }
+
+        // Known and unknown locations round-trip
+        let expected: serde_json::Value = serde_json::from_str(data).unwrap();
+        assert_eq!(serde_json::to_value(&locations).unwrap(), expected);
+    }
+}
@@ -1,5 +1,7 @@
+use std::{cmp::Ordering, collections::HashMap};
+
 use crate::{
-    supplychain::{License, Licenses},
+    supplychain::{DependencyRelationship, DependencyScope, License, Licenses},
     Dependency,
 };
 
@@ -9,14 +11,6 @@ pub struct Dependencies {
     dependencies: Vec<Dependency>,
 }
 
-impl Iterator for Dependencies {
-    type Item = Dependency;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.dependencies.pop()
-    }
-}
-
 impl Dependencies {
     /// Create a new list of dependencies
     ///
@@ -50,6 +44,78 @@ impl Dependencies {
         self.dependencies.extend(dependencies);
     }
 
+    /// Iterate over the dependencies (in order) without consuming the list
+    pub fn iter(&self) -> impl Iterator<Item = &Dependency> {
+        self.dependencies.iter()
+    }
+
+    /// Iterate over the dependencies mutably (e.g. to update their licenses)
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Dependency> {
+        self.dependencies.iter_mut()
+    }
+
+    /// Sort the dependencies by manager, then name, then version.
+    ///
+    /// Versions are compared as semantic versions when possible (`0.10.0` is after
+    /// `0.9.0`), dependencies without a version come first.
+    pub fn sort(&mut self) {
+        self.dependencies.sort_by(|a, b| {
+            a.manager
+                .cmp(&b.manager)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| compare_versions(a.version.as_deref(), b.version.as_deref()))
+        });
+    }
+
+    /// Remove duplicate dependencies (same manager, namespace, name, and version).
+    ///
+    /// The duplicates are merged into the first entry: the licenses and dependency
+    /// edges are combined, and the entry is runtime / direct if any of the duplicates is.
+    pub fn dedup(&mut self) {
+        let mut deduped: Vec<Dependency> = Vec::with_capacity(self.dependencies.len());
+        let mut seen: HashMap<(String, Option<String>, String, Option<String>), usize> =
+            HashMap::with_capacity(self.dependencies.len());
+
+        for dependency in self.dependencies.drain(..) {
+            let key = (
+                dependency.manager.clone(),
+                dependency.namespace.clone(),
+                dependency.name.clone(),
+                dependency.version.clone(),
+            );
+            match seen.get(&key) {
+                Some(&index) => {
+                    let entry = &mut deduped[index];
+                    entry.licenses.merge(dependency.licenses);
+                    if dependency.scope == DependencyScope::Runtime {
+                        entry.scope = DependencyScope::Runtime;
+                    }
+                    if dependency.relationship == DependencyRelationship::Direct {
+                        entry.relationship = DependencyRelationship::Direct;
+                    }
+                    for purl in dependency.dependencies {
+                        if !entry.dependencies.contains(&purl) {
+                            entry.dependencies.push(purl);
+                        }
+                    }
+                    entry.dependencies.sort();
+                }
+                None => {
+                    seen.insert(key, deduped.len());
+                    deduped.push(dependency);
+                }
+            }
+        }
+
+        self.dependencies = deduped;
+    }
+
+    /// Merge another list of dependencies into this list and remove any duplicates
+    pub fn merge(&mut self, other: Dependencies) {
+        self.dependencies.extend(other.dependencies);
+        self.dedup();
+    }
+
     /// Get the length of the list of dependencies
     pub fn len(&self) -> usize {
         self.dependencies.len()
@@ -89,6 +155,70 @@ impl Dependencies {
             .collect()
     }
 
+    /// Find the dependencies using a denied license (license policy violations).
+    ///
+    /// Licenses are compared using their SPDX identifiers so `GPL-3.0` matches
+    /// `GPL-3.0-only` and `GPL-3.0-or-later`. Dependencies with an SPDX expression
+    /// are only violations if every `OR` choice includes a denied license
+    /// (`GPL-3.0 OR MIT` is allowed).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ghastoolkit::{supplychain::License, Dependencies, Dependency};
+    ///
+    /// let mut dependencies = Dependencies::new();
+    /// dependencies.push(Dependency::from(("pkg:cargo/example@1.0.0", "GPL-3.0-only")));
+    /// dependencies.push(Dependency::from(("pkg:cargo/later@1.0.0", "GPL-3.0-or-later")));
+    /// dependencies.push(Dependency::from(("pkg:cargo/other@1.0.0", "MIT")));
+    ///
+    /// let violations = dependencies.license_violations(&[License::from("GPL-3.0")]);
+    /// assert_eq!(violations.len(), 2);
+    /// ```
+    pub fn license_violations(&self, denied: &[License]) -> Vec<&Dependency> {
+        self.dependencies
+            .iter()
+            .filter(|d| d.licenses.is_denied_by(denied))
+            .collect()
+    }
+
+    /// Export the dependencies as the `resolved` map of a Dependency Submission API
+    /// snapshot manifest, keyed by PURL.
+    ///
+    /// https://docs.github.com/en/rest/dependency-graph/dependency-submission
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ghastoolkit::{Dependencies, Dependency};
+    /// use ghastoolkit::supplychain::DependencyScope;
+    ///
+    /// let mut dependencies = Dependencies::new();
+    /// dependencies.push(Dependency::from("pkg:cargo/serde@1.0.200").with_scope(DependencyScope::Development));
+    ///
+    /// let resolved = dependencies.snapshot_resolved();
+    /// assert_eq!(resolved["pkg:cargo/serde@1.0.200"]["scope"], "development");
+    /// ```
+    pub fn snapshot_resolved(&self) -> serde_json::Value {
+        let resolved: serde_json::Map<String, serde_json::Value> = self
+            .dependencies
+            .iter()
+            .map(|d| {
+                let purl = d.purl();
+                (
+                    purl.clone(),
+                    serde_json::json!({
+                        "package_url": purl,
+                        "relationship": d.relationship,
+                        "scope": d.scope,
+                        "dependencies": d.dependencies,
+                    }),
+                )
+            })
+            .collect();
+        serde_json::Value::Object(resolved)
+    }
+
     /// Find a list of dependencies by licenses
     pub fn find_by_licenses(&self, licenses: &Licenses) -> Vec<Dependency> {
         // TODO(geekmasher): the clone here is not great, but it's a quick fix for now
@@ -105,9 +235,49 @@ impl Dependencies {
     }
 }
 
+/// Compare two (optional) versions, using semantic versioning if both versions are valid
+fn compare_versions(a: Option<&str>, b: Option<&str>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => match (semver::Version::parse(a), semver::Version::parse(b)) {
+            (Ok(va), Ok(vb)) => va.cmp(&vb),
+            _ => a.cmp(b),
+        },
+        (a, b) => a.cmp(&b),
+    }
+}
+
+impl IntoIterator for Dependencies {
+    type Item = Dependency;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.dependencies.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Dependencies {
+    type Item = &'a Dependency;
+    type IntoIter = std::slice::Iter<'a, Dependency>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.dependencies.iter()
+    }
+}
+
+impl FromIterator<Dependency> for Dependencies {
+    fn from_iter<T: IntoIterator<Item = Dependency>>(iter: T) -> Self {
+        Self {
+            dependencies: iter.into_iter().collect(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{supplychain::License, Dependencies, Dependency};
+    use crate::{
+        supplychain::{DependencyRelationship, DependencyScope, License, Licenses},
+        Dependencies, Dependency,
+    };
 
     #[test]
     fn test_find_by_name() {
@@ -144,4 +314,147 @@ mod tests {
         assert_eq!(dep.name, "ghastoolkit-rs");
         assert_eq!(dep.manager, "cargo");
     }
+
+    #[test]
+    fn test_license_violations() {
+        let mut choice = Dependency::from("pkg:cargo/choice@1.0.0");
+        choice.licenses = Licenses::from_spdx_expr("GPL-3.0 OR MIT").unwrap();
+        let mut both = Dependency::from("pkg:cargo/both@1.0.0");
+        both.licenses = Licenses::from_spdx_expr("GPL-3.0-or-later AND MIT").unwrap();
+
+        let mut deps = Dependencies::new();
+        deps.extend(vec![
+            Dependency::from(("pkg:cargo/only@1.0.0", "GPL-3.0-only")),
+            Dependency::from(("pkg:cargo/later@1.0.0", "GPL-3.0-or-later")),
+            Dependency::from(("pkg:cargo/mit@1.0.0", "MIT")),
+            choice,
+            both,
+        ]);
+
+        let violations: Vec<&str> = deps
+            .license_violations(&[License::from("GPL-3.0")])
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect();
+        assert_eq!(violations, vec!["only", "later", "both"]);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut deps = Dependencies::new();
+        deps.extend(vec![
+            Dependency::from(("pkg:cargo/ghastoolkit-rs@0.2.0", "MIT")),
+            Dependency::from(("pkg:cargo/ghastoolkit-rs@0.2.0", "Apache-2.0")),
+            Dependency::from(("pkg:cargo/ghastoolkit-rs@0.3.0", "MIT")),
+            // Same name in different namespaces
+            Dependency::from(("pkg:npm/%40geekmasher/ghastoolkit@1.0.0", "MIT")),
+            Dependency::from(("pkg:npm/%40other/ghastoolkit@1.0.0", "MIT")),
+        ]);
+
+        deps.dedup();
+        assert_eq!(deps.len(), 4);
+
+        let dep = deps
+            .find_by_name("ghastoolkit-rs")
+            .expect("Failed to find dependency by name");
+        assert_eq!(dep.licenses.len(), 2);
+        assert!(dep.licenses.contains(&License::MIT));
+        assert!(dep.licenses.contains(&License::Apache(String::from("2.0"))));
+    }
+
+    #[test]
+    fn test_dedup_scope_and_relationship() {
+        let mut dev = Dependency::from("pkg:npm/ms@2.1.2")
+            .with_scope(DependencyScope::Development)
+            .with_relationship(DependencyRelationship::Direct);
+        dev.dependencies = vec![String::from("pkg:npm/a@1.0.0")];
+        let mut runtime = Dependency::from("pkg:npm/ms@2.1.2")
+            .with_scope(DependencyScope::Runtime)
+            .with_relationship(DependencyRelationship::Indirect);
+        runtime.dependencies = vec![
+            String::from("pkg:npm/b@1.0.0"),
+            String::from("pkg:npm/a@1.0.0"),
+        ];
+
+        let mut deps = Dependencies::new();
+        deps.extend(vec![dev, runtime]);
+        deps.dedup();
+        assert_eq!(deps.len(), 1);
+
+        // Runtime and direct win over development and indirect
+        let ms = deps.find_by_name("ms").unwrap();
+        assert_eq!(ms.scope, DependencyScope::Runtime);
+        assert_eq!(ms.relationship, DependencyRelationship::Direct);
+        assert_eq!(ms.dependencies, vec!["pkg:npm/a@1.0.0", "pkg:npm/b@1.0.0"]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut deps = Dependencies::new();
+        deps.push(Dependency::from(("pkg:cargo/ghastoolkit-rs@0.2.0", "MIT")));
+
+        let mut other = Dependencies::new();
+        other.push(Dependency::from(("pkg:cargo/ghastoolkit-rs@0.2.0", "MIT")));
+        other.push(Dependency::from("pkg:pip/ghastoolkit@0.12.0"));
+
+        deps.merge(other);
+        assert_eq!(deps.len(), 2);
+        let dep = deps.find_by_name("ghastoolkit-rs").unwrap();
+        assert_eq!(dep.licenses.len(), 1);
+    }
+
+    #[test]
+    fn test_snapshot_resolved() {
+        let mut deps = Dependencies::new();
+        deps.push(Dependency::from("pkg:cargo/serde@1.0.200"));
+        deps.push(
+            Dependency::from("pkg:cargo/anyhow@1.0.0")
+                .with_relationship(DependencyRelationship::Indirect)
+                .with_scope(DependencyScope::Development),
+        );
+
+        let resolved = deps.snapshot_resolved();
+        let serde = &resolved["pkg:cargo/serde@1.0.200"];
+        assert_eq!(serde["package_url"], "pkg:cargo/serde@1.0.200");
+        assert_eq!(serde["relationship"], "direct");
+        assert_eq!(serde["scope"], "runtime");
+        assert_eq!(serde["dependencies"], serde_json::json!([]));
+
+        let anyhow = &resolved["pkg:cargo/anyhow@1.0.0"];
+        assert_eq!(anyhow["relationship"], "indirect");
+        assert_eq!(anyhow["scope"], "development");
+    }
+
+    #[test]
+    fn test_sort_and_iter() {
+        let mut deps: Dependencies = vec![
+            Dependency::from("pkg:pip/requests@2.0.0"),
+            Dependency::from("pkg:cargo/serde@1.0.10"),
+            Dependency::from("pkg:cargo/serde@1.0.9"),
+            Dependency::from("pkg:cargo/anyhow@1.0.0"),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(deps.len(), 4);
+
+        deps.sort();
+        let names: Vec<String> = deps
+            .iter()
+            .map(|d| format!("{}/{}@{}", d.manager, d.name, d.version.clone().unwrap()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "cargo/anyhow@1.0.0",
+                "cargo/serde@1.0.9",
+                "cargo/serde@1.0.10",
+                "pip/requests@2.0.0"
+            ]
+        );
+
+        // Iterating by reference and by value keeps the order
+        assert_eq!((&deps).into_iter().count(), 4);
+        let first = deps.into_iter().next().unwrap();
+        assert_eq!(first.name, "anyhow");
+    }
 }
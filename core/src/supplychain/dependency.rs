@@ -1,6 +1,7 @@
 use std::{collections::HashMap, fmt::Display, str::FromStr};
 
 use purl::GenericPurl;
+use serde::{Deserialize, Serialize};
 
 use crate::{supplychain::licenses::Licenses, Repository};
 
@@ -26,30 +27,162 @@ pub struct Dependency {
     pub namespace: Option<String>,
     /// Version of the dependency
     pub version: Option<String>,
-    /// Path to the dependency
+    /// Path to the dependency (PURL subpath)
     path: Option<String>,
     /// Qualifiers for the dependency
     qualifiers: HashMap<String, String>,
     /// SPDX licenses for the dependency
     pub licenses: Licenses,
+    /// Scope of the dependency (runtime or development)
+    pub scope: DependencyScope,
+    /// Relationship of the dependency to the project (direct or indirect)
+    pub relationship: DependencyRelationship,
+    /// PURLs of the dependencies this dependency depends on
+    pub dependencies: Vec<String>,
 
     repository: Option<Repository>,
     /// PURL
     purl: Option<GenericPurl<String>>,
 }
 
+/// Scope of a dependency (as used by the Dependency Submission API)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyScope {
+    /// Dependency used at runtime (default)
+    #[default]
+    Runtime,
+    /// Dependency only used for development (tests, builds, etc.)
+    Development,
+}
+
+impl Display for DependencyScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DependencyScope::Runtime => write!(f, "runtime"),
+            DependencyScope::Development => write!(f, "development"),
+        }
+    }
+}
+
+/// Relationship of a dependency to the project (as used by the Dependency Submission API)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyRelationship {
+    /// Dependency declared by the project itself (default)
+    #[default]
+    Direct,
+    /// Dependency pulled in by another dependency (transitive)
+    Indirect,
+}
+
+impl Display for DependencyRelationship {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DependencyRelationship::Direct => write!(f, "direct"),
+            DependencyRelationship::Indirect => write!(f, "indirect"),
+        }
+    }
+}
+
 impl Dependency {
     /// Create a new Dependency
     pub fn new() -> Self {
         Default::default()
     }
 
-    /// Get the PURL for the dependency
+    /// Set the scope of the dependency
+    pub fn with_scope(mut self, scope: DependencyScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Set the relationship of the dependency
+    pub fn with_relationship(mut self, relationship: DependencyRelationship) -> Self {
+        self.relationship = relationship;
+        self
+    }
+
+    /// Get the qualifiers of the dependency (decoded PURL qualifiers)
+    pub fn qualifiers(&self) -> &HashMap<String, String> {
+        &self.qualifiers
+    }
+
+    /// Get the subpath of the dependency (decoded PURL subpath)
+    pub fn subpath(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Get the GitHub repository hosting the dependency (if known).
+    ///
+    /// This is the repository set on the dependency or, for GitHub hosted packages
+    /// (`pkg:github/owner/repo`, `pkg:githubactions/owner/repo` and
+    /// `pkg:golang/github.com/owner/repo`), the repository from the PURL.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ghastoolkit::Dependency;
+    ///
+    /// let dependency = Dependency::from("pkg:github/geekmasher/ghastoolkit-rs@v0.5.0");
+    /// let repository = dependency.github_repository().unwrap();
+    /// assert_eq!(repository.to_string(), "geekmasher/ghastoolkit-rs");
+    /// ```
+    pub fn github_repository(&self) -> Option<Repository> {
+        if let Some(repository) = &self.repository {
+            return Some(repository.clone());
+        }
+
+        let namespace = self.namespace.as_deref()?;
+        let owner = match self.manager.as_str() {
+            "github" | "githubactions" => namespace,
+            "golang" => namespace.strip_prefix("github.com/")?,
+            _ => return None,
+        };
+        // Go modules can be nested in a repository (github.com/owner/repo/sub)
+        match owner.split_once('/') {
+            Some((owner, repo)) => Some(Repository::new(owner, repo)),
+            None => Some(Repository::new(owner, self.name.as_str())),
+        }
+    }
+
+    /// Get the canonical PURL for the dependency.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ghastoolkit::Dependency;
+    ///
+    /// let dependency = Dependency::from("pkg:golang/github.com/foo/bar@v1?goos=linux#subdir");
+    /// # assert_eq!(dependency.namespace, Some("github.com/foo".to_string()));
+    /// # assert_eq!(dependency.subpath(), Some("subdir"));
+    ///
+    /// assert_eq!(dependency.purl(), "pkg:golang/github.com/foo/bar@v1?goos=linux#subdir");
+    /// ```
     pub fn purl(&self) -> String {
-        if let Some(purl) = &self.purl {
-            purl.to_string()
-        } else {
-            format!("pkg:{}@{}", self.manager, self.name)
+        let mut builder = GenericPurl::<String>::builder(self.manager.clone(), self.name.as_str());
+        if let Some(namespace) = &self.namespace {
+            builder = builder.with_namespace(namespace.as_str());
+        }
+        if let Some(version) = &self.version {
+            builder = builder.with_version(version.as_str());
+        }
+        if let Some(subpath) = &self.path {
+            builder = builder.with_subpath(subpath.as_str());
+        }
+        for (key, value) in &self.qualifiers {
+            builder = match builder.clone().with_qualifier(key.as_str(), value.as_str()) {
+                Ok(b) => b,
+                Err(_) => builder,
+            };
+        }
+
+        match builder.build() {
+            Ok(purl) => purl.to_string(),
+            Err(_) => match &self.purl {
+                Some(purl) => purl.to_string(),
+                None => format!("pkg:{}/{}", self.manager, self.name),
+            },
         }
     }
 }
@@ -79,6 +212,12 @@ impl From<GenericPurl<String>> for Dependency {
             namespace: value.namespace().map(|s| s.to_string()),
             version: value.version().map(|s| s.to_string()),
             manager: value.package_type().clone(),
+            path: value.subpath().map(|s| s.to_string()),
+            qualifiers: value
+                .qualifiers()
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
             purl: Some(value),
             ..Default::default()
         }
@@ -110,4 +249,80 @@ mod tests {
             "pkg:generic/namespace/name@version".to_string()
         );
     }
+
+    #[test]
+    fn test_dependency_namespace() {
+        let dependency = Dependency::from("pkg:maven/org.apache/commons-lang3@3.12");
+        assert_eq!(dependency.manager, "maven");
+        assert_eq!(dependency.namespace, Some("org.apache".to_string()));
+        assert_eq!(dependency.name, "commons-lang3");
+        assert_eq!(dependency.version, Some("3.12".to_string()));
+        assert_eq!(dependency.purl(), "pkg:maven/org.apache/commons-lang3@3.12");
+    }
+
+    #[test]
+    fn test_dependency_qualifiers_subpath() {
+        let purl = "pkg:golang/github.com/foo/bar@v1?goos=linux#subdir";
+        let dependency = Dependency::from(purl);
+        assert_eq!(dependency.namespace, Some("github.com/foo".to_string()));
+        assert_eq!(dependency.name, "bar");
+        assert_eq!(
+            dependency.qualifiers().get("goos"),
+            Some(&"linux".to_string())
+        );
+        assert_eq!(dependency.subpath(), Some("subdir"));
+        assert_eq!(dependency.purl(), purl);
+    }
+
+    #[test]
+    fn test_dependency_percent_decoding() {
+        let purl = "pkg:npm/%40angular/core@16.0.0";
+        let dependency = Dependency::from(purl);
+        assert_eq!(dependency.namespace, Some("@angular".to_string()));
+        assert_eq!(dependency.name, "core");
+        assert_eq!(dependency.purl(), purl);
+    }
+
+    #[test]
+    fn test_dependency_purl_from_fields() {
+        let mut dependency = Dependency::new();
+        dependency.manager = "cargo".to_string();
+        dependency.name = "ghastoolkit".to_string();
+        dependency.version = Some("0.5.1".to_string());
+        assert_eq!(dependency.purl(), "pkg:cargo/ghastoolkit@0.5.1");
+    }
+
+    #[test]
+    fn test_scope_relationship() {
+        let dependency = Dependency::from("pkg:cargo/serde@1.0.200");
+        assert_eq!(dependency.scope, DependencyScope::Runtime);
+        assert_eq!(dependency.relationship, DependencyRelationship::Direct);
+
+        let dependency = dependency
+            .with_scope(DependencyScope::Development)
+            .with_relationship(DependencyRelationship::Indirect);
+        assert_eq!(dependency.scope.to_string(), "development");
+        assert_eq!(
+            serde_json::to_value(dependency.relationship).unwrap(),
+            "indirect"
+        );
+    }
+
+    #[test]
+    fn test_github_repository() {
+        let dependency = Dependency::from("pkg:githubactions/actions/checkout@v4");
+        let repo = dependency.github_repository().unwrap();
+        assert_eq!(repo.owner(), "actions");
+        assert_eq!(repo.name(), "checkout");
+
+        let dependency = Dependency::from("pkg:golang/github.com/foo/bar@v1");
+        assert_eq!(dependency.github_repository().unwrap().name(), "bar");
+        let dependency = Dependency::from("pkg:golang/github.com/foo/bar/v2@v2.0.0");
+        assert_eq!(dependency.github_repository().unwrap().name(), "bar");
+
+        let dependency = Dependency::from("pkg:cargo/ghastoolkit@0.5.1");
+        assert!(dependency.github_repository().is_none());
+        let dependency = Dependency::from("pkg:golang/golang.org/x/net@v0.1.0");
+        assert!(dependency.github_repository().is_none());
+    }
 }
@@ -1,4 +1,8 @@
-use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::NOASSERTION;
 
 /// A Dependency License enum with SPDX and custom licenses. We only support a few licenses
 /// but you can use the `Custom` variant to add your own license.
@@ -13,7 +17,7 @@ use serde::{Deserialize, Serialize};
 /// assert_eq!(license, License::MIT);
 ///
 /// ```
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum License {
     /// Apache (1.0, 1.1, 2.0)
     Apache(String),
@@ -46,18 +50,161 @@ impl From<&str> for License {
             // apache-1.0 or apache-2.0
             value if value.contains("apache") => License::Apache(split_or_default(value, "-")),
             value if value.contains("mit") => License::MIT,
-            value if value.starts_with("gpl") => License::GPL(split_or_default(value, "-")),
-            value if value.starts_with("lgpl") => License::LGPL(split_or_default(value, "-")),
-            value if value.starts_with("agpl") => License::AGPL(split_or_default(value, "-")),
+            value if value.starts_with("gpl") => License::GPL(gnu_version(value)),
+            value if value.starts_with("lgpl") => License::LGPL(gnu_version(value)),
+            value if value.starts_with("agpl") => License::AGPL(gnu_version(value)),
             value if value.starts_with("mpl") => License::MPL(split_or_default(value, "-")),
             value if value.starts_with("bsd") => License::BSD(split_or_default(value, "-")),
-            "cc0" => License::CC0,
+            "cc0" | "cc0-1.0" => License::CC0,
             "isc" => License::ISC,
+            "" | "noassertion" => License::Unknown,
             _ => License::Custom(String::from(value)),
         }
     }
 }
 
+impl License {
+    /// Get the SPDX license identifier (e.g. `Apache-2.0`, `GPL-3.0-only` or `MIT`).
+    ///
+    /// Licenses without a known version (and `Unknown`) return `NOASSERTION`, custom
+    /// licenses return the stored license string.
+    ///
+    /// ```rust
+    /// use ghastoolkit::supplychain::License;
+    ///
+    /// assert_eq!(License::from("Apache-2.0").to_spdx_id(), "Apache-2.0");
+    /// assert_eq!(License::from("GPL-3.0").to_spdx_id(), "GPL-3.0-only");
+    /// assert_eq!(License::from("BSD-3-Clause").to_spdx_id(), "BSD-3-Clause");
+    /// ```
+    pub fn to_spdx_id(&self) -> String {
+        match self {
+            License::Apache(version) => spdx_versioned("Apache", version, false),
+            License::MIT => String::from("MIT"),
+            License::GPL(version) => spdx_versioned("GPL", version, true),
+            License::LGPL(version) => spdx_versioned("LGPL", version, true),
+            License::AGPL(version) => spdx_versioned("AGPL", version, true),
+            License::MPL(version) => spdx_versioned("MPL", version, false),
+            License::BSD(clauses) => match clauses.strip_suffix("-clause") {
+                Some(count) if is_version(count) => format!("BSD-{}-Clause", count),
+                _ => String::from(NOASSERTION),
+            },
+            License::CC0 => String::from("CC0-1.0"),
+            License::ISC => String::from("ISC"),
+            License::Custom(license) => license.clone(),
+            License::Unknown => String::from(NOASSERTION),
+        }
+    }
+
+    /// Check if the license has a known SPDX license identifier (custom licenses and
+    /// licenses without a known version don't)
+    pub fn is_spdx(&self) -> bool {
+        match self {
+            License::Custom(_) | License::Unknown => false,
+            _ => self.to_spdx_id() != NOASSERTION,
+        }
+    }
+
+    /// Check if the license is denied by another license. A version denies all of its
+    /// variants (`GPL-3.0` denies `GPL-3.0-only` and `GPL-3.0-or-later`) but
+    /// `GPL-3.0-or-later` only denies itself.
+    ///
+    /// ```rust
+    /// use ghastoolkit::supplychain::License;
+    ///
+    /// let gpl = License::from("GPL-3.0");
+    /// assert!(License::from("GPL-3.0-or-later").is_denied_by(&gpl));
+    /// assert!(!License::from("GPL-2.0-only").is_denied_by(&gpl));
+    /// ```
+    pub fn is_denied_by(&self, denied: &License) -> bool {
+        let id = self.to_spdx_id();
+        let denied = denied.to_spdx_id();
+        if id == NOASSERTION || denied == NOASSERTION {
+            return false;
+        }
+        match denied.strip_suffix("-only") {
+            Some(base) => id == denied || id.strip_suffix("-or-later") == Some(base),
+            None => id == denied,
+        }
+    }
+}
+
+/// Create a versioned SPDX identifier (`<name>-<version>`). For the GNU licenses
+/// the `-only` / `-or-later` suffix is added (`-only` by default, `+` means `-or-later`).
+fn spdx_versioned(name: &str, version: &str, gnu: bool) -> String {
+    let (version, suffix) = if let Some(v) = version.strip_suffix("-or-later") {
+        (v, "-or-later")
+    } else if let Some(v) = version.strip_suffix('+') {
+        (v, "-or-later")
+    } else if let Some(v) = version.strip_suffix("-only") {
+        (v, "-only")
+    } else {
+        (version, "-only")
+    };
+
+    if !is_version(version) {
+        return String::from(NOASSERTION);
+    }
+    if gnu {
+        format!("{}-{}{}", name, version, suffix)
+    } else {
+        format!("{}-{}", name, version)
+    }
+}
+
+/// Get the version of a GNU license, `-only` is the default (`3.0-only` is `3.0`) and
+/// `+` is the same as `-or-later`
+fn gnu_version(value: &str) -> String {
+    let version = split_or_default(value, "-");
+    if let Some(version) = version.strip_suffix("-only") {
+        String::from(version)
+    } else if let Some(version) = version.strip_suffix('+') {
+        format!("{}-or-later", version)
+    } else {
+        version
+    }
+}
+
+/// Check if a value looks like a license version (`2.0`, `3`)
+fn is_version(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+impl Display for License {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_spdx_id())
+    }
+}
+
+impl Serialize for License {
+    /// Licenses are serialized as their SPDX identifier, licenses without a known version
+    /// keep their name and version so they deserialize to the same license.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (name, version) = match self {
+            License::Apache(version) => ("Apache", version),
+            License::GPL(version) => ("GPL", version),
+            License::LGPL(version) => ("LGPL", version),
+            License::AGPL(version) => ("AGPL", version),
+            License::MPL(version) => ("MPL", version),
+            License::BSD(version) => ("BSD", version),
+            _ => return serializer.serialize_str(&self.to_spdx_id()),
+        };
+        if self.is_spdx() {
+            serializer.serialize_str(&self.to_spdx_id())
+        } else if version.eq_ignore_ascii_case(name) {
+            serializer.serialize_str(name)
+        } else {
+            serializer.serialize_str(&format!("{}-{}", name, version))
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for License {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(License::from(value))
+    }
+}
+
 /// This helper function will split a string by a separator and return
 /// the second part or the default value (the same string).
 fn split_or_default(value: &str, sep: &str) -> String {
@@ -104,4 +251,99 @@ mod tests {
         let license = super::split_or_default("MIT", "-");
         assert_eq!(license, "MIT");
     }
+
+    #[test]
+    fn test_to_spdx_id() {
+        assert_eq!(License::MIT.to_spdx_id(), "MIT");
+        assert_eq!(License::from("Apache-2.0").to_spdx_id(), "Apache-2.0");
+        assert_eq!(License::from("apache").to_spdx_id(), "NOASSERTION");
+        assert_eq!(License::from("GPL-2.0").to_spdx_id(), "GPL-2.0-only");
+        assert_eq!(License::from("GPL-3.0-only").to_spdx_id(), "GPL-3.0-only");
+        assert_eq!(
+            License::from("LGPL-2.1-or-later").to_spdx_id(),
+            "LGPL-2.1-or-later"
+        );
+        assert_eq!(License::from("AGPL-3.0+").to_spdx_id(), "AGPL-3.0-or-later");
+        assert_eq!(License::from("MPL-2.0").to_spdx_id(), "MPL-2.0");
+        assert_eq!(License::from("BSD-2-Clause").to_spdx_id(), "BSD-2-Clause");
+        assert_eq!(License::CC0.to_spdx_id(), "CC0-1.0");
+        assert_eq!(License::ISC.to_spdx_id(), "ISC");
+        assert_eq!(License::from("Unlicense").to_spdx_id(), "Unlicense");
+        assert_eq!(License::Unknown.to_spdx_id(), "NOASSERTION");
+
+        // Round trip
+        for id in [
+            "Apache-2.0",
+            "GPL-3.0-only",
+            "MPL-2.0",
+            "BSD-3-Clause",
+            "MIT",
+        ] {
+            assert_eq!(License::from(id).to_spdx_id(), id);
+        }
+    }
+
+    #[test]
+    fn test_is_denied_by() {
+        let gpl3 = License::from("GPL-3.0");
+        assert!(License::from("GPL-3.0").is_denied_by(&gpl3));
+        assert!(License::from("GPL-3.0-only").is_denied_by(&gpl3));
+        assert!(License::from("GPL-3.0-or-later").is_denied_by(&gpl3));
+        assert!(License::from("GPL-3.0+").is_denied_by(&gpl3));
+        assert!(!License::from("GPL-2.0-or-later").is_denied_by(&gpl3));
+        assert!(!License::from("LGPL-3.0-only").is_denied_by(&gpl3));
+
+        let later = License::from("GPL-3.0-or-later");
+        assert!(License::from("GPL-3.0-or-later").is_denied_by(&later));
+        assert!(!License::from("GPL-3.0-only").is_denied_by(&later));
+
+        assert!(License::MIT.is_denied_by(&License::MIT));
+        assert!(!License::Unknown.is_denied_by(&License::Unknown));
+
+        assert!(License::MIT.is_spdx());
+        assert!(!License::from("apache").is_spdx());
+        assert!(!License::from("Proprietary").is_spdx());
+    }
+
+    #[test]
+    fn test_license_serde() {
+        let licenses = vec![
+            License::Apache(String::from("2.0")),
+            License::MIT,
+            License::Unknown,
+            License::GPL(String::from("3.0")),
+            License::GPL(String::from("2.0-or-later")),
+            License::LGPL(String::from("2.1")),
+            License::AGPL(String::from("3.0-or-later")),
+            License::BSD(String::from("3-clause")),
+            License::Apache(String::from("apache")),
+            License::Apache(String::new()),
+            License::GPL(String::from("gpl")),
+            License::Custom(String::from("Proprietary")),
+        ];
+        let json = serde_json::to_string(&licenses).unwrap();
+        assert_eq!(
+            json,
+            r#"["Apache-2.0","MIT","NOASSERTION","GPL-3.0-only","GPL-2.0-or-later","LGPL-2.1-only","AGPL-3.0-or-later","BSD-3-Clause","Apache","Apache-","GPL","Proprietary"]"#
+        );
+
+        let parsed: Vec<License> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, licenses);
+
+        // The GNU `-only` and `+` suffixes are normalised
+        assert_eq!(
+            License::from("GPL-3.0-only"),
+            License::GPL(String::from("3.0"))
+        );
+        assert_eq!(
+            License::from("LGPL-2.1+"),
+            License::LGPL(String::from("2.1-or-later"))
+        );
+
+        assert_eq!(License::from("CC0-1.0"), License::CC0);
+        assert_eq!(
+            License::GPL(String::from("3.0")).to_string(),
+            "GPL-3.0-only"
+        );
+    }
 }
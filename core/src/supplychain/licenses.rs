@@ -1,6 +1,11 @@
+use std::fmt::Display;
+
 use serde::{Deserialize, Serialize};
 
-use crate::supplychain::License;
+use crate::{
+    supplychain::{spdx::Expr, License},
+    GHASError,
+};
 
 /// List of Licenses for a dependency
 ///
@@ -15,9 +20,12 @@ use crate::supplychain::License;
 /// // Do some with the licenses
 ///
 /// ```
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Licenses {
     licenses: Vec<License>,
+    /// SPDX expression the licenses were parsed from (if any)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expression: Option<Expr>,
 }
 
 impl Licenses {
@@ -25,14 +33,54 @@ impl Licenses {
     pub fn new() -> Self {
         Self {
             licenses: Vec::new(),
+            expression: None,
         }
     }
 
-    /// Push a new license to the list
+    /// Push a new license to the list.
+    ///
+    /// If the licenses came from an SPDX expression, the license is added to it with
+    /// `AND` (the license applies as well as the expression).
     pub fn push(&mut self, license: License) {
+        if let Some(expression) = self.expression.take() {
+            self.expression = Some(Expr::And(
+                Box::new(expression),
+                Box::new(Expr::License(license.clone())),
+            ));
+        }
         self.licenses.push(license);
     }
 
+    /// Merge another list of licenses into this list, skipping any duplicate licenses.
+    ///
+    /// If either list came from an SPDX expression, the expressions are combined
+    /// with `AND`.
+    pub fn merge(&mut self, other: Licenses) {
+        if self.expression.is_some() || other.expression.is_some() {
+            self.expression = match (self.to_expr(), other.to_expr()) {
+                (Some(left), Some(right)) => Some(Expr::And(Box::new(left), Box::new(right))),
+                (left, right) => left.or(right),
+            };
+        }
+        for license in other.licenses {
+            if !self.licenses.contains(&license) {
+                self.licenses.push(license);
+            }
+        }
+    }
+
+    /// Get the licenses as an expression (all the licenses apply if they didn't
+    /// come from an SPDX expression)
+    fn to_expr(&self) -> Option<Expr> {
+        if let Some(expression) = &self.expression {
+            return Some(expression.clone());
+        }
+        self.licenses
+            .iter()
+            .map(|l| Expr::License(l.clone()))
+            .reduce(|left, right| Expr::And(Box::new(left), Box::new(right)))
+    }
+
     /// Check if the list of licenses is empty
     pub fn is_empty(&self) -> bool {
         self.licenses.is_empty()
@@ -43,6 +91,11 @@ impl Licenses {
         self.licenses.len()
     }
 
+    /// Iterate over the licenses
+    pub fn iter(&self) -> impl Iterator<Item = &License> {
+        self.licenses.iter()
+    }
+
     /// Check if the list contains a particular license
     pub fn contains(&self, license: &License) -> bool {
         self.licenses.contains(license)
@@ -62,6 +115,58 @@ impl Licenses {
         }
     }
 
+    /// Parse an SPDX License Expression (supports `AND`, `OR`, `WITH` and parentheses)
+    /// into a list of licenses. The operators are kept so `is_compatible_with` can
+    /// reason about `OR` choices.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ghastoolkit::supplychain::{License, Licenses};
+    ///
+    /// let licenses = Licenses::from_spdx_expr("(MIT OR GPL-3.0)").unwrap();
+    /// # assert_eq!(licenses.len(), 2);
+    ///
+    /// let allowed = Licenses::from(vec!["MIT"]);
+    /// assert!(licenses.is_compatible_with(&allowed));
+    /// ```
+    pub fn from_spdx_expr(value: &str) -> Result<Licenses, GHASError> {
+        let expression = Expr::parse(value)?;
+        Ok(Licenses {
+            licenses: expression.licenses(),
+            expression: Some(expression),
+        })
+    }
+
+    /// Get the SPDX expression the licenses were parsed from (including any licenses
+    /// pushed or merged afterwards)
+    pub fn expression(&self) -> Option<&Expr> {
+        self.expression.as_ref()
+    }
+
+    /// Check if the licenses are compatible with a list of allowed licenses.
+    ///
+    /// If the licenses came from an SPDX expression, only one side of an `OR` needs to be
+    /// allowed. Otherwise all the licenses need to be allowed.
+    pub fn is_compatible_with(&self, allowed: &Licenses) -> bool {
+        if let Some(expression) = &self.expression {
+            expression.is_satisfied_by(&allowed.licenses)
+        } else {
+            self.licenses.iter().all(|l| allowed.contains(l))
+        }
+    }
+
+    /// Check if the licenses are denied (see `License::is_denied_by`).
+    ///
+    /// If the licenses came from an SPDX expression, they are only denied if every
+    /// choice of an `OR` includes a denied license. Otherwise any denied license
+    /// denies the licenses.
+    pub fn is_denied_by(&self, denied: &[License]) -> bool {
+        let is_denied = |license: &License| denied.iter().any(|d| license.is_denied_by(d));
+        self.to_expr()
+            .is_some_and(|expression| !expression.avoids(&is_denied))
+    }
+
     fn parse_sep(value: &str, sep: &str) -> Licenses {
         let mut licenses = Licenses::new();
         for license in value.split(sep) {
@@ -71,6 +176,16 @@ impl Licenses {
     }
 }
 
+impl PartialEq for Licenses {
+    /// Licenses are equal if they have the same licenses and the same meaning (a list
+    /// without an SPDX expression is the same as the licenses joined with `AND`)
+    fn eq(&self, other: &Self) -> bool {
+        self.licenses == other.licenses && self.to_expr() == other.to_expr()
+    }
+}
+
+impl Eq for Licenses {}
+
 impl IntoIterator for Licenses {
     type Item = License;
     type IntoIter = std::vec::IntoIter<License>;
@@ -80,6 +195,13 @@ impl IntoIterator for Licenses {
     }
 }
 
+impl Display for Licenses {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let licenses: Vec<String> = self.licenses.iter().map(|l| l.to_string()).collect();
+        write!(f, "{}", licenses.join(", "))
+    }
+}
+
 impl From<&str> for Licenses {
     fn from(value: &str) -> Self {
         Licenses::parse(value)
@@ -112,6 +234,7 @@ mod tests {
 
         let correct = Licenses {
             licenses: vec![License::Apache(String::from("2.0"))],
+            ..Default::default()
         };
 
         assert_eq!(licenses, correct);
@@ -124,6 +247,7 @@ mod tests {
 
         let correct = Licenses {
             licenses: vec![License::Apache(String::from("2.0")), License::MIT],
+            ..Default::default()
         };
 
         assert_eq!(licenses, correct);
@@ -136,6 +260,7 @@ mod tests {
 
         let correct = Licenses {
             licenses: vec![License::Apache(String::from("2.0")), License::MIT],
+            ..Default::default()
         };
 
         assert_eq!(licenses, correct);
@@ -148,8 +273,92 @@ mod tests {
 
         let correct = Licenses {
             licenses: vec![License::Apache(String::from("2.0")), License::MIT],
+            ..Default::default()
         };
 
         assert_eq!(licenses, correct);
     }
+
+    #[test]
+    fn test_licenses_spdx_expr() {
+        let licenses = Licenses::from_spdx_expr("(MIT OR Apache-2.0)").unwrap();
+        assert_eq!(licenses.len(), 2);
+        assert!(licenses.contains(&License::MIT));
+
+        let allowed = Licenses::from(vec!["Apache-2.0"]);
+        assert!(licenses.is_compatible_with(&allowed));
+
+        let licenses = Licenses::from_spdx_expr("MIT AND Apache-2.0").unwrap();
+        assert!(!licenses.is_compatible_with(&allowed));
+    }
+
+    #[test]
+    fn test_licenses_spdx_mutation() {
+        let allowed = Licenses::from(vec!["MIT"]);
+
+        // Pushed licenses are part of the expression
+        let mut licenses = Licenses::from_spdx_expr("MIT OR Apache-2.0").unwrap();
+        assert!(licenses.is_compatible_with(&allowed));
+        licenses.push(License::from("GPL-3.0"));
+        assert_eq!(licenses.len(), 3);
+        assert!(!licenses.is_compatible_with(&allowed));
+        assert_eq!(
+            licenses.expression(),
+            Licenses::from_spdx_expr("(MIT OR Apache-2.0) AND GPL-3.0")
+                .unwrap()
+                .expression()
+        );
+
+        // Merged lists keep the choice of the expression
+        let mut licenses = Licenses::from_spdx_expr("MIT OR Apache-2.0").unwrap();
+        licenses.merge(Licenses::from("MIT"));
+        assert!(licenses.is_compatible_with(&allowed));
+        licenses.merge(Licenses::from_spdx_expr("BSD-3-Clause OR GPL-3.0").unwrap());
+        assert_eq!(licenses.len(), 4);
+        assert!(!licenses.is_compatible_with(&allowed));
+
+        let mut licenses = Licenses::from("MIT");
+        licenses.merge(Licenses::from_spdx_expr("MIT OR Apache-2.0").unwrap());
+        assert!(licenses.is_compatible_with(&allowed));
+
+        // Lists without an expression are compared by their licenses
+        let mut licenses = Licenses::from("MIT");
+        licenses.merge(Licenses::from("Apache-2.0"));
+        assert_eq!(licenses, Licenses::from(vec!["MIT", "Apache-2.0"]));
+        assert!(licenses.expression().is_none());
+        assert_eq!(
+            licenses,
+            Licenses::from_spdx_expr("MIT AND Apache-2.0").unwrap()
+        );
+        assert_ne!(
+            licenses,
+            Licenses::from_spdx_expr("MIT OR Apache-2.0").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_licenses_denied() {
+        let denied = [License::from("GPL-3.0")];
+
+        assert!(Licenses::from("GPL-3.0-or-later").is_denied_by(&denied));
+        assert!(Licenses::from("MIT, GPL-3.0").is_denied_by(&denied));
+        assert!(!Licenses::from("MIT").is_denied_by(&denied));
+        assert!(!Licenses::new().is_denied_by(&denied));
+
+        // Only one side of an `OR` needs to be allowed
+        let licenses = Licenses::from_spdx_expr("GPL-3.0 OR MIT").unwrap();
+        assert!(!licenses.is_denied_by(&denied));
+        let licenses = Licenses::from_spdx_expr("GPL-3.0-or-later AND MIT").unwrap();
+        assert!(licenses.is_denied_by(&denied));
+        let licenses = Licenses::from_spdx_expr("(GPL-3.0-only OR MIT) AND GPL-3.0+").unwrap();
+        assert!(licenses.is_denied_by(&denied));
+    }
+
+    #[test]
+    fn test_licenses_spdx_with() {
+        let licenses =
+            Licenses::from_spdx_expr("GPL-2.0-or-later WITH Classpath-exception-2.0").unwrap();
+        assert_eq!(licenses.len(), 1);
+        assert!(licenses.contains(&License::GPL(String::from("2.0-or-later"))));
+    }
 }
@@ -0,0 +1,693 @@
+//! Lockfile parsers (Cargo, npm and yarn) used to build the dependencies of a project
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use purl::GenericPurl;
+use serde::Deserialize;
+
+use crate::{
+    supplychain::{DependencyRelationship, DependencyScope},
+    Dependencies, Dependency, GHASError,
+};
+
+/// Cargo lockfile (`Cargo.lock`)
+#[derive(Debug, Clone, Deserialize)]
+struct CargoLock {
+    #[serde(default, rename = "package")]
+    packages: Vec<CargoLockPackage>,
+}
+
+/// Cargo lockfile package (`[[package]]`)
+#[derive(Debug, Clone, Deserialize)]
+struct CargoLockPackage {
+    name: String,
+    version: String,
+    /// Source of the package (`None` for the local / workspace packages)
+    #[serde(default)]
+    source: Option<String>,
+    /// Dependencies as `name`, `name version` or `name version (source)`
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+impl CargoLockPackage {
+    fn purl(&self) -> String {
+        format!("pkg:cargo/{}@{}", self.name, self.version)
+    }
+}
+
+/// npm lockfile (`package-lock.json`, v2 / v3)
+#[derive(Debug, Clone, Deserialize)]
+struct NpmLock {
+    #[serde(default)]
+    packages: HashMap<String, NpmLockPackage>,
+}
+
+/// npm lockfile package (entry of the `packages` map)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NpmLockPackage {
+    /// Name of the package (only set when it differs from the path, e.g. aliases)
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    dev: bool,
+    /// Optional dependency of a development dependency (npm v7+)
+    #[serde(default)]
+    dev_optional: bool,
+    /// Symlink to a local package (workspaces)
+    #[serde(default)]
+    link: bool,
+    #[serde(default)]
+    dependencies: HashMap<String, String>,
+    #[serde(default)]
+    dev_dependencies: HashMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: HashMap<String, String>,
+    #[serde(default)]
+    peer_dependencies: HashMap<String, String>,
+}
+
+impl NpmLockPackage {
+    /// Names of all the dependencies of the package
+    fn dependency_names(&self) -> impl Iterator<Item = &String> {
+        self.dependencies
+            .keys()
+            .chain(self.dev_dependencies.keys())
+            .chain(self.optional_dependencies.keys())
+            .chain(self.peer_dependencies.keys())
+    }
+}
+
+/// npm `package.json` manifest (used to find the direct dependencies of a `yarn.lock`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NpmManifest {
+    #[serde(default)]
+    dependencies: HashMap<String, String>,
+    #[serde(default)]
+    dev_dependencies: HashMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: HashMap<String, String>,
+}
+
+/// Classic yarn lockfile (v1) entry
+#[derive(Debug, Clone, Default)]
+struct YarnLockEntry {
+    name: String,
+    version: String,
+    /// Dependencies as `(name, range)`
+    dependencies: Vec<(String, String)>,
+}
+
+/// Create a npm dependency, handling scoped packages (`@scope/name`)
+fn npm_dependency(name: &str, version: &str) -> Dependency {
+    let (namespace, package) = match name.split_once('/') {
+        Some((scope, package)) if scope.starts_with('@') => (Some(scope), package),
+        _ => (None, name),
+    };
+    let mut builder = GenericPurl::<String>::builder(String::from("npm"), package);
+    if let Some(namespace) = namespace {
+        builder = builder.with_namespace(namespace);
+    }
+    match builder.with_version(version).build() {
+        Ok(purl) => Dependency::from(purl),
+        Err(_) => {
+            let mut dependency = Dependency::new();
+            dependency.manager = String::from("npm");
+            dependency.name = package.to_string();
+            dependency.namespace = namespace.map(|n| n.to_string());
+            dependency.version = Some(version.to_string());
+            dependency
+        }
+    }
+}
+
+/// Get the package name from a npm lockfile path (`node_modules/a/node_modules/@b/c`)
+fn npm_package_name(path: &str) -> &str {
+    match path.rfind("node_modules/") {
+        Some(index) => &path[index + "node_modules/".len()..],
+        None => path,
+    }
+}
+
+/// Resolve a npm dependency from a package path using the Node.js resolution
+/// (nested `node_modules` first, then the parent directories)
+fn npm_resolve(
+    packages: &HashMap<String, NpmLockPackage>,
+    from: &str,
+    name: &str,
+) -> Option<String> {
+    let mut base = from.to_string();
+    loop {
+        let candidate = if base.is_empty() {
+            format!("node_modules/{}", name)
+        } else {
+            format!("{}/node_modules/{}", base, name)
+        };
+        if packages.contains_key(&candidate) {
+            return Some(candidate);
+        }
+        if base.is_empty() {
+            return None;
+        }
+        base = match base.rfind("/node_modules/") {
+            Some(index) => base[..index].to_string(),
+            None => String::new(),
+        };
+    }
+}
+
+/// Split a yarn specifier (`@scope/name@^1.0.0`) into name and range
+fn yarn_split_specifier(specifier: &str) -> Option<(&str, &str)> {
+    let index = specifier.get(1..)?.find('@')? + 1;
+    Some((&specifier[..index], &specifier[index + 1..]))
+}
+
+/// Parse a classic (v1) yarn lockfile, returning the entries and the index of
+/// specifiers (`name@range`) to entries
+fn parse_yarn_lock(content: &str) -> (Vec<YarnLockEntry>, HashMap<String, usize>) {
+    let mut entries: Vec<YarnLockEntry> = Vec::new();
+    let mut specifiers: HashMap<String, usize> = HashMap::new();
+    let mut in_dependencies = false;
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let line = line.trim();
+
+        if indent == 0 {
+            // Entry header: `"a@^1.0.0", a@^1.1.0:`
+            in_dependencies = false;
+            let header = line.trim_end_matches(':');
+            let mut name = String::new();
+            for specifier in header.split(',') {
+                let specifier = specifier.trim().trim_matches('"');
+                if let Some((n, _)) = yarn_split_specifier(specifier) {
+                    name = n.to_string();
+                }
+                specifiers.insert(specifier.to_string(), entries.len());
+            }
+            entries.push(YarnLockEntry {
+                name,
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let Some(entry) = entries.last_mut() else {
+            continue;
+        };
+        if indent <= 2 {
+            in_dependencies = line == "dependencies:" || line == "optionalDependencies:";
+            if let Some(version) = line.strip_prefix("version ") {
+                entry.version = version.trim_matches('"').to_string();
+            }
+        } else if in_dependencies {
+            if let Some((name, range)) = line.split_once(' ') {
+                entry.dependencies.push((
+                    name.trim_matches('"').to_string(),
+                    range.trim().trim_matches('"').to_string(),
+                ));
+            }
+        }
+    }
+
+    (entries, specifiers)
+}
+
+impl Dependencies {
+    /// Load the dependencies from a Cargo lockfile (`Cargo.lock`).
+    ///
+    /// Local packages (workspace members, without a source) are the project itself and
+    /// are not included, their dependencies are direct and everything else is indirect.
+    /// The dependencies of each package are recorded as PURLs. Licenses are not part
+    /// of the lockfile and are left empty.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ghastoolkit::Dependencies;
+    ///
+    /// let dependencies = Dependencies::from_cargo_lock("Cargo.lock")
+    ///     .expect("Failed to parse Cargo.lock");
+    /// println!("Dependencies: {}", dependencies.len());
+    /// ```
+    pub fn from_cargo_lock(path: impl AsRef<Path>) -> Result<Dependencies, GHASError> {
+        let path = path.as_ref();
+        log::debug!("Loading Cargo lockfile: {}", path.display());
+        let content = std::fs::read_to_string(path)?;
+        Self::parse_cargo_lock(&content)
+    }
+
+    /// Load the dependencies from a npm (`package-lock.json`, v2 / v3) or classic yarn
+    /// (`yarn.lock`) lockfile.
+    ///
+    /// Scoped packages (`@scope/name`) use the scope as the PURL namespace. The
+    /// dependencies only used for development are marked with the development scope.
+    /// For `yarn.lock`, the direct and development dependencies come from the
+    /// `package.json` next to the lockfile (if present).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ghastoolkit::Dependencies;
+    ///
+    /// let dependencies = Dependencies::from_npm_lock("package-lock.json")
+    ///     .expect("Failed to parse package-lock.json");
+    /// println!("Dependencies: {}", dependencies.len());
+    /// ```
+    pub fn from_npm_lock(path: impl AsRef<Path>) -> Result<Dependencies, GHASError> {
+        let path = path.as_ref();
+        log::debug!("Loading npm lockfile: {}", path.display());
+        let content = std::fs::read_to_string(path)?;
+
+        if path.file_name().map(|f| f == "yarn.lock").unwrap_or(false) {
+            let manifest_path = path.with_file_name("package.json");
+            let manifest = if manifest_path.exists() {
+                Some(std::fs::read_to_string(manifest_path)?)
+            } else {
+                None
+            };
+            Self::parse_yarn_lock(&content, manifest.as_deref())
+        } else {
+            Self::parse_npm_lock(&content)
+        }
+    }
+
+    /// Parse the content of a npm lockfile (`package-lock.json`, v2 / v3)
+    pub(crate) fn parse_npm_lock(content: &str) -> Result<Dependencies, GHASError> {
+        let lock: NpmLock = serde_json::from_str(content)?;
+        if lock.packages.is_empty() {
+            return Err(GHASError::Unsupported(String::from(
+                "npm lockfile has no `packages` (only lockfile v2 and v3 are supported)",
+            )));
+        }
+
+        let root = lock.packages.get("").cloned().unwrap_or_default();
+        let direct: HashSet<String> = root
+            .dependency_names()
+            .filter_map(|name| npm_resolve(&lock.packages, "", name))
+            .collect();
+
+        let mut paths: Vec<&String> = lock.packages.keys().filter(|p| !p.is_empty()).collect();
+        paths.sort();
+
+        Ok(paths
+            .into_iter()
+            .filter_map(|path| {
+                let package = lock.packages.get(path)?;
+                if package.link || !path.contains("node_modules/") {
+                    return None;
+                }
+                let name = package
+                    .name
+                    .as_deref()
+                    .unwrap_or_else(|| npm_package_name(path));
+                let mut dependency = npm_dependency(name, package.version.as_deref()?);
+
+                dependency.dependencies = package
+                    .dependencies
+                    .keys()
+                    .chain(package.optional_dependencies.keys())
+                    .filter_map(|d| npm_resolve(&lock.packages, path, d))
+                    .filter_map(|d| {
+                        let resolved = lock.packages.get(&d)?;
+                        let name = resolved
+                            .name
+                            .as_deref()
+                            .unwrap_or_else(|| npm_package_name(&d));
+                        Some(npm_dependency(name, resolved.version.as_deref()?).purl())
+                    })
+                    .collect();
+                dependency.dependencies.sort();
+
+                if !direct.contains(path) {
+                    dependency.relationship = DependencyRelationship::Indirect;
+                }
+                if package.dev || package.dev_optional {
+                    dependency.scope = DependencyScope::Development;
+                }
+                Some(dependency)
+            })
+            .collect())
+    }
+
+    /// Parse the content of a classic (v1) yarn lockfile with an optional `package.json`
+    pub(crate) fn parse_yarn_lock(
+        content: &str,
+        manifest: Option<&str>,
+    ) -> Result<Dependencies, GHASError> {
+        let (entries, specifiers) = parse_yarn_lock(content);
+        let manifest: Option<NpmManifest> = manifest.map(serde_json::from_str).transpose()?;
+
+        let resolve = |name: &str, range: &str| -> Option<usize> {
+            specifiers.get(&format!("{}@{}", name, range)).copied()
+        };
+
+        // Walk the dependency graph from the direct dependencies
+        let reachable = |roots: &HashMap<String, String>| -> HashSet<usize> {
+            let mut seen: HashSet<usize> = HashSet::new();
+            let mut queue: Vec<usize> = roots
+                .iter()
+                .filter_map(|(name, range)| resolve(name, range))
+                .collect();
+            while let Some(index) = queue.pop() {
+                if seen.insert(index) {
+                    queue.extend(
+                        entries[index]
+                            .dependencies
+                            .iter()
+                            .filter_map(|(name, range)| resolve(name, range)),
+                    );
+                }
+            }
+            seen
+        };
+
+        let (direct, runtime) = match &manifest {
+            Some(manifest) => {
+                let mut runtime_roots = manifest.dependencies.clone();
+                runtime_roots.extend(manifest.optional_dependencies.clone());
+                let mut all_roots = runtime_roots.clone();
+                all_roots.extend(manifest.dev_dependencies.clone());
+
+                let direct: HashSet<usize> = all_roots
+                    .iter()
+                    .filter_map(|(name, range)| resolve(name, range))
+                    .collect();
+                (Some(direct), Some(reachable(&runtime_roots)))
+            }
+            None => (None, None),
+        };
+
+        Ok(entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| !entry.name.is_empty() && !entry.version.is_empty())
+            .map(|(index, entry)| {
+                let mut dependency = npm_dependency(&entry.name, &entry.version);
+                dependency.dependencies = entry
+                    .dependencies
+                    .iter()
+                    .filter_map(|(name, range)| resolve(name, range))
+                    .map(|i| npm_dependency(&entries[i].name, &entries[i].version).purl())
+                    .collect();
+
+                if let Some(direct) = &direct {
+                    if !direct.contains(&index) {
+                        dependency.relationship = DependencyRelationship::Indirect;
+                    }
+                }
+                if let Some(runtime) = &runtime {
+                    if !runtime.contains(&index) {
+                        dependency.scope = DependencyScope::Development;
+                    }
+                }
+                dependency
+            })
+            .collect())
+    }
+
+    /// Parse the content of a Cargo lockfile
+    pub(crate) fn parse_cargo_lock(content: &str) -> Result<Dependencies, GHASError> {
+        let lock: CargoLock = toml::from_str(content)?;
+
+        // Index of name -> packages (a crate can be locked at multiple versions)
+        let mut index: HashMap<&str, Vec<&CargoLockPackage>> = HashMap::new();
+        for package in &lock.packages {
+            index
+                .entry(package.name.as_str())
+                .or_default()
+                .push(package);
+        }
+
+        let resolve = |entry: &str| -> Option<&CargoLockPackage> {
+            let mut parts = entry.split_whitespace();
+            let name = parts.next()?;
+            let version = parts.next();
+            let candidates = index.get(name)?;
+            match version {
+                Some(version) => candidates.iter().find(|p| p.version == version).copied(),
+                None => candidates.first().copied(),
+            }
+        };
+
+        let mut direct: Vec<String> = Vec::new();
+        for package in lock.packages.iter().filter(|p| p.source.is_none()) {
+            direct.extend(
+                package
+                    .dependencies
+                    .iter()
+                    .filter_map(|d| resolve(d))
+                    .map(|d| d.purl()),
+            );
+        }
+
+        Ok(lock
+            .packages
+            .iter()
+            .filter(|p| p.source.is_some())
+            .map(|package| {
+                let mut dependency = Dependency::from(package.purl());
+                dependency.dependencies = package
+                    .dependencies
+                    .iter()
+                    .filter_map(|d| resolve(d))
+                    .map(|d| d.purl())
+                    .collect();
+                if !direct.contains(&package.purl()) {
+                    dependency.relationship = DependencyRelationship::Indirect;
+                }
+                dependency
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        supplychain::{DependencyRelationship, DependencyScope},
+        Dependencies,
+    };
+
+    const CARGO_LOCK: &str = r#"# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 4
+
+[[package]]
+name = "ghastoolkit"
+version = "0.5.1"
+dependencies = [
+ "serde",
+ "syn 2.0.60",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "ddc6f9cc94d67c0e21aaf7eda3a010fd3af78ebf6e096aa6e2e13c79749cce4f"
+dependencies = [
+ "serde_derive",
+]
+
+[[package]]
+name = "serde_derive"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "syn 1.0.109",
+]
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.60"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+    #[test]
+    fn test_cargo_lock() {
+        let dependencies = Dependencies::parse_cargo_lock(CARGO_LOCK).unwrap();
+        // The local package is not a dependency
+        assert_eq!(dependencies.len(), 4);
+        assert!(dependencies.find_by_name("ghastoolkit").is_none());
+
+        let serde = dependencies.find_by_name("serde").unwrap();
+        assert_eq!(serde.purl(), "pkg:cargo/serde@1.0.200");
+        assert_eq!(serde.relationship, DependencyRelationship::Direct);
+        assert_eq!(serde.dependencies, vec!["pkg:cargo/serde_derive@1.0.200"]);
+        assert!(serde.licenses.is_empty());
+
+        let derive = dependencies.find_by_name("serde_derive").unwrap();
+        assert_eq!(derive.relationship, DependencyRelationship::Indirect);
+        assert_eq!(derive.dependencies, vec!["pkg:cargo/syn@1.0.109"]);
+
+        // Multiple versions of the same crate
+        let syn: Vec<_> = dependencies.iter().filter(|d| d.name == "syn").collect();
+        assert_eq!(syn.len(), 2);
+        let syn2 = syn
+            .iter()
+            .find(|d| d.version.as_deref() == Some("2.0.60"))
+            .unwrap();
+        assert_eq!(syn2.relationship, DependencyRelationship::Direct);
+        let syn1 = syn
+            .iter()
+            .find(|d| d.version.as_deref() == Some("1.0.109"))
+            .unwrap();
+        assert_eq!(syn1.relationship, DependencyRelationship::Indirect);
+    }
+
+    #[test]
+    fn test_cargo_lock_workspace() {
+        // The toolkit can describe itself
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../Cargo.lock");
+        let dependencies = Dependencies::from_cargo_lock(path).unwrap();
+        assert!(dependencies.find_by_name("serde").is_some());
+        assert!(dependencies.find_by_name("ghastoolkit").is_none());
+    }
+
+    const PACKAGE_LOCK: &str = r#"{
+  "name": "example",
+  "version": "1.0.0",
+  "lockfileVersion": 3,
+  "requires": true,
+  "packages": {
+    "": {
+      "name": "example",
+      "version": "1.0.0",
+      "dependencies": { "@octokit/rest": "^20.0.0", "debug": "^4.3.0" },
+      "devDependencies": { "jest": "^29.0.0" }
+    },
+    "node_modules/@octokit/rest": { "version": "20.1.0" },
+    "node_modules/debug": {
+      "version": "4.3.4",
+      "dependencies": { "ms": "2.1.2" }
+    },
+    "node_modules/ms": { "version": "2.1.3" },
+    "node_modules/debug/node_modules/ms": { "version": "2.1.2" },
+    "node_modules/jest": {
+      "version": "29.7.0",
+      "dev": true,
+      "dependencies": { "ms": "^2.1.3" },
+      "optionalDependencies": { "fsevents": "^2.3.2" }
+    },
+    "node_modules/fsevents": {
+      "version": "2.3.3",
+      "devOptional": true,
+      "optional": true
+    },
+    "packages/local": { "version": "0.0.1" },
+    "node_modules/local": { "resolved": "packages/local", "link": true }
+  }
+}"#;
+
+    #[test]
+    fn test_npm_lock() {
+        let dependencies = Dependencies::parse_npm_lock(PACKAGE_LOCK).unwrap();
+        assert_eq!(dependencies.len(), 6);
+
+        let octokit = dependencies.find_by_name("rest").unwrap();
+        assert_eq!(octokit.namespace.as_deref(), Some("@octokit"));
+        assert_eq!(octokit.purl(), "pkg:npm/%40octokit/rest@20.1.0");
+        assert_eq!(octokit.relationship, DependencyRelationship::Direct);
+        assert_eq!(octokit.scope, DependencyScope::Runtime);
+
+        // Nested dependencies are resolved from the closest node_modules
+        let debug = dependencies.find_by_name("debug").unwrap();
+        assert_eq!(debug.dependencies, vec!["pkg:npm/ms@2.1.2"]);
+        let jest = dependencies.find_by_name("jest").unwrap();
+        assert_eq!(jest.relationship, DependencyRelationship::Direct);
+        assert_eq!(jest.scope, DependencyScope::Development);
+        assert_eq!(
+            jest.dependencies,
+            vec!["pkg:npm/fsevents@2.3.3", "pkg:npm/ms@2.1.3"]
+        );
+
+        // Optional dependencies of development dependencies are development
+        let fsevents = dependencies.find_by_name("fsevents").unwrap();
+        assert_eq!(fsevents.relationship, DependencyRelationship::Indirect);
+        assert_eq!(fsevents.scope, DependencyScope::Development);
+
+        let ms: Vec<_> = dependencies.iter().filter(|d| d.name == "ms").collect();
+        assert_eq!(ms.len(), 2);
+        assert!(ms
+            .iter()
+            .all(|d| d.relationship == DependencyRelationship::Indirect));
+
+        // Old lockfiles (v1) are not supported
+        assert!(Dependencies::parse_npm_lock(r#"{"lockfileVersion": 1}"#).is_err());
+    }
+
+    const YARN_LOCK: &str = r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@octokit/rest@^20.0.0":
+  version "20.1.0"
+  resolved "https://registry.yarnpkg.com/@octokit/rest/-/rest-20.1.0.tgz"
+
+debug@^4.3.0:
+  version "4.3.4"
+  resolved "https://registry.yarnpkg.com/debug/-/debug-4.3.4.tgz"
+  dependencies:
+    ms "2.1.2"
+
+jest@^29.0.0:
+  version "29.7.0"
+  dependencies:
+    "@jest/core" "^29.7.0"
+
+"@jest/core@^29.7.0":
+  version "29.7.0"
+
+ms@2.1.2, ms@^2.1.1:
+  version "2.1.2"
+"#;
+
+    #[test]
+    fn test_yarn_lock() {
+        let manifest = r#"{
+  "name": "example",
+  "dependencies": { "@octokit/rest": "^20.0.0", "debug": "^4.3.0" },
+  "devDependencies": { "jest": "^29.0.0" }
+}"#;
+        let dependencies = Dependencies::parse_yarn_lock(YARN_LOCK, Some(manifest)).unwrap();
+        assert_eq!(dependencies.len(), 5);
+
+        let octokit = dependencies.find_by_name("rest").unwrap();
+        assert_eq!(octokit.purl(), "pkg:npm/%40octokit/rest@20.1.0");
+        assert_eq!(octokit.relationship, DependencyRelationship::Direct);
+
+        let debug = dependencies.find_by_name("debug").unwrap();
+        assert_eq!(debug.dependencies, vec!["pkg:npm/ms@2.1.2"]);
+
+        let ms = dependencies.find_by_name("ms").unwrap();
+        assert_eq!(ms.relationship, DependencyRelationship::Indirect);
+        assert_eq!(ms.scope, DependencyScope::Runtime);
+
+        // Only used by a development dependency
+        let jest_core = dependencies.find_by_name("core").unwrap();
+        assert_eq!(jest_core.namespace.as_deref(), Some("@jest"));
+        assert_eq!(jest_core.relationship, DependencyRelationship::Indirect);
+        assert_eq!(jest_core.scope, DependencyScope::Development);
+
+        // Without a package.json the defaults are used
+        let dependencies = Dependencies::parse_yarn_lock(YARN_LOCK, None).unwrap();
+        let jest = dependencies.find_by_name("jest").unwrap();
+        assert_eq!(jest.relationship, DependencyRelationship::Direct);
+        assert_eq!(jest.scope, DependencyScope::Runtime);
+    }
+}
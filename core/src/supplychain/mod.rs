@@ -10,8 +10,17 @@ pub mod dependency;
 pub mod license;
 /// This module contains the licenses
 pub mod licenses;
+/// This module contains the lockfile parsers
+pub mod lockfiles;
+/// This module contains the GitHub Dependency Graph SBOM models
+pub mod sbom;
+/// This module contains the SPDX license expression parser
+pub mod spdx;
 
 pub use dependencies::Dependencies;
-pub use dependency::Dependency;
+pub use dependency::{Dependency, DependencyRelationship, DependencyScope};
 pub use license::License;
 pub use licenses::Licenses;
+
+/// SPDX value for an unknown license
+pub(crate) const NOASSERTION: &str = "NOASSERTION";
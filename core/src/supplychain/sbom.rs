@@ -0,0 +1,358 @@
+//! GitHub Dependency Graph SBOM (SPDX)
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use purl::GenericPurl;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    supplychain::{DependencyRelationship, DependencyScope, Licenses, NOASSERTION},
+    Dependencies, Dependency,
+};
+
+/// SBOM exported from the GitHub Dependency Graph
+///
+/// https://docs.github.com/en/rest/dependency-graph/sboms?apiVersion=2022-11-28
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyGraphSbom {
+    /// SPDX document
+    pub sbom: SpdxDocument,
+}
+
+/// SPDX (JSON) document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpdxDocument {
+    /// SPDX version
+    #[serde(rename = "spdxVersion")]
+    pub spdx_version: String,
+    /// Document name
+    pub name: String,
+    /// Packages
+    #[serde(default)]
+    pub packages: Vec<SpdxPackage>,
+    /// Other fields of the document (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
+}
+
+/// SPDX Package
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpdxPackage {
+    /// Package name
+    pub name: String,
+    /// Package version
+    #[serde(
+        rename = "versionInfo",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub version_info: Option<String>,
+    /// Concluded license (SPDX expression)
+    #[serde(
+        rename = "licenseConcluded",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub license_concluded: Option<String>,
+    /// Declared license (SPDX expression)
+    #[serde(
+        rename = "licenseDeclared",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub license_declared: Option<String>,
+    /// External references (PURLs)
+    #[serde(rename = "externalRefs", default)]
+    pub external_refs: Vec<SpdxExternalRef>,
+    /// Other fields of the package (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
+}
+
+/// SPDX Package External Reference
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpdxExternalRef {
+    /// Reference category (e.g. `PACKAGE-MANAGER`)
+    #[serde(rename = "referenceCategory")]
+    pub reference_category: String,
+    /// Reference type (e.g. `purl`)
+    #[serde(rename = "referenceType")]
+    pub reference_type: String,
+    /// Reference locator (e.g. `pkg:cargo/serde@1.0.0`)
+    #[serde(rename = "referenceLocator")]
+    pub reference_locator: String,
+}
+
+impl SpdxPackage {
+    /// Get the SPDX identifier of the package (if any)
+    pub fn spdx_id(&self) -> Option<&str> {
+        self.additional_properties
+            .get("SPDXID")
+            .and_then(|id| id.as_str())
+    }
+
+    /// Get the PURL of the package (if any)
+    pub fn purl(&self) -> Option<&str> {
+        self.external_refs
+            .iter()
+            .find(|r| r.reference_type == "purl")
+            .map(|r| r.reference_locator.as_str())
+    }
+
+    /// Get the license expression of the package (concluded, else declared)
+    pub fn license_expression(&self) -> Option<&str> {
+        [&self.license_concluded, &self.license_declared]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .find(|l| !l.is_empty() && *l != NOASSERTION)
+    }
+
+    /// Get the licenses of the package (concluded, else declared)
+    pub fn licenses(&self) -> Licenses {
+        self.license_expression()
+            .map(|l| Licenses::from_spdx_expr(l).unwrap_or_else(|_| Licenses::parse(l)))
+            .unwrap_or_default()
+    }
+}
+
+/// SPDX Relationship between two elements
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpdxRelationship {
+    /// Element the relationship is from
+    #[serde(rename = "spdxElementId")]
+    pub spdx_element_id: String,
+    /// Relationship type (e.g. `DEPENDS_ON`)
+    #[serde(rename = "relationshipType")]
+    pub relationship_type: String,
+    /// Element the relationship is to
+    #[serde(rename = "relatedSpdxElement")]
+    pub related_spdx_element: String,
+}
+
+impl SpdxDocument {
+    /// Get the relationships of the document
+    pub fn relationships(&self) -> Vec<SpdxRelationship> {
+        self.additional_properties
+            .get("relationships")
+            .and_then(|r| serde_json::from_value(r.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Get the dependencies (packages with a PURL) from the SBOM.
+    ///
+    /// The scope and relationship of the dependencies are set from the SPDX
+    /// relationships (`DEPENDS_ON` from the described package is direct, anything else
+    /// is indirect and `DEV_DEPENDENCY_OF` is development). Without any relationships,
+    /// the defaults (runtime and direct) are used.
+    ///
+    /// The dependencies of each package (`Dependency::dependencies`) are the PURLs of the
+    /// packages it depends on in the SPDX relationships.
+    pub fn dependencies(&self) -> Dependencies {
+        let relationships = self.relationships();
+
+        let mut roots: HashSet<&str> = relationships
+            .iter()
+            .filter(|r| r.relationship_type == "DESCRIBES")
+            .map(|r| r.related_spdx_element.as_str())
+            .collect();
+        if let Some(described) = self
+            .additional_properties
+            .get("documentDescribes")
+            .and_then(|d| d.as_array())
+        {
+            roots.extend(described.iter().filter_map(|d| d.as_str()));
+        }
+
+        let packages: Vec<(Option<&str>, Dependency)> = self
+            .packages
+            .iter()
+            .filter_map(|package| {
+                let purl = GenericPurl::<String>::from_str(package.purl()?).ok()?;
+                let mut dependency = Dependency::from(purl);
+                dependency.licenses = package.licenses();
+                Some((package.spdx_id(), dependency))
+            })
+            .collect();
+        let purls: HashMap<&str, String> = packages
+            .iter()
+            .filter_map(|(id, dependency)| Some(((*id)?, dependency.purl())))
+            .collect();
+
+        let mut direct: HashSet<&str> = HashSet::new();
+        let mut development: HashSet<&str> = HashSet::new();
+        // Package SPDX ID -> SPDX IDs of the packages it depends on
+        let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+        for relationship in &relationships {
+            let element = relationship.spdx_element_id.as_str();
+            let related = relationship.related_spdx_element.as_str();
+            match relationship.relationship_type.as_str() {
+                "DEPENDS_ON" => {
+                    if roots.contains(element) {
+                        direct.insert(related);
+                    }
+                    edges.entry(element).or_default().push(related);
+                }
+                "DEPENDENCY_OF" => {
+                    if roots.contains(related) {
+                        direct.insert(element);
+                    }
+                    edges.entry(related).or_default().push(element);
+                }
+                "DEV_DEPENDENCY_OF" => {
+                    development.insert(element);
+                    if roots.contains(related) {
+                        direct.insert(element);
+                    }
+                    edges.entry(related).or_default().push(element);
+                }
+                _ => {}
+            }
+        }
+
+        packages
+            .into_iter()
+            .map(|(id, mut dependency)| {
+                if let Some(id) = id {
+                    if !roots.is_empty() && !direct.contains(id) {
+                        dependency.relationship = DependencyRelationship::Indirect;
+                    }
+                    if development.contains(id) {
+                        dependency.scope = DependencyScope::Development;
+                    }
+                    if let Some(related) = edges.get(id) {
+                        dependency.dependencies = related
+                            .iter()
+                            .filter_map(|r| purls.get(r).cloned())
+                            .collect();
+                        dependency.dependencies.sort();
+                        dependency.dependencies.dedup();
+                    }
+                }
+                dependency
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DependencyGraphSbom;
+    use crate::supplychain::{DependencyRelationship, DependencyScope, License};
+
+    #[test]
+    fn test_sbom_dependencies() {
+        let sbom: DependencyGraphSbom = serde_json::from_str(
+            r#"{
+  "sbom": {
+    "SPDXID": "SPDXRef-DOCUMENT",
+    "spdxVersion": "SPDX-2.3",
+    "name": "com.github.geekmasher/ghastoolkit-rs",
+    "packages": [
+      {
+        "SPDXID": "SPDXRef-cargo-serde",
+        "name": "cargo:serde",
+        "versionInfo": "1.0.200",
+        "licenseConcluded": "MIT OR Apache-2.0",
+        "externalRefs": [
+          { "referenceCategory": "PACKAGE-MANAGER", "referenceType": "purl", "referenceLocator": "pkg:cargo/serde@1.0.200" }
+        ]
+      },
+      {
+        "SPDXID": "SPDXRef-cargo-other",
+        "name": "cargo:other",
+        "licenseConcluded": "NOASSERTION",
+        "licenseDeclared": "GPL-3.0-only",
+        "externalRefs": [
+          { "referenceCategory": "PACKAGE-MANAGER", "referenceType": "purl", "referenceLocator": "pkg:cargo/other@0.1.0" }
+        ]
+      },
+      { "SPDXID": "SPDXRef-repo", "name": "ghastoolkit-rs" }
+    ]
+  }
+}"#,
+        )
+        .unwrap();
+
+        let dependencies = sbom.sbom.dependencies();
+        assert_eq!(dependencies.len(), 2);
+
+        let serde = dependencies.find_by_name("serde").unwrap();
+        assert_eq!(serde.version.as_deref(), Some("1.0.200"));
+        assert!(serde.licenses.contains(&License::MIT));
+
+        let other = dependencies.find_by_name("other").unwrap();
+        assert!(other.licenses.contains(&License::GPL(String::from("3.0"))));
+
+        // No relationships, so the defaults are used
+        assert_eq!(serde.relationship, DependencyRelationship::Direct);
+        assert_eq!(serde.scope, DependencyScope::Runtime);
+
+        // Unknown fields are kept
+        let json = serde_json::to_value(&sbom).unwrap();
+        assert_eq!(json["sbom"]["SPDXID"], "SPDXRef-DOCUMENT");
+    }
+
+    #[test]
+    fn test_sbom_relationships() {
+        let sbom: DependencyGraphSbom = serde_json::from_str(
+            r#"{
+  "sbom": {
+    "SPDXID": "SPDXRef-DOCUMENT",
+    "spdxVersion": "SPDX-2.3",
+    "name": "com.github.geekmasher/ghastoolkit-rs",
+    "packages": [
+      { "SPDXID": "SPDXRef-repo", "name": "ghastoolkit-rs" },
+      {
+        "SPDXID": "SPDXRef-cargo-serde",
+        "name": "cargo:serde",
+        "externalRefs": [
+          { "referenceCategory": "PACKAGE-MANAGER", "referenceType": "purl", "referenceLocator": "pkg:cargo/serde@1.0.200" }
+        ]
+      },
+      {
+        "SPDXID": "SPDXRef-cargo-serde-derive",
+        "name": "cargo:serde_derive",
+        "externalRefs": [
+          { "referenceCategory": "PACKAGE-MANAGER", "referenceType": "purl", "referenceLocator": "pkg:cargo/serde_derive@1.0.200" }
+        ]
+      },
+      {
+        "SPDXID": "SPDXRef-cargo-tempfile",
+        "name": "cargo:tempfile",
+        "externalRefs": [
+          { "referenceCategory": "PACKAGE-MANAGER", "referenceType": "purl", "referenceLocator": "pkg:cargo/tempfile@3.10.0" }
+        ]
+      }
+    ],
+    "relationships": [
+      { "spdxElementId": "SPDXRef-DOCUMENT", "relationshipType": "DESCRIBES", "relatedSpdxElement": "SPDXRef-repo" },
+      { "spdxElementId": "SPDXRef-repo", "relationshipType": "DEPENDS_ON", "relatedSpdxElement": "SPDXRef-cargo-serde" },
+      { "spdxElementId": "SPDXRef-cargo-serde", "relationshipType": "DEPENDS_ON", "relatedSpdxElement": "SPDXRef-cargo-serde-derive" },
+      { "spdxElementId": "SPDXRef-cargo-tempfile", "relationshipType": "DEV_DEPENDENCY_OF", "relatedSpdxElement": "SPDXRef-repo" }
+    ]
+  }
+}"#,
+        )
+        .unwrap();
+
+        let dependencies = sbom.sbom.dependencies();
+        assert_eq!(dependencies.len(), 3);
+
+        let serde = dependencies.find_by_name("serde").unwrap();
+        assert_eq!(serde.relationship, DependencyRelationship::Direct);
+        assert_eq!(serde.scope, DependencyScope::Runtime);
+
+        assert_eq!(serde.dependencies, vec!["pkg:cargo/serde_derive@1.0.200"]);
+
+        let derive = dependencies.find_by_name("serde_derive").unwrap();
+        assert_eq!(derive.relationship, DependencyRelationship::Indirect);
+        assert!(derive.dependencies.is_empty());
+
+        let tempfile = dependencies.find_by_name("tempfile").unwrap();
+        assert_eq!(tempfile.relationship, DependencyRelationship::Direct);
+        assert_eq!(tempfile.scope, DependencyScope::Development);
+    }
+}
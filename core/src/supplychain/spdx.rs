@@ -0,0 +1,244 @@
+//! SPDX License Expression parser
+//!
+//! SPDX License Expressions: https://spdx.github.io/spdx-spec/v2.3/SPDX-license-expressions/
+use serde::{Deserialize, Serialize};
+
+use crate::{supplychain::License, GHASError};
+
+/// SPDX License Expression
+///
+/// # Example
+///
+/// ```rust
+/// use ghastoolkit::supplychain::{spdx::Expr, License};
+///
+/// let expr = Expr::parse("(MIT OR Apache-2.0)").unwrap();
+///
+/// assert_eq!(
+///     expr,
+///     Expr::Or(
+///         Box::new(Expr::License(License::MIT)),
+///         Box::new(Expr::License(License::Apache(String::from("2.0")))),
+///     )
+/// );
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Expr {
+    /// A single license
+    License(License),
+    /// Both licenses apply (`AND`)
+    And(Box<Expr>, Box<Expr>),
+    /// Either license can be chosen (`OR`)
+    Or(Box<Expr>, Box<Expr>),
+    /// License with an exception (`WITH`)
+    With(License, String),
+}
+
+impl Expr {
+    /// Parse an SPDX License Expression.
+    ///
+    /// Operators are case-insensitive and follow the SPDX precedence rules
+    /// (`WITH` binds tighter than `AND` which binds tighter than `OR`).
+    pub fn parse(value: &str) -> Result<Expr, GHASError> {
+        let tokens = tokenize(value);
+        let mut parser = Parser { tokens, pos: 0 };
+
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(GHASError::Parse(format!(
+                "Unexpected token '{}' in SPDX expression: {}",
+                token, value
+            )));
+        }
+        Ok(expr)
+    }
+
+    /// Flatten the expression into a list of licenses (in order of appearance)
+    pub fn licenses(&self) -> Vec<License> {
+        match self {
+            Expr::License(license) | Expr::With(license, _) => vec![license.clone()],
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                let mut licenses = left.licenses();
+                licenses.extend(right.licenses());
+                licenses
+            }
+        }
+    }
+
+    /// Check if the licenses can be chosen without any denied license.
+    /// For `OR` expressions only one of the choices needs to be free of denied licenses.
+    pub fn avoids(&self, denied: &dyn Fn(&License) -> bool) -> bool {
+        match self {
+            Expr::License(license) | Expr::With(license, _) => !denied(license),
+            Expr::And(left, right) => left.avoids(denied) && right.avoids(denied),
+            Expr::Or(left, right) => left.avoids(denied) || right.avoids(denied),
+        }
+    }
+
+    /// Check if the expression is satisfied by the allowed licenses.
+    /// For `OR` expressions only one of the choices needs to be allowed.
+    pub fn is_satisfied_by(&self, allowed: &[License]) -> bool {
+        match self {
+            Expr::License(license) | Expr::With(license, _) => allowed.contains(license),
+            Expr::And(left, right) => {
+                left.is_satisfied_by(allowed) && right.is_satisfied_by(allowed)
+            }
+            Expr::Or(left, right) => {
+                left.is_satisfied_by(allowed) || right.is_satisfied_by(allowed)
+            }
+        }
+    }
+}
+
+/// Split an expression into license ids, operators, and parentheses
+fn tokenize(value: &str) -> Vec<String> {
+    value
+        .replace('(', " ( ")
+        .replace(')', " ) ")
+        .split_whitespace()
+        .map(String::from)
+        .collect()
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(|t| t.as_str())
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn next_is(&self, operator: &str) -> bool {
+        self.peek()
+            .map(|t| t.eq_ignore_ascii_case(operator))
+            .unwrap_or(false)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, GHASError> {
+        let mut left = self.parse_and()?;
+        while self.next_is("or") {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, GHASError> {
+        let mut left = self.parse_with()?;
+        while self.next_is("and") {
+            self.pos += 1;
+            let right = self.parse_with()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_with(&mut self) -> Result<Expr, GHASError> {
+        let expr = self.parse_primary()?;
+        if self.next_is("with") {
+            self.pos += 1;
+            let license = match expr {
+                Expr::License(license) => license,
+                _ => {
+                    return Err(GHASError::Parse(String::from(
+                        "WITH must follow a single license",
+                    )))
+                }
+            };
+            match self.next() {
+                Some(exception) if exception != "(" && exception != ")" => {
+                    Ok(Expr::With(license, exception))
+                }
+                _ => Err(GHASError::Parse(String::from(
+                    "Missing exception after WITH",
+                ))),
+            }
+        } else {
+            Ok(expr)
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, GHASError> {
+        match self.next() {
+            Some(token) if token == "(" => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(token) if token == ")" => Ok(expr),
+                    _ => Err(GHASError::Parse(String::from(
+                        "Missing closing parenthesis in SPDX expression",
+                    ))),
+                }
+            }
+            Some(token)
+                if token == ")"
+                    || ["and", "or", "with"]
+                        .iter()
+                        .any(|op| token.eq_ignore_ascii_case(op)) =>
+            {
+                Err(GHASError::Parse(format!(
+                    "Unexpected token '{}' in SPDX expression",
+                    token
+                )))
+            }
+            Some(token) => Ok(Expr::License(License::from(token.as_str()))),
+            None => Err(GHASError::Parse(String::from(
+                "Unexpected end of SPDX expression",
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Expr;
+    use crate::supplychain::License;
+
+    #[test]
+    fn test_parse_single() {
+        let expr = Expr::parse("MIT").unwrap();
+        assert_eq!(expr, Expr::License(License::MIT));
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let expr = Expr::parse("MIT OR Apache-2.0 AND ISC").unwrap();
+        assert_eq!(
+            expr,
+            Expr::Or(
+                Box::new(Expr::License(License::MIT)),
+                Box::new(Expr::And(
+                    Box::new(Expr::License(License::Apache(String::from("2.0")))),
+                    Box::new(Expr::License(License::ISC)),
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_with() {
+        let expr = Expr::parse("GPL-2.0-or-later WITH Classpath-exception-2.0").unwrap();
+        assert_eq!(
+            expr,
+            Expr::With(
+                License::GPL(String::from("2.0-or-later")),
+                String::from("Classpath-exception-2.0")
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Expr::parse("(MIT OR").is_err());
+        assert!(Expr::parse("MIT AND").is_err());
+        assert!(Expr::parse("").is_err());
+    }
+}
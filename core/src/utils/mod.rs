@@ -4,3 +4,5 @@
 
 /// Module for SARIF related utilities
 pub mod sarif;
+/// Module for the optional `tracing` span helpers
+pub(crate) mod spans;
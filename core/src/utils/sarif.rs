@@ -1,11 +1,15 @@
 use std::{
+    collections::HashMap,
     fmt::{Display, Formatter},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 use serde::{Deserialize, Serialize};
 
-use crate::GHASError;
+use crate::{
+    codescanning::models::{CodeScanningAlert, CodeScanningAlertRule},
+    GHASError,
+};
 
 /// Sarif Structure
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -17,6 +21,9 @@ pub struct Sarif {
     pub version: String,
     /// Runs
     pub runs: Vec<SarifRun>,
+    /// Other properties (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
 }
 
 impl TryFrom<PathBuf> for Sarif {
@@ -45,7 +52,108 @@ impl Sarif {
             schema: String::from("https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json"),
             version: String::from("2.1.0"),
             runs: vec![],
+            additional_properties: HashMap::new(),
+        }
+    }
+
+    /// Create a SARIF (with a single run) from code scanning alerts.
+    ///
+    /// The rules are reconstructed from the alerts and each alert is mapped to a result
+    /// using the location of its most recent instance. The level of a result is the
+    /// rule severity (`warning` if it is not a SARIF level).
+    pub fn from_alerts(alerts: &[CodeScanningAlert], tool_name: &str) -> Sarif {
+        let mut rules: Vec<SarifReportingDescriptor> = Vec::new();
+        let mut results = Vec::new();
+
+        for alert in alerts {
+            let index = match rules.iter().position(|r| r.id == alert.rule.id) {
+                Some(index) => index,
+                None => {
+                    rules.push(SarifReportingDescriptor::from(&alert.rule));
+                    rules.len() - 1
+                }
+            };
+            let level = match alert.rule.severity.as_str() {
+                "error" | "warning" | "note" | "none" => alert.rule.severity.clone(),
+                _ => String::from("warning"),
+            };
+
+            // Alerts without an instance have no location
+            let (message, locations) = match &alert.most_recent_instance {
+                Some(instance) => (
+                    instance.message.text.clone(),
+                    vec![SarifLocation {
+                        id: None,
+                        message: None,
+                        physical_location: SarifPhysicalLocation {
+                            artifact_location: SarifArtifactLocation {
+                                uri: instance.location.path.clone(),
+                                uri_base_id: None,
+                                id: -1,
+                                additional_properties: HashMap::new(),
+                            },
+                            region: SarifRegion {
+                                start_line: instance.location.start_line as i32,
+                                start_column: instance.location.start_column as i32,
+                                end_line: Some(instance.location.end_line as i32),
+                                end_column: Some(instance.location.end_column as i32),
+                                additional_properties: HashMap::new(),
+                            },
+                            additional_properties: HashMap::new(),
+                        },
+                        additional_properties: HashMap::new(),
+                    }],
+                ),
+                None => (alert.rule.description.clone(), Vec::new()),
+            };
+
+            results.push(SarifResult {
+                rule_id: alert.rule.id.clone(),
+                rule_index: index as i32,
+                rule: SarifRule {
+                    id: alert.rule.id.clone(),
+                    index: index as i32,
+                    additional_properties: HashMap::new(),
+                },
+                level,
+                message: SarifMessage::from(message.as_str()),
+                locations,
+                fingerprints: None,
+                partial_fingerprints: None,
+                suppressions: None,
+                related_locations: None,
+                code_flows: None,
+                fixes: None,
+                occurrence_count: None,
+                additional_properties: HashMap::new(),
+            });
         }
+
+        let mut sarif = Sarif::new();
+        sarif.runs.push(SarifRun {
+            tool: SarifTool {
+                driver: SarifToolDriver {
+                    name: tool_name.to_string(),
+                    organization: None,
+                    version: alerts.first().and_then(|a| a.tool.version.clone()),
+                    notifications: None,
+                    rules,
+                    additional_properties: HashMap::new(),
+                },
+                additional_properties: HashMap::new(),
+            },
+            results,
+            original_uri_base_ids: None,
+            automation_details: None,
+            additional_properties: HashMap::new(),
+        });
+        sarif
+    }
+
+    /// Merge the runs of another SARIF into this one. Each run keeps its tool and
+    /// category (`automationDetails`).
+    pub fn merge(&mut self, other: Sarif) {
+        self.runs.extend(other.runs);
     }
 
     /// Get Results from all runs
@@ -57,13 +165,446 @@ impl Sarif {
         results
     }
 
-    /// Write SARIF to file
-    pub fn write(&self, path: PathBuf) -> Result<(), GHASError> {
-        let file = std::fs::File::create(path)?;
-        let writer = std::io::BufWriter::new(file);
-        serde_json::to_writer_pretty(writer, self)?;
-        Ok(())
+    /// Count the result objects in all runs.
+    ///
+    /// A result with an `occurrenceCount` is only counted once, use
+    /// [`Sarif::count_occurrences`] to get the number of findings GitHub displays.
+    pub fn count_results(&self) -> usize {
+        self.runs.iter().map(|run| run.results.len()).sum()
+    }
+
+    /// Count the occurrences of all results in all runs (`occurrenceCount`, or 1 if
+    /// it is not set).
+    pub fn count_occurrences(&self) -> usize {
+        self.runs
+            .iter()
+            .flat_map(|run| run.results.iter())
+            .map(|result| result.occurrence_count.unwrap_or(1) as usize)
+            .sum()
+    }
+
+    /// Get all the results (from all runs) where the primary location is the file `uri`.
+    ///
+    /// The `uriBaseId` of a result is resolved using the run's `originalUriBaseIds` (if
+    /// present). Forward and back slashes, `file://` prefixes, and leading `./` are ignored.
+    pub fn results_for_file(&self, uri: &str) -> Vec<&SarifResult> {
+        let uri = normalize_uri(uri);
+        let mut results = vec![];
+
+        for run in &self.runs {
+            for result in &run.results {
+                let location = match result.locations.first() {
+                    Some(location) => &location.physical_location.artifact_location,
+                    None => continue,
+                };
+                if normalize_uri(&location.uri) == uri
+                    || normalize_uri(&run.resolve_uri(location)) == uri
+                {
+                    results.push(result);
+                }
+            }
+        }
+        results
+    }
+
+    /// Summarise the results by rule (number of results per rule), sorted by count
+    /// (most results first).
+    ///
+    /// Results whose rule can't be found in the tool driver are still counted.
+    pub fn rule_summary(&self) -> Vec<RuleSummary> {
+        let mut summaries: Vec<RuleSummary> = vec![];
+        // Index of the summary for each rule
+        let mut index: HashMap<String, usize> = HashMap::new();
+
+        for run in &self.runs {
+            for result in &run.results {
+                let rule_id = result.resolved_rule_id();
+                if let Some(i) = index.get(rule_id) {
+                    summaries[*i].count += 1;
+                    continue;
+                }
+
+                let rule = result.resolve_rule(run);
+                index.insert(rule_id.to_string(), summaries.len());
+                summaries.push(RuleSummary {
+                    rule_id: rule_id.to_string(),
+                    name: rule.and_then(|r| r.name.clone()),
+                    level: rule
+                        .and_then(|r| r.default_configuration.as_ref())
+                        .and_then(|c| c.level.clone())
+                        .unwrap_or_else(|| result.level.clone()),
+                    count: 1,
+                    help_uri: rule.and_then(|r| r.help_uri.clone()),
+                });
+            }
+        }
+
+        summaries.sort_by(|a, b| b.count.cmp(&a.count).then(a.rule_id.cmp(&b.rule_id)));
+        summaries
+    }
+
+    /// Apply external suppressions to the results.
+    ///
+    /// Matching results are kept (GitHub expects suppressed results to be present) but
+    /// are marked with an `external` suppression.
+    pub fn apply_suppressions(&mut self, suppressions: &[SuppressionRule]) {
+        for run in self.runs.iter_mut() {
+            for result in run.results.iter_mut() {
+                if let Some(rule) = suppressions.iter().find(|s| s.matches(result)) {
+                    let suppressions = result.suppressions.get_or_insert_with(Vec::new);
+                    if !suppressions
+                        .iter()
+                        .any(|s| s.kind == SarifSuppressionKind::External)
+                    {
+                        suppressions.push(SarifSuppression {
+                            kind: SarifSuppressionKind::External,
+                            justification: rule.justification.clone(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Create a copy of the SARIF with only the results whose rule has a CodeQL
+    /// `security-severity` at or above the threshold.
+    ///
+    /// GitHub uses the score for the severity bands: critical (`9.0`+), high (`7.0`+),
+    /// medium (`4.0`+) and low. Results whose rule has no security severity are excluded,
+    /// see [`Sarif::filter_by_security_severity_or_unscored`] to keep them.
+    pub fn filter_by_security_severity(&self, min: f64) -> Sarif {
+        self.filter_security_severity(min, false)
+    }
+
+    /// Same as [`Sarif::filter_by_security_severity`] but results whose rule has no
+    /// security severity (e.g. quality queries) are kept.
+    pub fn filter_by_security_severity_or_unscored(&self, min: f64) -> Sarif {
+        self.filter_security_severity(min, true)
+    }
+
+    fn filter_security_severity(&self, min: f64, include_unscored: bool) -> Sarif {
+        let mut sarif = self.clone();
+        for run in sarif.runs.iter_mut() {
+            let results = std::mem::take(&mut run.results);
+            run.results = results
+                .into_iter()
+                .filter(|result| {
+                    match result
+                        .resolve_rule(run)
+                        .and_then(|rule| rule.security_severity())
+                    {
+                        Some(severity) => severity >= min,
+                        None => include_unscored,
+                    }
+                })
+                .collect();
+        }
+        sarif
+    }
+
+    /// Remove duplicate results from each run.
+    ///
+    /// Results are duplicates if they have the same rule, message and primary location
+    /// (file and region). The first result is kept and its `occurrenceCount` is the sum
+    /// of the duplicates (results without a count are counted once).
+    pub fn dedup_results(&mut self) {
+        for run in self.runs.iter_mut() {
+            let mut seen: HashMap<String, usize> = HashMap::new();
+            let mut results: Vec<SarifResult> = Vec::with_capacity(run.results.len());
+
+            for result in run.results.drain(..) {
+                let key = result.dedup_key();
+                match seen.get(&key) {
+                    Some(&index) => {
+                        let kept = &mut results[index];
+                        kept.occurrence_count = Some(
+                            kept.occurrence_count.unwrap_or(1)
+                                + result.occurrence_count.unwrap_or(1),
+                        );
+                    }
+                    None => {
+                        seen.insert(key, results.len());
+                        results.push(result);
+                    }
+                }
+            }
+            run.results = results;
+        }
+    }
+
+    /// Get the number of results (in all runs) which have not been suppressed
+    pub fn unsuppressed_count(&self) -> usize {
+        self.runs
+            .iter()
+            .flat_map(|r| r.results.iter())
+            .filter(|r| !r.is_suppressed())
+            .count()
+    }
+
+    /// Rewrite all the artifact location URIs (primary and related locations, code flows,
+    /// and fixes) to be relative to the source root, clearing the `uriBaseId`.
+    ///
+    /// URIs which are not in the source root are left unchanged.
+    pub fn relativize(&mut self, source_root: &Path) {
+        for run in self.runs.iter_mut() {
+            let bases = run.original_uri_base_ids.clone();
+
+            for location in run.artifact_locations_mut() {
+                let path = match uri_to_path(&resolve_uri(&bases, location)) {
+                    Some(path) if path.is_absolute() => path,
+                    _ => continue,
+                };
+                if let Ok(relative) = path.strip_prefix(source_root) {
+                    location.uri = relative
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    location.uri_base_id = None;
+                }
+            }
+        }
+    }
+
+    /// Stream the results of a SARIF file one at a time without loading the whole
+    /// document into memory (useful for very large SARIF files).
+    ///
+    /// The file is parsed on a background thread and results are yielded as they are
+    /// read. Parsing stops early if the iterator is dropped.
+    ///
+    /// ```no_run
+    /// use ghastoolkit::utils::sarif::Sarif;
+    ///
+    /// let errors = Sarif::stream_results("results.sarif")
+    ///     .filter_map(|r| r.ok())
+    ///     .filter(|r| r.level == "error")
+    ///     .count();
+    /// ```
+    pub fn stream_results(
+        path: impl Into<PathBuf>,
+    ) -> impl Iterator<Item = Result<SarifResult, GHASError>> {
+        let path = path.into();
+        let (sender, receiver) = std::sync::mpsc::sync_channel(64);
+
+        std::thread::spawn(move || {
+            let file = match std::fs::File::open(&path) {
+                Ok(file) => file,
+                Err(e) => {
+                    let _ = sender.send(Err(GHASError::from(e)));
+                    return;
+                }
+            };
+            let reader = std::io::BufReader::new(file);
+            let mut deserializer = serde_json::Deserializer::from_reader(reader);
+
+            if let Err(e) = serde::de::DeserializeSeed::deserialize(
+                stream::SarifSeed(&sender),
+                &mut deserializer,
+            ) {
+                // Receiver was dropped, nothing to report
+                if !e.to_string().starts_with(stream::STOPPED) {
+                    let _ = sender.send(Err(GHASError::from(e)));
+                }
+            }
+        });
+
+        receiver.into_iter()
+    }
+
+    /// Write SARIF to file (pretty printed), returning the number of bytes written.
+    ///
+    /// The parent directories are created if they do not exist.
+    pub fn write(&self, path: PathBuf) -> Result<u64, GHASError> {
+        self.write_with_options(path, true)
+    }
+
+    /// Write SARIF to file as compact JSON, returning the number of bytes written.
+    ///
+    /// This is a lot smaller than the pretty printed output for large SARIF files
+    /// which are only going to be compressed and uploaded.
+    pub fn write_compact(&self, path: PathBuf) -> Result<u64, GHASError> {
+        self.write_with_options(path, false)
+    }
+
+    /// Serialize the SARIF to a compact JSON string
+    pub fn to_string_compact(&self) -> Result<String, GHASError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Write SARIF to file as pretty printed or compact JSON, returning the number
+    /// of bytes written.
+    ///
+    /// The parent directories are created if they do not exist.
+    pub fn write_with_options(&self, path: PathBuf, pretty: bool) -> Result<u64, GHASError> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+
+        let file = std::fs::File::create(&path)?;
+        let mut writer = std::io::BufWriter::new(file);
+        if pretty {
+            serde_json::to_writer_pretty(&mut writer, self)?;
+        } else {
+            serde_json::to_writer(&mut writer, self)?;
+        }
+        let file = writer
+            .into_inner()
+            .map_err(|e| GHASError::from(e.into_error()))?;
+        Ok(file.metadata()?.len())
+    }
+}
+
+/// Streaming deserialization of SARIF results (`runs[].results[]`)
+mod stream {
+    use std::{fmt::Formatter, sync::mpsc::SyncSender};
+
+    use serde::de::{DeserializeSeed, Error, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+    use super::SarifResult;
+    use crate::GHASError;
+
+    pub(super) const STOPPED: &str = "SARIF stream stopped";
+
+    type Sender<'a> = &'a SyncSender<Result<SarifResult, GHASError>>;
+
+    /// Top-level SARIF object, only the `runs` are visited
+    pub(super) struct SarifSeed<'a>(pub(super) Sender<'a>);
+
+    impl<'de> DeserializeSeed<'de> for SarifSeed<'_> {
+        type Value = ();
+
+        fn deserialize<D: serde::Deserializer<'de>>(self, d: D) -> Result<(), D::Error> {
+            d.deserialize_map(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for SarifSeed<'_> {
+        type Value = ();
+
+        fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+            write!(f, "a SARIF object")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+            while let Some(key) = map.next_key::<String>()? {
+                if key == "runs" {
+                    map.next_value_seed(RunsSeed(self.0))?;
+                } else {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// List of runs
+    struct RunsSeed<'a>(Sender<'a>);
+
+    impl<'de> DeserializeSeed<'de> for RunsSeed<'_> {
+        type Value = ();
+
+        fn deserialize<D: serde::Deserializer<'de>>(self, d: D) -> Result<(), D::Error> {
+            d.deserialize_seq(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for RunsSeed<'_> {
+        type Value = ();
+
+        fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+            write!(f, "a list of SARIF runs")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+            while seq.next_element_seed(RunSeed(self.0))?.is_some() {}
+            Ok(())
+        }
+    }
+
+    /// A run, only the `results` are visited
+    struct RunSeed<'a>(Sender<'a>);
+
+    impl<'de> DeserializeSeed<'de> for RunSeed<'_> {
+        type Value = ();
+
+        fn deserialize<D: serde::Deserializer<'de>>(self, d: D) -> Result<(), D::Error> {
+            d.deserialize_map(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for RunSeed<'_> {
+        type Value = ();
+
+        fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+            write!(f, "a SARIF run")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+            while let Some(key) = map.next_key::<String>()? {
+                if key == "results" {
+                    map.next_value_seed(ResultsSeed(self.0))?;
+                } else {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// List of results, each result is sent as soon as it is parsed
+    struct ResultsSeed<'a>(Sender<'a>);
+
+    impl<'de> DeserializeSeed<'de> for ResultsSeed<'_> {
+        type Value = ();
+
+        fn deserialize<D: serde::Deserializer<'de>>(self, d: D) -> Result<(), D::Error> {
+            d.deserialize_seq(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for ResultsSeed<'_> {
+        type Value = ();
+
+        fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+            write!(f, "a list of SARIF results")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+            while let Some(result) = seq.next_element::<SarifResult>()? {
+                if self.0.send(Ok(result)).is_err() {
+                    return Err(A::Error::custom(STOPPED));
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Summary of the results for a rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleSummary {
+    /// Rule ID
+    pub rule_id: String,
+    /// Rule name (if the rule is in the tool driver)
+    pub name: Option<String>,
+    /// Level (rule default level, else the result level)
+    pub level: String,
+    /// Number of results
+    pub count: usize,
+    /// Help URI
+    pub help_uri: Option<String>,
+}
+
+/// Normalize a SARIF URI for comparison (slashes, `file://` and `./` prefixes)
+fn normalize_uri(uri: &str) -> String {
+    let uri = uri.replace('\\', "/");
+    let mut uri = uri.trim_start_matches("file://");
+    while let Some(stripped) = uri.strip_prefix("./") {
+        uri = stripped;
     }
+    uri.to_string()
 }
 
 /// Sarif Run
@@ -73,25 +614,359 @@ pub struct SarifRun {
     pub tool: SarifTool,
     /// Results
     pub results: Vec<SarifResult>,
+    /// Original URI Base IDs (e.g. `%SRCROOT%`)
+    #[serde(
+        rename = "originalUriBaseIds",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub original_uri_base_ids: Option<HashMap<String, SarifArtifactLocationBase>>,
+    /// Automation Details (the `id` is the analysis category)
+    #[serde(
+        rename = "automationDetails",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub automation_details: Option<SarifRunAutomationDetails>,
+    /// Other properties (e.g. `artifacts`, `invocations`) (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
+}
+
+/// SARIF Run Automation Details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifRunAutomationDetails {
+    /// Identifier (e.g. `/language:python/`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl SarifRun {
+    /// Find a rule of the tool's driver by its identifier
+    pub fn rule(&self, rule_id: &str) -> Option<&SarifReportingDescriptor> {
+        self.tool
+            .driver
+            .rules
+            .iter()
+            .find(|rule| rule.id == rule_id)
+    }
+
+    /// Resolve an artifact location URI against the run's `originalUriBaseIds`
+    pub fn resolve_uri(&self, location: &SarifArtifactLocation) -> String {
+        resolve_uri(&self.original_uri_base_ids, location)
+    }
+
+    /// Get all the artifact locations of the results (primary, related, code flows, and fixes)
+    fn artifact_locations_mut(&mut self) -> impl Iterator<Item = &mut SarifArtifactLocation> {
+        self.results.iter_mut().flat_map(|result| {
+            let locations = result.locations.iter_mut();
+            let related = result.related_locations.iter_mut().flatten();
+            let flows = result
+                .code_flows
+                .iter_mut()
+                .flatten()
+                .flat_map(|c| c.thread_flows.iter_mut())
+                .flat_map(|t| t.locations.iter_mut())
+                .map(|l| &mut l.location);
+            let fixes = result
+                .fixes
+                .iter_mut()
+                .flatten()
+                .flat_map(|f| f.artifact_changes.iter_mut())
+                .map(|c| &mut c.artifact_location);
+
+            locations
+                .chain(related)
+                .chain(flows)
+                .map(|l| &mut l.physical_location.artifact_location)
+                .chain(fixes)
+        })
+    }
+}
+
+/// Resolve an artifact location URI against the `originalUriBaseIds`
+fn resolve_uri(
+    bases: &Option<HashMap<String, SarifArtifactLocationBase>>,
+    location: &SarifArtifactLocation,
+) -> String {
+    // Absolute URIs don't use the base
+    if location.uri.starts_with('/') || location.uri.contains("://") {
+        return location.uri.clone();
+    }
+    let base = location
+        .uri_base_id
+        .as_ref()
+        .and_then(|id| bases.as_ref()?.get(id));
+
+    match base {
+        Some(base) => format!("{}/{}", base.uri.trim_end_matches('/'), location.uri),
+        None => location.uri.clone(),
+    }
+}
+
+/// Convert a (resolved) SARIF URI to a path, supporting `file://` URIs
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    if uri.starts_with("file:") {
+        url::Url::parse(uri).ok()?.to_file_path().ok()
+    } else {
+        Some(PathBuf::from(uri))
+    }
+}
+
+/// SARIF Artifact Location used as a base for URIs (`originalUriBaseIds`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifArtifactLocationBase {
+    /// URI
+    pub uri: String,
 }
 
 /// Sarif Result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SarifResult {
-    /// Rule ID
-    #[serde(rename = "ruleId")]
+    /// Rule ID (empty if not set)
+    #[serde(rename = "ruleId", default, skip_serializing_if = "String::is_empty")]
     pub rule_id: String,
-    /// Rule Index
-    #[serde(rename = "ruleIndex")]
+    /// Rule Index (`-1` if not set)
+    #[serde(
+        rename = "ruleIndex",
+        default = "default_index",
+        skip_serializing_if = "is_unset_index"
+    )]
     pub rule_index: i32,
-    /// Rule
+    /// Rule (empty identifier and `-1` index if not set)
+    #[serde(default, skip_serializing_if = "SarifRule::is_unset")]
     pub rule: SarifRule,
-    /// Level
+    /// Level (defaults to `warning`)
+    #[serde(default = "default_level")]
     pub level: String,
     /// Message
     pub message: SarifMessage,
     /// Locations
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub locations: Vec<SarifLocation>,
+    /// Fingerprints
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprints: Option<HashMap<String, String>>,
+    /// Partial Fingerprints (e.g. `primaryLocationLineHash`)
+    #[serde(
+        rename = "partialFingerprints",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub partial_fingerprints: Option<HashMap<String, String>>,
+    /// Suppressions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppressions: Option<Vec<SarifSuppression>>,
+    /// Related Locations
+    #[serde(
+        rename = "relatedLocations",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub related_locations: Option<Vec<SarifLocation>>,
+    /// Code Flows (e.g. data flow paths)
+    #[serde(rename = "codeFlows", default, skip_serializing_if = "Option::is_none")]
+    pub code_flows: Option<Vec<SarifCodeFlow>>,
+    /// Fixes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixes: Option<Vec<SarifFix>>,
+    /// Number of times the result was found
+    #[serde(
+        rename = "occurrenceCount",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub occurrence_count: Option<u64>,
+    /// Other properties (e.g. `properties`) (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
+}
+
+/// SARIF Code Flow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifCodeFlow {
+    /// Thread Flows
+    #[serde(rename = "threadFlows")]
+    pub thread_flows: Vec<SarifThreadFlow>,
+}
+
+/// SARIF Thread Flow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifThreadFlow {
+    /// Locations
+    pub locations: Vec<SarifThreadFlowLocation>,
+}
+
+/// SARIF Thread Flow Location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifThreadFlowLocation {
+    /// Location
+    pub location: SarifLocation,
+}
+
+/// SARIF Fix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifFix {
+    /// Description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<SarifMessage>,
+    /// Artifact Changes
+    #[serde(rename = "artifactChanges")]
+    pub artifact_changes: Vec<SarifArtifactChange>,
+}
+
+/// SARIF Artifact Change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifArtifactChange {
+    /// Artifact Location
+    #[serde(rename = "artifactLocation")]
+    pub artifact_location: SarifArtifactLocation,
+    /// Replacements
+    pub replacements: Vec<serde_json::Value>,
+}
+
+impl SarifResult {
+    /// Check if the result has been suppressed
+    pub fn is_suppressed(&self) -> bool {
+        self.suppressions
+            .as_ref()
+            .map(|s| !s.is_empty())
+            .unwrap_or(false)
+    }
+
+    /// Get the identifier of the rule (`ruleId`, else `rule.id`)
+    fn resolved_rule_id(&self) -> &str {
+        if self.rule_id.is_empty() {
+            &self.rule.id
+        } else {
+            &self.rule_id
+        }
+    }
+
+    /// Resolve the rule of the result in the run that produced it.
+    ///
+    /// The rule index (`ruleIndex`, else `rule.index`) into the driver's rules is
+    /// used first. If the result also has a rule identifier and it doesn't match the
+    /// indexed rule, the rule is looked up by its identifier instead.
+    pub fn resolve_rule<'a>(&self, run: &'a SarifRun) -> Option<&'a SarifReportingDescriptor> {
+        let rule_id = self.resolved_rule_id();
+        let index = if self.rule_index >= 0 {
+            self.rule_index
+        } else {
+            self.rule.index
+        };
+
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| run.tool.driver.rules.get(index))
+            .filter(|rule| rule_id.is_empty() || rule.id == rule_id)
+            .or_else(|| run.rule(rule_id))
+    }
+
+    /// Key used to find duplicate results (rule, message and primary location)
+    fn dedup_key(&self) -> String {
+        let location = self.locations.first().map(|l| {
+            let region = &l.physical_location.region;
+            format!(
+                "{}:{}:{}:{:?}:{:?}",
+                l.physical_location.artifact_location.uri,
+                region.start_line,
+                region.start_column,
+                region.end_line,
+                region.end_column
+            )
+        });
+        format!(
+            "{}\0{}\0{}",
+            self.resolved_rule_id(),
+            self.message.text,
+            location.unwrap_or_default()
+        )
+    }
+
+    /// Get the URI of the primary location (if any)
+    fn primary_uri(&self) -> Option<&str> {
+        self.locations
+            .first()
+            .map(|l| l.physical_location.artifact_location.uri.as_str())
+    }
+
+    /// Check if any of the (partial) fingerprints match
+    fn has_fingerprint(&self, fingerprint: &str) -> bool {
+        [&self.fingerprints, &self.partial_fingerprints]
+            .into_iter()
+            .flatten()
+            .any(|f| f.values().any(|v| v == fingerprint))
+    }
+}
+
+/// SARIF Suppression
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SarifSuppression {
+    /// Kind of suppression
+    pub kind: SarifSuppressionKind,
+    /// Justification for the suppression
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub justification: Option<String>,
+}
+
+/// SARIF Suppression Kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SarifSuppressionKind {
+    /// Suppressed in the source code (e.g. a comment)
+    InSource,
+    /// Suppressed externally (e.g. a triage file)
+    External,
+}
+
+/// Rule used to suppress SARIF results (e.g. loaded from a triage file).
+///
+/// A result is suppressed when all the set criteria match (`rule_id`, `file` glob,
+/// and `fingerprint`). A rule with no criteria set matches nothing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SuppressionRule {
+    /// Rule ID of the results to suppress
+    #[serde(default)]
+    pub rule_id: Option<String>,
+    /// Glob of the files to suppress (e.g. `tests/**`)
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Fingerprint (or partial fingerprint) of the result to suppress
+    #[serde(default)]
+    pub fingerprint: Option<String>,
+    /// Justification for the suppression
+    #[serde(default)]
+    pub justification: Option<String>,
+}
+
+impl SuppressionRule {
+    /// Check if the suppression rule matches a result
+    pub fn matches(&self, result: &SarifResult) -> bool {
+        if self.rule_id.is_none() && self.file.is_none() && self.fingerprint.is_none() {
+            return false;
+        }
+        if let Some(rule_id) = &self.rule_id {
+            if result.resolved_rule_id() != rule_id {
+                return false;
+            }
+        }
+        if let Some(file) = &self.file {
+            let matched = match (glob::Pattern::new(file), result.primary_uri()) {
+                (Ok(pattern), Some(uri)) => pattern.matches(&normalize_uri(uri)),
+                _ => false,
+            };
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(fingerprint) = &self.fingerprint {
+            if !result.has_fingerprint(fingerprint) {
+                return false;
+            }
+        }
+        true
+    }
 }
 
 impl Display for SarifResult {
@@ -104,17 +979,63 @@ impl Display for SarifResult {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SarifRule {
     /// ID
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub id: String,
-    /// Index
+    /// Index (`-1` if not set)
+    #[serde(default = "default_index", skip_serializing_if = "is_unset_index")]
     pub index: i32,
+    /// Other properties (e.g. `toolComponent`) (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
+}
+
+impl Default for SarifRule {
+    fn default() -> Self {
+        SarifRule {
+            id: String::new(),
+            index: -1,
+            additional_properties: HashMap::new(),
+        }
+    }
+}
+
+impl SarifRule {
+    /// Check if the rule reference is not set (no identifier or index)
+    fn is_unset(&self) -> bool {
+        self.id.is_empty() && self.index < 0 && self.additional_properties.is_empty()
+    }
+}
+
+/// Default (unset) index
+fn default_index() -> i32 {
+    -1
+}
+
+/// Check if an index is unset (negative)
+fn is_unset_index(index: &i32) -> bool {
+    *index < 0
+}
+
+/// Default level of a result (SARIF default)
+fn default_level() -> String {
+    String::from("warning")
 }
 
 /// SARIF Location
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SarifLocation {
+    /// ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    /// Message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<SarifMessage>,
     /// Physical Location
     #[serde(rename = "physicalLocation")]
     pub physical_location: SarifPhysicalLocation,
+    /// Other properties (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
 }
 
 /// SARIF Physical Location
@@ -125,6 +1046,9 @@ pub struct SarifPhysicalLocation {
     pub artifact_location: SarifArtifactLocation,
     /// Region
     pub region: SarifRegion,
+    /// Other properties (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
 }
 
 /// SARIF Artifact Location
@@ -133,10 +1057,19 @@ pub struct SarifArtifactLocation {
     /// URI
     pub uri: String,
     /// URI Base ID
-    #[serde(rename = "uriBaseId")]
-    pub uri_base_id: String,
-    /// ID
+    #[serde(rename = "uriBaseId", default, skip_serializing_if = "Option::is_none")]
+    pub uri_base_id: Option<String>,
+    /// Index of the artifact in the run (`-1` if not set)
+    #[serde(
+        rename = "index",
+        alias = "id",
+        default = "default_index",
+        skip_serializing_if = "is_unset_index"
+    )]
     pub id: i32,
+    /// Other properties (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
 }
 
 /// SARIF Region
@@ -145,15 +1078,23 @@ pub struct SarifRegion {
     /// Start Line
     #[serde(rename = "startLine")]
     pub start_line: i32,
-    /// Start Column
-    #[serde(rename = "startColumn")]
+    /// Start Column (defaults to `1`)
+    #[serde(rename = "startColumn", default = "default_column")]
     pub start_column: i32,
     /// End Line
-    #[serde(rename = "endLine")]
+    #[serde(rename = "endLine", default, skip_serializing_if = "Option::is_none")]
     pub end_line: Option<i32>,
     /// End Column
-    #[serde(rename = "endColumn")]
+    #[serde(rename = "endColumn", default, skip_serializing_if = "Option::is_none")]
     pub end_column: Option<i32>,
+    /// Other properties (e.g. `snippet`) (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
+}
+
+/// Default column of a region (SARIF default)
+fn default_column() -> i32 {
+    1
 }
 
 /// SARIF Tool
@@ -161,6 +1102,9 @@ pub struct SarifRegion {
 pub struct SarifTool {
     /// Driver
     pub driver: SarifToolDriver,
+    /// Other properties (e.g. `extensions`) (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
 }
 
 impl Display for SarifTool {
@@ -179,12 +1123,131 @@ pub struct SarifToolDriver {
     /// Name
     pub name: String,
     /// Organization
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub organization: Option<String>,
     /// Version
-    #[serde(rename = "semanticVersion")]
+    #[serde(
+        rename = "semanticVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     pub version: Option<String>,
     /// Notifications
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub notifications: Option<Vec<SarifToolDriverNotification>>,
+    /// Rules
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<SarifReportingDescriptor>,
+    /// Other properties (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
+}
+
+/// SARIF Reporting Descriptor (rule metadata)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifReportingDescriptor {
+    /// Identifier
+    pub id: String,
+    /// Name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Short Description
+    #[serde(
+        rename = "shortDescription",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub short_description: Option<SarifMessage>,
+    /// Full Description
+    #[serde(
+        rename = "fullDescription",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub full_description: Option<SarifMessage>,
+    /// Help URI
+    #[serde(rename = "helpUri", default, skip_serializing_if = "Option::is_none")]
+    pub help_uri: Option<String>,
+    /// Default Configuration
+    #[serde(
+        rename = "defaultConfiguration",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub default_configuration: Option<SarifReportingConfiguration>,
+    /// Properties
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<SarifPropertyBag>,
+    /// Other properties (e.g. `help`) (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
+}
+
+impl SarifReportingDescriptor {
+    /// CodeQL security severity of the rule (see [`SarifPropertyBag::security_severity`])
+    pub fn security_severity(&self) -> Option<f64> {
+        self.properties.as_ref()?.security_severity()
+    }
+}
+
+impl From<&CodeScanningAlertRule> for SarifReportingDescriptor {
+    fn from(rule: &CodeScanningAlertRule) -> Self {
+        SarifReportingDescriptor {
+            id: rule.id.clone(),
+            name: (!rule.name.is_empty()).then(|| rule.name.clone()),
+            short_description: Some(SarifMessage::from(rule.description.as_str())),
+            full_description: None,
+            help_uri: None,
+            default_configuration: Some(SarifReportingConfiguration {
+                level: Some(rule.severity.clone()),
+            }),
+            properties: Some(SarifPropertyBag {
+                tags: Some(rule.tags.clone()),
+                ..Default::default()
+            }),
+            additional_properties: HashMap::new(),
+        }
+    }
+}
+
+/// SARIF Reporting Configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifReportingConfiguration {
+    /// Level (e.g. `note`, `warning`, `error`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+}
+
+/// SARIF Property Bag
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SarifPropertyBag {
+    /// Tags
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Additional properties
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
+}
+
+impl SarifPropertyBag {
+    /// CodeQL security severity (`security-severity`, a CVSS-like score from `0.0` to `10.0`).
+    ///
+    /// The score is usually a string but numbers are also supported.
+    pub fn security_severity(&self) -> Option<f64> {
+        match self.additional_properties.get("security-severity")? {
+            serde_json::Value::String(value) => value.trim().parse().ok(),
+            serde_json::Value::Number(value) => value.as_f64(),
+            _ => None,
+        }
+    }
+
+    /// CodeQL query precision (e.g. `very-high`, `high`, `medium`, `low`)
+    pub fn precision(&self) -> Option<String> {
+        self.additional_properties
+            .get("precision")?
+            .as_str()
+            .map(|p| p.to_string())
+    }
 }
 
 /// SARIF Tool Driver Notification
@@ -207,4 +1270,555 @@ pub struct SarifToolDriverNotification {
 pub struct SarifMessage {
     /// Text
     pub text: String,
+    /// Other properties (e.g. `markdown`, `arguments`) (kept when serializing)
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
+}
+
+impl From<&str> for SarifMessage {
+    fn from(text: &str) -> Self {
+        SarifMessage {
+            text: text.to_string(),
+            additional_properties: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::path::Path;
+
+    use super::{
+        CodeScanningAlert, Sarif, SarifCodeFlow, SarifPropertyBag, SarifRunAutomationDetails,
+        SarifSuppression, SarifSuppressionKind, SarifThreadFlow, SarifThreadFlowLocation,
+        SuppressionRule,
+    };
+
+    pub(crate) const SARIF: &str = r#"{
+  "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+  "version": "2.1.0",
+  "runs": [
+    {
+      "tool": {
+        "driver": {
+          "name": "CodeQL",
+          "semanticVersion": "2.19.0",
+          "rules": [
+            {
+              "id": "py/sql-injection",
+              "name": "py/sql-injection",
+              "helpUri": "https://codeql.github.com/codeql-query-help/python/py-sql-injection/",
+              "properties": { "security-severity": "8.8" },
+              "defaultConfiguration": { "level": "error" }
+            },
+            {
+              "id": "py/clear-text-logging-sensitive-data",
+              "name": "py/clear-text-logging-sensitive-data",
+              "defaultConfiguration": { "level": "error" }
+            }
+          ]
+        }
+      },
+      "originalUriBaseIds": {
+        "%SRCROOT%": { "uri": "file:///home/runner/work/repo/" }
+      },
+      "results": [
+        {
+          "ruleId": "py/sql-injection",
+          "ruleIndex": 0,
+          "rule": { "id": "py/sql-injection", "index": 0 },
+          "level": "error",
+          "message": { "text": "SQL query built from user-controlled sources" },
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": { "uri": "app/main.py", "uriBaseId": "%SRCROOT%", "index": 0 },
+                "region": { "startLine": 10, "startColumn": 5, "endColumn": 20 }
+              }
+            }
+          ]
+        },
+        {
+          "ruleId": "py/sql-injection",
+          "ruleIndex": 0,
+          "rule": { "id": "py/sql-injection", "index": 0 },
+          "level": "error",
+          "message": { "text": "SQL query built from user-controlled sources" },
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": { "uri": "app/db.py", "uriBaseId": "%SRCROOT%", "index": 1 },
+                "region": { "startLine": 3, "startColumn": 1 }
+              }
+            }
+          ]
+        },
+        {
+          "ruleId": "py/clear-text-logging-sensitive-data",
+          "ruleIndex": 1,
+          "rule": { "id": "py/clear-text-logging-sensitive-data", "index": 1 },
+          "level": "error",
+          "message": { "text": "Sensitive data is logged" },
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": { "uri": "app/main.py", "uriBaseId": "%SRCROOT%", "index": 0 },
+                "region": { "startLine": 42, "startColumn": 1 }
+              }
+            }
+          ]
+        }
+      ]
+    }
+  ]
+}"#;
+
+    pub(crate) fn sarif() -> Sarif {
+        serde_json::from_str(SARIF).expect("Failed to parse SARIF")
+    }
+
+    #[test]
+    fn test_property_bag() {
+        let sarif = sarif();
+        let rules = &sarif.runs[0].tool.driver.rules;
+        assert_eq!(rules[0].security_severity(), Some(8.8));
+        assert_eq!(rules[1].security_severity(), None);
+
+        let properties: SarifPropertyBag = serde_json::from_str(
+            r#"{"security-severity": 7.5, "precision": "high", "tags": ["security"]}"#,
+        )
+        .unwrap();
+        assert_eq!(properties.security_severity(), Some(7.5));
+        assert_eq!(properties.precision().as_deref(), Some("high"));
+
+        let properties: SarifPropertyBag =
+            serde_json::from_str(r#"{"security-severity": "high", "precision": 1}"#).unwrap();
+        assert_eq!(properties.security_severity(), None);
+        assert_eq!(properties.precision(), None);
+    }
+
+    #[test]
+    fn test_filter_by_security_severity() {
+        let sarif = sarif();
+
+        let high = sarif.filter_by_security_severity(7.0);
+        assert_eq!(high.get_results().len(), 2);
+        assert!(high
+            .get_results()
+            .iter()
+            .all(|r| r.rule_id == "py/sql-injection"));
+        // The original is unchanged
+        assert_eq!(sarif.get_results().len(), 3);
+
+        assert_eq!(
+            sarif.filter_by_security_severity(9.0).get_results().len(),
+            0
+        );
+        assert_eq!(
+            sarif
+                .filter_by_security_severity_or_unscored(9.0)
+                .get_results()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut other = sarif();
+        let mut sarif = sarif();
+        other.runs[0].automation_details = Some(SarifRunAutomationDetails {
+            id: Some(String::from("/language:python/")),
+        });
+
+        sarif.merge(other);
+        assert_eq!(sarif.runs.len(), 2);
+        assert_eq!(sarif.get_results().len(), 6);
+
+        let json = serde_json::to_value(&sarif).unwrap();
+        assert!(json["runs"][0].get("automationDetails").is_none());
+        assert_eq!(
+            json["runs"][1]["automationDetails"]["id"],
+            "/language:python/"
+        );
+    }
+
+    #[test]
+    fn test_from_alerts() {
+        let alert = |number: i32, rule: &str, path: &str| -> CodeScanningAlert {
+            serde_json::from_value(serde_json::json!({
+                "number": number,
+                "created_at": "2024-01-01T00:00:00Z",
+                "url": "",
+                "html_url": "",
+                "state": "open",
+                "rule": {
+                    "id": rule,
+                    "severity": "error",
+                    "tags": ["security"],
+                    "description": "Description",
+                    "name": rule
+                },
+                "tool": { "name": "CodeQL", "version": "2.16.0" },
+                "most_recent_instance": {
+                    "ref": "refs/heads/main",
+                    "state": "open",
+                    "commit_sha": "",
+                    "message": { "text": "Message" },
+                    "location": {
+                        "path": path,
+                        "start_line": 1,
+                        "end_line": 2,
+                        "start_column": 3,
+                        "end_column": 4
+                    }
+                }
+            }))
+            .unwrap()
+        };
+        let alerts = vec![
+            alert(1, "py/sql-injection", "app/db.py"),
+            alert(2, "py/sql-injection", "app/main.py"),
+            alert(3, "py/path-injection", "app/main.py"),
+        ];
+
+        let sarif = Sarif::from_alerts(&alerts, "CodeQL");
+        assert_eq!(sarif.runs.len(), 1);
+        let run = &sarif.runs[0];
+        assert_eq!(run.tool.to_string(), "CodeQL v2.16.0");
+        assert_eq!(run.tool.driver.rules.len(), 2);
+        assert_eq!(run.results.len(), 3);
+        assert_eq!(run.results[2].rule_index, 1);
+        assert_eq!(run.results[1].level, "error");
+        assert_eq!(
+            run.results[1].locations[0]
+                .physical_location
+                .artifact_location
+                .uri,
+            "app/main.py"
+        );
+        assert_eq!(
+            run.results[0].resolve_rule(run).map(|r| r.id.as_str()),
+            Some("py/sql-injection")
+        );
+    }
+
+    #[test]
+    fn test_count_occurrences() {
+        let mut sarif = sarif();
+        assert_eq!(sarif.count_results(), 3);
+        assert_eq!(sarif.count_occurrences(), 3);
+
+        sarif.runs[0].results[0].occurrence_count = Some(4);
+        assert_eq!(sarif.count_results(), 3);
+        assert_eq!(sarif.count_occurrences(), 6);
+    }
+
+    #[test]
+    fn test_dedup_results() {
+        let mut sarif = sarif();
+        let duplicate = sarif.runs[0].results[0].clone();
+        sarif.runs[0].results.push(duplicate);
+        assert_eq!(sarif.runs[0].results.len(), 4);
+
+        sarif.dedup_results();
+        let results = &sarif.runs[0].results;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].occurrence_count, Some(2));
+        // Same rule and message but a different location
+        assert_eq!(results[1].occurrence_count, None);
+
+        let json = serde_json::to_string(&sarif).unwrap();
+        assert!(json.contains("\"occurrenceCount\":2"));
+    }
+
+    #[test]
+    fn test_resolve_rule() {
+        let sarif = sarif();
+        let run = &sarif.runs[0];
+
+        assert!(run.rule("py/sql-injection").is_some());
+        assert!(run.rule("py/unknown").is_none());
+
+        let mut result = run.results[2].clone();
+        let rule = result.resolve_rule(run).unwrap();
+        assert_eq!(rule.id, "py/clear-text-logging-sensitive-data");
+
+        // Index doesn't match the identifier, the identifier is used
+        result.rule_index = 0;
+        result.rule.index = 0;
+        let rule = result.resolve_rule(run).unwrap();
+        assert_eq!(rule.id, "py/clear-text-logging-sensitive-data");
+
+        // Only an index (`rule.index`)
+        result.rule_id = String::new();
+        result.rule.id = String::new();
+        result.rule_index = -1;
+        result.rule.index = 1;
+        let rule = result.resolve_rule(run).unwrap();
+        assert_eq!(rule.id, "py/clear-text-logging-sensitive-data");
+
+        // Only an identifier
+        result.rule_id = String::from("py/sql-injection");
+        result.rule.index = -1;
+        let rule = result.resolve_rule(run).unwrap();
+        assert_eq!(rule.id, "py/sql-injection");
+
+        result.rule_id = String::from("py/unknown");
+        assert!(result.resolve_rule(run).is_none());
+    }
+
+    #[test]
+    fn test_results_for_file() {
+        let sarif = sarif();
+
+        assert_eq!(sarif.results_for_file("app/main.py").len(), 2);
+        assert_eq!(sarif.results_for_file("./app/main.py").len(), 2);
+        assert_eq!(sarif.results_for_file("app\\db.py").len(), 1);
+        assert_eq!(
+            sarif
+                .results_for_file("file:///home/runner/work/repo/app/main.py")
+                .len(),
+            2
+        );
+        assert!(sarif.results_for_file("app/other.py").is_empty());
+    }
+
+    #[test]
+    fn test_rule_summary() {
+        let mut sarif = sarif();
+        // Result for a rule missing from the driver
+        let mut result = sarif.runs[0].results[0].clone();
+        result.rule_id = String::from("py/unknown");
+        result.rule_index = 10;
+        result.level = String::from("warning");
+        sarif.runs[0].results.push(result);
+
+        let summary = sarif.rule_summary();
+        assert_eq!(summary.len(), 3);
+
+        assert_eq!(summary[0].rule_id, "py/sql-injection");
+        assert_eq!(summary[0].count, 2);
+        assert_eq!(summary[0].level, "error");
+        assert_eq!(
+            summary[0].help_uri.as_deref(),
+            Some("https://codeql.github.com/codeql-query-help/python/py-sql-injection/")
+        );
+
+        assert_eq!(summary[1].rule_id, "py/clear-text-logging-sensitive-data");
+        assert_eq!(summary[1].count, 1);
+
+        assert_eq!(summary[2].rule_id, "py/unknown");
+        assert_eq!(summary[2].name, None);
+        assert_eq!(summary[2].level, "warning");
+    }
+
+    #[test]
+    fn test_apply_suppressions() {
+        let mut sarif = sarif();
+        sarif.runs[0].results[1].partial_fingerprints = Some(
+            [(
+                String::from("primaryLocationLineHash"),
+                String::from("39fa2ee980eb94b0:1"),
+            )]
+            .into(),
+        );
+        assert_eq!(sarif.unsuppressed_count(), 3);
+
+        sarif.apply_suppressions(&[
+            SuppressionRule {
+                rule_id: Some(String::from("py/clear-text-logging-sensitive-data")),
+                file: Some(String::from("app/*.py")),
+                justification: Some(String::from("Test data only")),
+                ..Default::default()
+            },
+            SuppressionRule {
+                fingerprint: Some(String::from("39fa2ee980eb94b0:1")),
+                ..Default::default()
+            },
+            // Matches nothing
+            SuppressionRule::default(),
+        ]);
+
+        let results = &sarif.runs[0].results;
+        assert_eq!(results.len(), 3);
+        assert!(!results[0].is_suppressed());
+        assert!(results[1].is_suppressed());
+        assert_eq!(
+            results[2].suppressions,
+            Some(vec![SarifSuppression {
+                kind: SarifSuppressionKind::External,
+                justification: Some(String::from("Test data only")),
+            }])
+        );
+        assert_eq!(sarif.unsuppressed_count(), 1);
+
+        // Applying again doesn't duplicate suppressions
+        sarif.apply_suppressions(&[SuppressionRule {
+            rule_id: Some(String::from("py/clear-text-logging-sensitive-data")),
+            ..Default::default()
+        }]);
+        assert_eq!(
+            sarif.runs[0].results[2]
+                .suppressions
+                .as_ref()
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_relativize() {
+        let mut sarif = sarif();
+        let db_location = sarif.runs[0].results[1].locations[0].clone();
+
+        let result = &mut sarif.runs[0].results[0];
+        // Absolute URI without a base
+        let location = &mut result.locations[0].physical_location.artifact_location;
+        location.uri = String::from("file:///home/runner/work/repo/app/main.py");
+        location.uri_base_id = None;
+        // Related / code flow locations using the base
+        result.related_locations = Some(vec![db_location.clone()]);
+        result.code_flows = Some(vec![SarifCodeFlow {
+            thread_flows: vec![SarifThreadFlow {
+                locations: vec![SarifThreadFlowLocation {
+                    location: db_location,
+                }],
+            }],
+        }]);
+        // Outside of the source root
+        sarif.runs[0].results[2].locations[0]
+            .physical_location
+            .artifact_location
+            .uri = String::from("/usr/lib/python3/site.py");
+
+        sarif.relativize(Path::new("/home/runner/work/repo"));
+
+        let result = &sarif.runs[0].results[0];
+        let location = &result.locations[0].physical_location.artifact_location;
+        assert_eq!(location.uri, "app/main.py");
+        assert_eq!(location.uri_base_id, None);
+
+        let related = &result.related_locations.as_ref().unwrap()[0];
+        assert_eq!(related.physical_location.artifact_location.uri, "app/db.py");
+        assert_eq!(
+            related.physical_location.artifact_location.uri_base_id,
+            None
+        );
+
+        let flow = &result.code_flows.as_ref().unwrap()[0].thread_flows[0].locations[0];
+        assert_eq!(
+            flow.location.physical_location.artifact_location.uri,
+            "app/db.py"
+        );
+
+        let location = &sarif.runs[0].results[2].locations[0]
+            .physical_location
+            .artifact_location;
+        assert_eq!(location.uri, "/usr/lib/python3/site.py");
+        assert_eq!(location.uri_base_id.as_deref(), Some("%SRCROOT%"));
+    }
+
+    #[test]
+    fn test_stream_results() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("results.sarif");
+        std::fs::write(&path, SARIF).unwrap();
+
+        let results: Vec<_> = Sarif::stream_results(path.clone())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].rule_id, "py/sql-injection");
+        assert_eq!(results[2].rule_id, "py/clear-text-logging-sensitive-data");
+
+        // Stopping early
+        assert_eq!(Sarif::stream_results(path.clone()).take(1).count(), 1);
+
+        // Invalid SARIF / missing file
+        std::fs::write(&path, r#"{"runs": [{"results": [{"ruleId": 1}]}]}"#).unwrap();
+        assert!(Sarif::stream_results(path.clone()).any(|r| r.is_err()));
+        std::fs::remove_file(&path).unwrap();
+        assert!(Sarif::stream_results(path).any(|r| r.is_err()));
+    }
+
+    #[test]
+    fn test_round_trip_third_party() {
+        // No `rule` / `ruleIndex` and properties the model doesn't cover
+        let json = serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "Semgrep",
+                        "rules": [{
+                            "id": "python.sqli",
+                            "help": { "text": "Use parameters", "markdown": "Use **parameters**" }
+                        }]
+                    },
+                    "extensions": [{ "name": "custom-pack", "version": "1.0.0" }]
+                },
+                "artifacts": [{ "location": { "uri": "src/app.py" } }],
+                "invocations": [{ "executionSuccessful": true }],
+                "versionControlProvenance": [{ "repositoryUri": "https://github.com/geekmasher/ghastoolkit-rs" }],
+                "results": [{
+                    "ruleId": "python.sqli",
+                    "level": "error",
+                    "message": { "text": "SQL injection" },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": "src/app.py", "index": 0 },
+                            "region": { "startLine": 10, "startColumn": 1, "snippet": { "text": "cursor.execute(q)" } }
+                        }
+                    }],
+                    "properties": { "confidence": "HIGH" }
+                }, {
+                    "rule": { "id": "python.sqli", "toolComponent": { "name": "custom-pack" } },
+                    "level": "warning",
+                    "message": { "text": "Another one" }
+                }]
+            }]
+        });
+
+        let sarif: Sarif = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(sarif.runs[0].results[0].rule_id, "python.sqli");
+        assert_eq!(sarif.runs[0].results[0].rule_index, -1);
+        assert_eq!(sarif.runs[0].results[1].rule.id, "python.sqli");
+        assert_eq!(serde_json::to_value(&sarif).unwrap(), json);
+
+        // Results with only `rule.id` are summarised with their rule
+        let summary = sarif.rule_summary();
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].rule_id, "python.sqli");
+        assert_eq!(summary[0].count, 2);
+
+        // Rule suppressions match results with only `rule.id`
+        let suppression = SuppressionRule {
+            rule_id: Some(String::from("python.sqli")),
+            ..Default::default()
+        };
+        assert!(suppression.matches(&sarif.runs[0].results[0]));
+        assert!(suppression.matches(&sarif.runs[0].results[1]));
+    }
+
+    #[test]
+    fn test_write() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        let path = root.join("results").join("python.sarif");
+
+        let sarif = sarif();
+        let pretty = sarif.write(path.clone()).unwrap();
+        assert_eq!(pretty, std::fs::metadata(&path).unwrap().len());
+
+        let compact = sarif.write_compact(path.clone()).unwrap();
+        assert!(compact < pretty);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            sarif.to_string_compact().unwrap()
+        );
+        assert_eq!(Sarif::try_from(path).unwrap().get_results().len(), 3);
+    }
 }
@@ -0,0 +1,143 @@
+//! Helpers for the optional `tracing` spans.
+//!
+//! Without the `tracing` feature these helpers do nothing, so they can be used
+//! without `cfg` attributes in the instrumented functions.
+use std::fmt::Display;
+
+/// Records the elapsed time (`duration_ms`) on the current span when dropped
+pub(crate) struct SpanTimer {
+    #[cfg(feature = "tracing")]
+    start: std::time::Instant,
+}
+
+impl SpanTimer {
+    /// Start timing the current span
+    pub(crate) fn start() -> Self {
+        SpanTimer {
+            #[cfg(feature = "tracing")]
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for SpanTimer {
+    fn drop(&mut self) {
+        tracing::Span::current().record("duration_ms", self.start.elapsed().as_millis() as u64);
+    }
+}
+
+/// Record a field (declared as `tracing::field::Empty`) on the current span
+#[cfg(feature = "tracing")]
+pub(crate) fn record(field: &'static str, value: impl Display) {
+    tracing::Span::current().record(field, tracing::field::display(value));
+}
+
+/// Record a field on the current span (no-op without the `tracing` feature)
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn record(_field: &'static str, _value: impl Display) {}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Event, Metadata, Subscriber,
+    };
+    use tracing_core::span::Current;
+
+    use crate::{CodeQLDatabases, GitHub, Repository};
+
+    type Spans = Arc<Mutex<HashMap<String, HashMap<String, String>>>>;
+
+    /// Minimal subscriber recording the fields of the spans (by span name)
+    #[derive(Default)]
+    struct Recorder {
+        spans: Spans,
+        ids: Mutex<HashMap<u64, &'static Metadata<'static>>>,
+        stack: Mutex<Vec<Id>>,
+        next: AtomicU64,
+    }
+
+    struct Fields<'a>(&'a mut HashMap<String, String>);
+
+    impl Visit for Fields<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    impl Recorder {
+        fn name(&self, id: &Id) -> String {
+            self.ids.lock().unwrap()[&id.into_u64()].name().to_string()
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let id = self.next.fetch_add(1, Ordering::SeqCst) + 1;
+            self.ids.lock().unwrap().insert(id, span.metadata());
+            let mut fields = HashMap::new();
+            span.record(&mut Fields(&mut fields));
+            self.spans
+                .lock()
+                .unwrap()
+                .insert(span.metadata().name().to_string(), fields);
+            Id::from_u64(id)
+        }
+        fn record(&self, id: &Id, values: &Record<'_>) {
+            let name = self.name(id);
+            let mut spans = self.spans.lock().unwrap();
+            values.record(&mut Fields(spans.entry(name).or_default()));
+        }
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event<'_>) {}
+        fn enter(&self, id: &Id) {
+            self.stack.lock().unwrap().push(id.clone());
+        }
+        fn exit(&self, _: &Id) {
+            self.stack.lock().unwrap().pop();
+        }
+        fn current_span(&self) -> Current {
+            match self.stack.lock().unwrap().last() {
+                Some(id) => Current::new(id.clone(), self.ids.lock().unwrap()[&id.into_u64()]),
+                None => Current::none(),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_span() {
+        let recorder = Recorder::default();
+        let spans = Arc::clone(&recorder.spans);
+        let _guard = tracing::subscriber::set_default(recorder);
+
+        let github = GitHub::default();
+        let repository = Repository::new("geekmasher", "ghastoolkit-rs");
+        // Unknown language, fails before any request is made
+        let result = CodeQLDatabases::download_database(&github, &repository, None::<String>).await;
+        assert!(result.is_err());
+
+        let spans = spans.lock().unwrap();
+        let span = &spans["codeql.database.download"];
+        assert_eq!(span["repo"], "geekmasher/ghastoolkit-rs");
+        assert!(span.contains_key("language"));
+        assert!(span.contains_key("duration_ms"));
+
+        // Finding a database doesn't record anything on the caller's span
+        let databases = CodeQLDatabases::new();
+        assert!(databases.find_by_language("python").is_none());
+    }
+}